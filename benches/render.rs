@@ -47,25 +47,12 @@ fn mock_session() -> SessionData {
         exceeds_200k_tokens: Some(false),
         vim: None,
         agent: None,
-    }
-}
-
-fn widget(widget_type: &str) -> LineWidgetConfig {
-    LineWidgetConfig {
-        widget_type: widget_type.into(),
-        id: String::new(),
-        color: None,
-        background_color: None,
-        bold: None,
-        raw_value: false,
-        padding: None,
-        merge_next: false,
-        metadata: HashMap::new(),
+        limits: None,
     }
 }
 
 fn widget_colored(widget_type: &str, fg: Option<&str>, bg: Option<&str>) -> LineWidgetConfig {
-    let mut w = widget(widget_type);
+    let mut w = LineWidgetConfig::new(widget_type);
     w.color = fg.map(String::from);
     w.background_color = bg.map(String::from);
     w
@@ -97,10 +84,12 @@ fn bench_powerline_render(c: &mut Criterion) {
         powerline: PowerlineConfig {
             enabled: true,
             separator: "\u{E0B0}".into(),
+            separator_reverse: "\u{E0B2}".into(),
             separator_invert_background: false,
             start_cap: None,
             end_cap: Some("\u{E0B0}".into()),
             auto_align: false,
+            auto_contrast: false,
         },
         ..Config::default()
     };
@@ -160,6 +149,7 @@ fn bench_single_widget(c: &mut Criterion) {
         padding: None,
         merge_next: false,
         metadata: HashMap::new(),
+        settings: Default::default(),
     };
 
     c.bench_function("single_widget_render", |b| {
@@ -172,14 +162,18 @@ fn bench_multiline_full(c: &mut Criterion) {
     let config = Config {
         lines: vec![
             vec![
-                widget("model"),
-                widget("context-percentage"),
-                widget("tokens-input"),
-                widget("tokens-output"),
-                widget("session-cost"),
-                widget("session-duration"),
+                LineWidgetConfig::new("model"),
+                LineWidgetConfig::new("context-percentage"),
+                LineWidgetConfig::new("tokens-input"),
+                LineWidgetConfig::new("tokens-output"),
+                LineWidgetConfig::new("session-cost"),
+                LineWidgetConfig::new("session-duration"),
+            ],
+            vec![
+                LineWidgetConfig::new("cwd"),
+                LineWidgetConfig::new("lines-changed"),
+                LineWidgetConfig::new("version"),
             ],
-            vec![widget("cwd"), widget("lines-changed"), widget("version")],
         ],
         ..Config::default()
     };
@@ -1,17 +1,29 @@
-use std::collections::HashMap;
-
 use chrono::Datelike;
 use clap::Subcommand;
 
-use claude_status::config::{Config, LineWidgetConfig, PowerlineConfig};
-use claude_status::themes::Theme;
+use claude_status::config::{
+    Config, CostConfig, DEFAULT_UPDATE_URL, LineWidgetConfig, PowerlineConfig, StatsConfig,
+};
+use claude_status::render::Renderer;
+use claude_status::themes::{ROLES, Theme};
+use claude_status::widgets::{Limits, format};
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Launch interactive TUI configuration
-    Config,
+    Config {
+        /// Print the fully-resolved config as TOML instead of opening the TUI
+        #[arg(long, visible_alias = "dry-run")]
+        show: bool,
+    },
     /// Generate default config file
-    Init,
+    Init {
+        /// Config file format to write: toml or json (as config.json)
+        #[arg(long, default_value = "toml", value_parser = ["toml", "json"])]
+        format: String,
+    },
+    /// Guided first-run setup: preset, theme, fonts, then the settings.json snippet
+    Setup,
     /// Check environment compatibility
     Doctor,
     /// Manage themes
@@ -26,16 +38,36 @@ pub enum Commands {
     },
     /// Dump the expected JSON input schema
     DumpSchema,
+    /// Dump a JSON Schema for the config file (for editor autocomplete)
+    DumpConfigSchema,
     /// Manage Pro license
     License {
         #[command(subcommand)]
         action: LicenseAction,
     },
+    /// Show this build's version, commit and build date
+    Version {
+        /// Print machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check whether a newer release is available
+    CheckUpdate,
     /// Show historical cost statistics (Pro)
     Stats {
         /// Time period: daily, weekly, monthly
         #[arg(long, default_value = "weekly")]
         period: String,
+        /// Rank and count sessions from this long ago instead of the period's
+        /// start: 12h, 3d, 2w, ...
+        #[arg(long, value_name = "DURATION", value_parser = duration_arg)]
+        since: Option<String>,
+        /// Weekly spend limit in USD (default: the plan's limit, [stats] weekly_limit, or 200)
+        #[arg(long)]
+        weekly_limit: Option<f64>,
+        /// Session times: short ("Oct 16, 14:05"), iso (ISO-8601) or relative ("2h ago")
+        #[arg(long, default_value = "short", value_parser = ["short", "iso", "relative"])]
+        time_format: String,
     },
 }
 
@@ -45,6 +77,8 @@ pub enum ThemeAction {
     List,
     /// Set active theme
     Set { name: String },
+    /// Print a theme's role colors as swatches
+    Preview { name: String },
 }
 
 #[derive(Subcommand)]
@@ -56,31 +90,89 @@ pub enum LicenseAction {
     },
     /// Deactivate (remove) the current license
     Deactivate,
+    /// Release the license from this machine so it can be activated on another
+    Transfer,
+    /// Start a one-time 7-day Pro trial
+    Trial,
     /// Show current license status
-    Status,
+    Status {
+        /// Print machine-readable JSON (key masked)
+        #[arg(long)]
+        json: bool,
+    },
 }
 
-pub fn handle_command(cmd: Commands) {
+pub fn handle_command(
+    cmd: Commands,
+    config_override: Option<&str>,
+    profile: Option<&str>,
+    no_history: bool,
+    color_level: &str,
+) {
     match cmd {
-        Commands::Config => {
+        Commands::Config { show: true } => cmd_config_show(config_override, profile),
+        Commands::Config { show: false } => {
             if let Err(e) = claude_status::tui::run_tui() {
                 eprintln!("TUI error: {e}");
             }
         }
-        Commands::Init => cmd_init(),
+        Commands::Init { format } => cmd_init(&format),
+        Commands::Setup => cmd_setup(config_override),
         Commands::Doctor => cmd_doctor(),
         Commands::Theme { action } => match action {
             ThemeAction::List => cmd_theme_list(),
             ThemeAction::Set { name } => cmd_theme_set(&name),
+            ThemeAction::Preview { name } => cmd_theme_preview(&name, color_level),
         },
         Commands::Preset { name } => cmd_preset(&name),
         Commands::DumpSchema => cmd_dump_schema(),
+        Commands::DumpConfigSchema => cmd_dump_config_schema(),
+        Commands::Version { json } => cmd_version(json),
+        Commands::CheckUpdate => cmd_check_update(&Config::load(config_override)),
         Commands::License { action } => match action {
             LicenseAction::Activate { key } => cmd_license_activate(&key),
             LicenseAction::Deactivate => cmd_license_deactivate(),
-            LicenseAction::Status => cmd_license_status(),
+            LicenseAction::Transfer => cmd_license_transfer(),
+            LicenseAction::Trial => cmd_license_trial(),
+            LicenseAction::Status { json } => cmd_license_status(json),
         },
-        Commands::Stats { period } => cmd_stats(&period),
+        Commands::Stats {
+            period,
+            since,
+            weekly_limit,
+            time_format,
+        } => {
+            let config = Config::load(config_override);
+            let mut stats = config.stats.clone();
+            if no_history {
+                stats.history = false;
+            }
+            // Limits reported by the last session payload win over config.
+            if stats.history
+                && let Some(limits) = Limits::load_saved()
+            {
+                stats.weekly_limit = limits.weekly().unwrap_or(stats.weekly_limit);
+                stats.monthly_budget = limits.monthly().or(stats.monthly_budget);
+            }
+            if let Some(limit) = weekly_limit {
+                stats.weekly_limit = limit;
+            }
+            cmd_stats(
+                &period,
+                since.as_deref(),
+                &stats,
+                &config.cost,
+                &time_format,
+            )
+        }
+    }
+}
+
+/// Validate a duration argument ("3d", "12h") for clap, keeping the text.
+fn duration_arg(s: &str) -> Result<String, String> {
+    match format::parse_duration(s) {
+        Some(_) => Ok(s.to_string()),
+        None => Err("expected a duration like 12h, 3d or 2w".into()),
     }
 }
 
@@ -91,8 +183,10 @@ fn config_path() -> std::path::PathBuf {
         .join("config.toml")
 }
 
-fn cmd_init() {
-    let path = config_path();
+fn cmd_init(format: &str) {
+    let config = Config::default();
+    let path = config_path().with_extension(format);
+    let contents = config.serialize_for(&path);
     if let Some(parent) = path.parent()
         && let Err(e) = std::fs::create_dir_all(parent)
     {
@@ -100,17 +194,14 @@ fn cmd_init() {
         return;
     }
 
-    let config = Config::default();
-    let toml_str = config.to_toml();
-
-    if let Err(e) = std::fs::write(&path, &toml_str) {
+    if let Err(e) = std::fs::write(&path, &contents) {
         eprintln!("Error writing config file: {e}");
         return;
     }
 
     println!("Config written to: {}", path.display());
     println!();
-    println!("{toml_str}");
+    println!("{contents}");
     println!("---");
     println!("To use with Claude Code, add to your settings.json:");
     println!();
@@ -121,6 +212,139 @@ fn cmd_init() {
     println!(r#"  }}"#);
 }
 
+/// Ask `question` on stdout and read one answer from stdin. An empty
+/// answer or end of input gives `default`.
+fn prompt(question: &str, default: &str) -> String {
+    use std::io::{BufRead, Write};
+
+    print!("{question} [{default}]: ");
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    let read = std::io::stdin().lock().read_line(&mut answer).unwrap_or(0);
+    if read == 0 {
+        // No more input: answer everything else with its default.
+        println!();
+    }
+    match answer.trim() {
+        "" => default.to_string(),
+        a => a.to_string(),
+    }
+}
+
+/// Ask until the answer is one of `options` (or its 1-based number).
+fn choose(question: &str, options: &[&str], default: &str) -> String {
+    for (i, option) in options.iter().enumerate() {
+        println!("  {}) {option}", i + 1);
+    }
+    loop {
+        let answer = prompt(question, default);
+        if let Ok(n) = answer.parse::<usize>()
+            && (1..=options.len()).contains(&n)
+        {
+            return options[n - 1].to_string();
+        }
+        if options.contains(&answer.as_str()) {
+            return answer;
+        }
+        println!("Please pick one of: {}", options.join(", "));
+    }
+}
+
+fn confirm(question: &str, default: bool) -> bool {
+    let hint = if default { "Y/n" } else { "y/N" };
+    let answer = prompt(question, hint);
+    if answer == hint {
+        return default;
+    }
+    matches!(answer.to_lowercase().as_str(), "y" | "yes")
+}
+
+fn cmd_setup(config_override: Option<&str>) {
+    let path = config_override
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(config_path);
+    println!("claude-status setup");
+    println!();
+
+    if path.exists() {
+        println!("A config already exists at {}", path.display());
+        if confirm("Back it up before replacing it?", true) {
+            let mut backup = path.clone().into_os_string();
+            backup.push(".bak");
+            match std::fs::copy(&path, &backup) {
+                Ok(_) => println!("Backed up to {}", backup.to_string_lossy()),
+                Err(e) => {
+                    eprintln!("Error backing up config: {e}");
+                    return;
+                }
+            }
+        }
+        println!();
+    }
+
+    println!("Layout presets:");
+    let preset_name = choose("Preset", &PRESETS, "full");
+    let mut config = preset(&preset_name).unwrap_or_default();
+    println!();
+
+    println!("Themes:");
+    config.theme = choose("Theme", &Theme::list(), &config.theme);
+    println!();
+
+    let nerd = confirm("Does your terminal use a Nerd Font?", false);
+    if nerd {
+        config.icon_set = "nerd".into();
+    } else if config.powerline.enabled {
+        println!("Note: powerline arrows need a Nerd Font or Powerline-patched font.");
+    }
+    println!();
+
+    if let Some(parent) = path.parent()
+        && let Err(e) = std::fs::create_dir_all(parent)
+    {
+        eprintln!("Error creating config directory: {e}");
+        return;
+    }
+    if let Err(e) = std::fs::write(&path, config.serialize_for(&path)) {
+        eprintln!("Error writing config file: {e}");
+        return;
+    }
+    println!("Config written to {}", path.display());
+    println!();
+
+    let command = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.file_name().map(|f| f.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "ai-statusline".into());
+    println!("Add this to ~/.claude/settings.json, then restart Claude Code:");
+    println!();
+    println!("{}", settings_snippet(&command));
+}
+
+/// The `statusLine` block for Claude Code's settings.json.
+fn settings_snippet(command: &str) -> String {
+    let command = serde_json::to_string(command).unwrap_or_default();
+    format!(
+        "{{\n  \"statusLine\": {{\n    \"type\": \"command\",\n    \"command\": {command}\n  }}\n}}"
+    )
+}
+
+fn cmd_config_show(config_override: Option<&str>, profile: Option<&str>) {
+    match Config::resolve_path(config_override) {
+        Some(p) if p.exists() => println!("# Resolved from {}", p.display()),
+        _ => println!("# No config file found; showing defaults"),
+    }
+    let mut config = Config::load(config_override);
+    if let Some(name) = Config::active_profile(profile) {
+        if config.apply_profile(&name) {
+            println!("# Profile: {name}");
+        } else {
+            println!("# Profile {name:?} not found; showing top-level config");
+        }
+    }
+    print!("{}", config.to_toml());
+}
+
 fn cmd_doctor() {
     println!("claude-status doctor");
     println!("=================");
@@ -156,9 +380,11 @@ fn cmd_doctor() {
     }
 
     // Nerd Font detection
-    let nerd_hint = std::env::var("NERD_FONT").is_ok() || std::env::var("NERDFONTS").is_ok();
-    if nerd_hint {
-        print_check(true, "Nerd Fonts: detected via env var");
+    if claude_status::icons::nerd_font_hint() {
+        print_check(
+            true,
+            "Nerd Fonts: detected via env var (icon_set = \"nerd\" or \"auto\" uses them)",
+        );
     } else {
         println!(
             "  ? Nerd Fonts: unknown (set NERD_FONT=1 to confirm, or check your terminal font)"
@@ -166,12 +392,12 @@ fn cmd_doctor() {
     }
 
     // Config file
-    let cfg_path = config_path();
+    let cfg_path = Config::default_path().unwrap_or_else(config_path);
     let cfg_exists = cfg_path.exists();
     if cfg_exists {
         match std::fs::read_to_string(&cfg_path) {
             Ok(contents) => {
-                let valid = toml::from_str::<Config>(&contents).is_ok();
+                let valid = Config::parse(&cfg_path, &contents).is_ok();
                 print_check(
                     valid,
                     &format!("Config: {} (valid: {})", cfg_path.display(), valid),
@@ -219,6 +445,36 @@ fn cmd_theme_list() {
     }
 }
 
+fn cmd_theme_preview(name: &str, color_level: &str) {
+    let available = Theme::list();
+    if !available.contains(&name) {
+        eprintln!(
+            "Unknown theme '{name}'. Available: {}",
+            available.join(", ")
+        );
+        return;
+    }
+
+    let theme = Theme::get(name);
+    let renderer = Renderer::detect(color_level);
+    let width = ROLES
+        .iter()
+        .map(|(_, label)| label.len())
+        .max()
+        .unwrap_or(0);
+    println!("Theme: {name}");
+    for (role, label) in ROLES {
+        match theme.color(role) {
+            Some(color) => println!(
+                "  {label:<width$}  {}████{}  {color}",
+                renderer.fg(&renderer.color(color)),
+                renderer.reset()
+            ),
+            None => println!("  {label:<width$}  ----  (none)"),
+        }
+    }
+}
+
 fn cmd_theme_set(name: &str) {
     let available = Theme::list();
     if !available.contains(&name) {
@@ -229,10 +485,16 @@ fn cmd_theme_set(name: &str) {
         return;
     }
 
-    let path = config_path();
+    let path = Config::default_path().unwrap_or_else(config_path);
     let mut config = if path.exists() {
-        let contents = std::fs::read_to_string(&path).unwrap_or_default();
-        toml::from_str::<Config>(&contents).unwrap_or_default()
+        match Config::read(&path) {
+            Ok(config) => config,
+            // Don't overwrite a config we couldn't read with the defaults.
+            Err(e) => {
+                eprintln!("Error reading config: {e}");
+                return;
+            }
+        }
     } else {
         Config::default()
     };
@@ -242,22 +504,28 @@ fn cmd_theme_set(name: &str) {
     if let Some(parent) = path.parent() {
         let _ = std::fs::create_dir_all(parent);
     }
-    match std::fs::write(&path, config.to_toml()) {
+    match std::fs::write(&path, config.serialize_for(&path)) {
         Ok(_) => println!("Theme set to '{name}' in {}", path.display()),
         Err(e) => eprintln!("Error saving config: {e}"),
     }
 }
 
+const PRESETS: [&str; 4] = ["minimal", "full", "powerline", "compact"];
+
+fn preset(name: &str) -> Option<Config> {
+    match name {
+        "minimal" => Some(preset_minimal()),
+        "full" => Some(preset_full()),
+        "powerline" => Some(preset_powerline()),
+        "compact" => Some(preset_compact()),
+        _ => None,
+    }
+}
+
 fn cmd_preset(name: &str) {
-    let config = match name {
-        "minimal" => preset_minimal(),
-        "full" => preset_full(),
-        "powerline" => preset_powerline(),
-        "compact" => preset_compact(),
-        _ => {
-            eprintln!("Unknown preset '{name}'. Available: minimal, full, powerline, compact");
-            return;
-        }
+    let Some(config) = preset(name) else {
+        eprintln!("Unknown preset '{name}'. Available: {}", PRESETS.join(", "));
+        return;
     };
 
     let path = config_path();
@@ -274,28 +542,14 @@ fn cmd_preset(name: &str) {
     }
 }
 
-fn widget(widget_type: &str) -> LineWidgetConfig {
-    LineWidgetConfig {
-        widget_type: widget_type.into(),
-        id: String::new(),
-        color: None,
-        background_color: None,
-        bold: None,
-        raw_value: false,
-        padding: None,
-        merge_next: false,
-        metadata: HashMap::new(),
-    }
-}
-
 fn widget_raw(widget_type: &str) -> LineWidgetConfig {
-    let mut w = widget(widget_type);
+    let mut w = LineWidgetConfig::new(widget_type);
     w.raw_value = true;
     w
 }
 
 fn widget_colored(widget_type: &str, fg: Option<&str>, bg: Option<&str>) -> LineWidgetConfig {
-    let mut w = widget(widget_type);
+    let mut w = LineWidgetConfig::new(widget_type);
     w.color = fg.map(String::from);
     w.background_color = bg.map(String::from);
     w
@@ -303,7 +557,10 @@ fn widget_colored(widget_type: &str, fg: Option<&str>, bg: Option<&str>) -> Line
 
 fn preset_minimal() -> Config {
     Config {
-        lines: vec![vec![widget("model"), widget("context-percentage")]],
+        lines: vec![vec![
+            LineWidgetConfig::new("model"),
+            LineWidgetConfig::new("context-percentage"),
+        ]],
         ..Config::default()
     }
 }
@@ -312,19 +569,19 @@ fn preset_full() -> Config {
     Config {
         lines: vec![
             vec![
-                widget("model"),
-                widget("context-percentage"),
-                widget("tokens-input"),
-                widget("tokens-output"),
-                widget("session-cost"),
-                widget("session-duration"),
+                LineWidgetConfig::new("model"),
+                LineWidgetConfig::new("context-percentage"),
+                LineWidgetConfig::new("tokens-input"),
+                LineWidgetConfig::new("tokens-output"),
+                LineWidgetConfig::new("session-cost"),
+                LineWidgetConfig::new("session-duration"),
             ],
             vec![
-                widget("cwd"),
-                widget("git-branch"),
-                widget("git-status"),
-                widget("lines-changed"),
-                widget("version"),
+                LineWidgetConfig::new("cwd"),
+                LineWidgetConfig::new("git-branch"),
+                LineWidgetConfig::new("git-status"),
+                LineWidgetConfig::new("lines-changed"),
+                LineWidgetConfig::new("version"),
             ],
         ],
         ..Config::default()
@@ -353,10 +610,12 @@ fn preset_powerline() -> Config {
         powerline: PowerlineConfig {
             enabled: true,
             separator: "\u{E0B0}".into(),
+            separator_reverse: "\u{E0B2}".into(),
             separator_invert_background: false,
             start_cap: None,
             end_cap: Some("\u{E0B0}".into()),
             auto_align: true,
+            auto_contrast: false,
         },
         ..Config::default()
     }
@@ -407,8 +666,58 @@ fn cmd_license_deactivate() {
     }
 }
 
-fn cmd_license_status() {
+fn cmd_license_transfer() {
+    let validator = claude_status::license::LicenseValidator::new();
+    match validator.transfer() {
+        Ok(()) => {
+            println!("License released from this machine.");
+            println!(
+                "Activate it on your new machine with `claude-status license activate <key>`."
+            );
+        }
+        Err(e) => {
+            eprintln!("Error transferring license: {e}");
+        }
+    }
+}
+
+fn cmd_license_trial() {
+    let validator = claude_status::license::LicenseValidator::new();
+    match validator.start_trial() {
+        Ok(info) => {
+            println!("Pro trial started. Pro features are enabled for 7 days.");
+            if let Some(expires) = info.expires {
+                println!("Trial ends: {}", expires.format("%Y-%m-%d %H:%M UTC"));
+            }
+        }
+        Err(e) => {
+            eprintln!("Error starting trial: {e}");
+        }
+    }
+}
+
+fn cmd_license_status(json: bool) {
+    if json {
+        cmd_license_status_json();
+        return;
+    }
+
     match claude_status::license::check_pro() {
+        Some(info) if info.is_trial() => {
+            let expires = info.expires.unwrap_or_default();
+            println!("claude-status Pro Trial");
+            println!("=======================");
+            println!();
+            println!(
+                "  Status:   Trial ({} days left)",
+                claude_status::license::days_left(expires)
+            );
+            println!("  Features: {}", info.features.join(", "));
+            println!("  Expires:  {}", expires.format("%Y-%m-%d"));
+            println!();
+            println!("  Activate: claude-status license activate <key>");
+            println!("  Purchase: https://claude-status.dev/pro");
+        }
         Some(info) => {
             println!("claude-status Pro");
             println!("=================");
@@ -416,9 +725,8 @@ fn cmd_license_status() {
             println!("  Status:   {:?}", info.status);
             println!("  Tier:     {:?}", info.tier);
             println!(
-                "  Key:      {}...{}",
-                &info.key[..11],
-                &info.key[info.key.len() - 4..]
+                "  Key:      {}",
+                claude_status::license::mask_key(&info.key)
             );
             println!("  Features: {}", info.features.join(", "));
             if let Some(expires) = info.expires {
@@ -440,11 +748,7 @@ fn cmd_license_status() {
                 println!("==================================");
                 println!();
                 println!("  Status:  {:?}", info.status);
-                println!(
-                    "  Key:     {}...{}",
-                    &key[..11.min(key.len())],
-                    &key[key.len().saturating_sub(4)..]
-                );
+                println!("  Key:     {}", claude_status::license::mask_key(&key));
                 println!();
                 println!("Your license key could not be validated.");
                 println!("Run `claude-status license activate <key>` with a valid key.");
@@ -459,12 +763,64 @@ fn cmd_license_status() {
                 println!();
                 println!("  Activate: claude-status license activate <key>");
                 println!("  Purchase: https://claude-status.dev/pro");
+                if claude_status::license::LicenseValidator::new().trial_used() {
+                    println!();
+                    println!("Your Pro trial has ended.");
+                } else {
+                    println!("  Try it:   claude-status license trial (7 days, once per machine)");
+                }
             }
         }
     }
 }
 
-fn cmd_stats(period: &str) {
+fn days_in_month(date: chrono::NaiveDate) -> u32 {
+    let (year, month) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    let next_month = chrono::NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+    (next_month - date.with_day(1).unwrap()).num_days() as u32
+}
+
+fn cmd_license_status_json() {
+    let storage = claude_status::license::LicenseStorage::new();
+    let validator = claude_status::license::LicenseValidator::new();
+    let value = match (storage.load_key(), validator.trial()) {
+        (Some(key), _) => serde_json::to_value(validator.validate(&key).masked()).unwrap(),
+        (None, Some(trial)) => serde_json::to_value(trial).unwrap(),
+        (None, None) => serde_json::json!({
+            "tier": "Free",
+            "status": null,
+            "key": null,
+            "expires": null,
+            "features": [],
+            "trial": false,
+        }),
+    };
+    println!("{}", serde_json::to_string_pretty(&value).unwrap());
+}
+
+/// A session start time for `stats --time-format`.
+fn format_session_time(start: i64, now: i64, time_format: &str) -> String {
+    let Some(dt) = chrono::DateTime::from_timestamp(start, 0) else {
+        return "unknown".into();
+    };
+    match time_format {
+        "iso" => dt.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        "relative" => format::ago(now.saturating_sub(start).max(0) as u64),
+        _ => dt.format("%b %d, %H:%M").to_string(),
+    }
+}
+
+fn cmd_stats(
+    period: &str,
+    since: Option<&str>,
+    stats: &StatsConfig,
+    cost: &CostConfig,
+    time_format: &str,
+) {
     if !claude_status::license::is_pro() {
         println!("claude-status Stats (Pro feature)");
         println!("=================================");
@@ -476,6 +832,12 @@ fn cmd_stats(period: &str) {
         return;
     }
 
+    if !stats.history {
+        println!("Cost history is disabled (--no-history or [stats] history = false).");
+        println!("Nothing has been recorded to report on.");
+        return;
+    }
+
     let tracker = match claude_status::CostTracker::open() {
         Ok(t) => t,
         Err(e) => {
@@ -521,18 +883,21 @@ fn cmd_stats(period: &str) {
     } else {
         String::new()
     };
+    println!("  Daily:   {}{}", cost.format(today_cost), daily_change);
+    let recent = tracker.daily_costs(today_start + 86400, 14);
     println!(
-        "  Daily:   ${:.2}{}",
-        today_cost, daily_change
+        "           {} (last 14 days)",
+        claude_status::widgets::format::sparkline(&recent)
     );
 
     // Weekly
     let weekly_cost = tracker.session_cost_range(week_start, now_ts);
-    let weekly_limit = 200.0;
-    let weekly_pct = (weekly_cost / weekly_limit) * 100.0;
+    let weekly_pct = (weekly_cost / stats.weekly_limit) * 100.0;
     println!(
-        "  Weekly:  ${:.2} ({:.0}% of ${:.0} limit)",
-        weekly_cost, weekly_pct, weekly_limit
+        "  Weekly:  {} ({:.0}% of {} limit)",
+        cost.format(weekly_cost),
+        weekly_pct,
+        cost.format_places(stats.weekly_limit, 0)
     );
 
     // Monthly
@@ -540,29 +905,47 @@ fn cmd_stats(period: &str) {
     let days_elapsed = ((now_ts - month_start) as f64 / 86400.0).max(1.0);
     let avg_daily = monthly_cost / days_elapsed;
     println!(
-        "  Monthly: ${:.2} (avg ${:.2}/day)",
-        monthly_cost, avg_daily
+        "  Monthly: {} (avg {}/day)",
+        cost.format(monthly_cost),
+        cost.format(avg_daily)
     );
+    let projected = avg_daily * days_in_month(now.date_naive()) as f64;
+    let over_budget = match stats.monthly_budget {
+        Some(budget) if projected > budget => {
+            format!(" (over {} budget)", cost.format_places(budget, 0))
+        }
+        _ => String::new(),
+    };
+    println!("  Projected month: {}{over_budget}", cost.format(projected));
 
-    // Top sessions
-    let range_start = match period {
-        "daily" => today_start,
-        "monthly" => month_start,
-        _ => week_start, // default: weekly
+    // Top sessions, from a rolling `--since` start or the period's calendar start
+    let rolling = since.and_then(|span| Some((span, format::parse_duration(span)?)));
+    let (range_start, range, count_range) = match rolling {
+        Some((span, secs)) => (
+            now_ts.saturating_sub(i64::try_from(secs).unwrap_or(i64::MAX)),
+            format!("last {span}"),
+            format!("in the last {span}"),
+        ),
+        None => {
+            let start = match period {
+                "daily" => today_start,
+                "monthly" => month_start,
+                _ => week_start, // default: weekly
+            };
+            (start, period.to_string(), format!("this {period}"))
+        }
     };
     let top = tracker.top_sessions(range_start, now_ts, 5);
     if !top.is_empty() {
         println!();
-        println!("  Top costly sessions ({period}):");
+        println!("  Top costly sessions ({range}):");
         for (i, session) in top.iter().enumerate() {
-            let dt = chrono::DateTime::from_timestamp(session.start_time, 0)
-                .map(|d| d.format("%b %d, %H:%M").to_string())
-                .unwrap_or_else(|| "unknown".into());
+            let dt = format_session_time(session.start_time, now_ts, time_format);
             println!(
-                "  {}. {} - ${:.2} ({})",
+                "  {}. {} - {} ({})",
                 i + 1,
                 dt,
-                session.total_cost,
+                cost.format(session.total_cost),
                 session.model
             );
         }
@@ -570,54 +953,82 @@ fn cmd_stats(period: &str) {
 
     let session_count = tracker.session_count_range(range_start, now_ts);
     println!();
-    println!("  Sessions this {period}: {session_count}");
+    println!("  Sessions {count_range}: {session_count}");
 }
 
-fn cmd_dump_schema() {
-    let sample = serde_json::json!({
-        "cwd": "/home/user/project",
-        "session_id": "abc-123-def-456",
-        "transcript_path": "/tmp/claude/transcript.jsonl",
-        "model": {
-            "id": "claude-opus-4-6",
-            "display_name": "Claude Opus 4.6"
-        },
-        "workspace": {
-            "current_dir": "/home/user/project",
-            "project_dir": "/home/user/project"
-        },
-        "version": "1.0.30",
-        "output_style": {
-            "name": "text"
-        },
-        "cost": {
-            "total_cost_usd": 0.1234,
-            "total_duration_ms": 45000,
-            "total_api_duration_ms": 32000,
-            "total_lines_added": 120,
-            "total_lines_removed": 30
-        },
-        "context_window": {
-            "total_input_tokens": 50000,
-            "total_output_tokens": 12000,
-            "context_window_size": 200000,
-            "used_percentage": 31.0,
-            "remaining_percentage": 69.0,
-            "current_usage": {
-                "input_tokens": 8000,
-                "output_tokens": 2000,
-                "cache_creation_input_tokens": 1000,
-                "cache_read_input_tokens": 5000
-            }
-        },
-        "exceeds_200k_tokens": false,
-        "vim": {
-            "mode": "normal"
-        },
-        "agent": {
-            "name": "task-agent-1"
+fn cmd_dump_config_schema() {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&Config::json_schema()).unwrap()
+    );
+}
+
+fn cmd_version(json: bool) {
+    let version = env!("CARGO_PKG_VERSION");
+    let commit = env!("CLAUDE_STATUS_GIT_SHA");
+    let built = env!("CLAUDE_STATUS_BUILD_DATE");
+    let target = env!("CLAUDE_STATUS_TARGET");
+    if json {
+        let value = serde_json::json!({
+            "version": version,
+            "commit": commit,
+            "build_date": built,
+            "target": target,
+        });
+        println!("{}", serde_json::to_string_pretty(&value).unwrap());
+        return;
+    }
+    println!("claude-status {version}");
+    println!("  Commit: {commit}");
+    println!("  Built:  {built}");
+    println!("  Target: {target}");
+}
+
+fn cmd_check_update(config: &Config) {
+    let current = env!("CARGO_PKG_VERSION");
+    let url = config.update_url.as_deref().unwrap_or(DEFAULT_UPDATE_URL);
+    match latest_release(url) {
+        Some(latest) if version_newer(&latest, current) => {
+            println!("Update available: {current} -> {latest}");
         }
-    });
+        Some(_) => println!("claude-status {current} is up to date"),
+        None => println!("claude-status: couldn't check for updates ({url} unreachable)"),
+    }
+}
+
+/// The version named by the release JSON at `url` (`tag_name`, or
+/// `version`), fetched with curl so the binary carries no HTTP client.
+/// `None` when offline, on a timeout or on a body that isn't a release.
+fn latest_release(url: &str) -> Option<String> {
+    let output = std::process::Command::new("curl")
+        .args(["-fsSL", "--max-time", "5"])
+        .args(["-H", "Accept: application/vnd.github+json"])
+        .args(["-A", concat!("claude-status/", env!("CARGO_PKG_VERSION"))])
+        .arg(url)
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let body: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    let tag = body.get("tag_name").or_else(|| body.get("version"))?;
+    Some(tag.as_str()?.trim_start_matches('v').to_string())
+}
 
+/// Whether dotted version `latest` is past `current`. Pre-release and
+/// build suffixes are ignored, so "1.2.0-rc1" counts as "1.2.0".
+fn version_newer(latest: &str, current: &str) -> bool {
+    let parts = |version: &str| {
+        let core = version.split(['-', '+']).next().unwrap_or_default();
+        let mut parts: Vec<u64> = core.split('.').map(|p| p.parse().unwrap_or(0)).collect();
+        parts.resize(parts.len().max(3), 0);
+        parts
+    };
+    parts(latest) > parts(current)
+}
+
+fn cmd_dump_schema() {
+    let sample = claude_status::widgets::SessionData::sample();
     println!("{}", serde_json::to_string_pretty(&sample).unwrap());
 }
@@ -0,0 +1,97 @@
+//! The two TOML spellings of `lines`.
+//!
+//! Nested arrays (`lines = [[{ type = "model" }]]`) are compact but awkward
+//! to edit by hand, so each line may also be a table with a `widgets` array:
+//!
+//! ```toml
+//! [[lines]]
+//! [[lines.widgets]]
+//! type = "model"
+//! ```
+//!
+//! Both load into the same `Vec<Vec<LineWidgetConfig>>`, and may be mixed.
+//! Serialization always writes the table form.
+
+use std::fmt;
+
+use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::LineWidgetConfig;
+
+/// One line in either spelling. Branching on array vs. table by hand (not
+/// `#[serde(untagged)]`) keeps the widget's own error, so a bad field is
+/// named instead of "did not match any variant".
+struct Line(Vec<LineWidgetConfig>);
+
+impl<'de> Deserialize<'de> for Line {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(LineVisitor)
+    }
+}
+
+struct LineVisitor;
+
+impl<'de> Visitor<'de> for LineVisitor {
+    type Value = Line;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of widgets or a table with a `widgets` array")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Line, A::Error> {
+        Vec::deserialize(SeqAccessDeserializer::new(seq)).map(Line)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Line, A::Error> {
+        LineTable::deserialize(MapAccessDeserializer::new(map)).map(|table| Line(table.widgets))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LineTable {
+    #[serde(default)]
+    widgets: Vec<LineWidgetConfig>,
+}
+
+#[derive(Serialize)]
+struct LineTableRef<'a> {
+    widgets: &'a [LineWidgetConfig],
+}
+
+pub(super) fn serialize<S: Serializer>(
+    lines: &[Vec<LineWidgetConfig>],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(lines.iter().map(|widgets| LineTableRef { widgets }))
+}
+
+pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Vec<LineWidgetConfig>>, D::Error> {
+    let lines = Vec::<Line>::deserialize(deserializer)?;
+    Ok(lines.into_iter().map(|Line(widgets)| widgets).collect())
+}
+
+/// The same, for an optional `lines` (profiles).
+pub(super) mod option {
+    use super::*;
+
+    pub(in super::super) fn serialize<S: Serializer>(
+        lines: &Option<Vec<Vec<LineWidgetConfig>>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match lines {
+            Some(lines) => super::serialize(lines, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(in super::super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<Vec<LineWidgetConfig>>>, D::Error> {
+        super::deserialize(deserializer).map(Some)
+    }
+}
@@ -1,18 +1,35 @@
-use std::collections::HashMap;
-use std::path::PathBuf;
+mod lines;
+mod schema;
 
-use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
-use crate::widgets::WidgetConfig;
+use serde::de::{Error as _, Unexpected};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::icons::IconSet;
+use crate::widgets::{RenderSettings, WidgetConfig};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
-    #[serde(default = "default_lines")]
+    /// Widgets per status line. TOML accepts nested arrays or `[[lines]]`
+    /// tables with `[[lines.widgets]]`; see `lines`.
+    #[serde(default = "default_lines", with = "lines")]
     pub lines: Vec<Vec<LineWidgetConfig>>,
+    /// Per-line settings, matched to `lines` by index.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub line_options: Vec<LineOptions>,
     #[serde(default = "default_theme")]
     pub theme: String,
     #[serde(default)]
     pub powerline: PowerlineConfig,
+    #[serde(default)]
+    pub stats: StatsConfig,
+    #[serde(default)]
+    pub cost: CostConfig,
+    /// Color aliases usable anywhere a color is, e.g. `brand = "#ff6600"`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub palette: BTreeMap<String, String>,
     #[serde(default = "default_color_level")]
     pub color_level: String,
     #[serde(default = "default_padding")]
@@ -27,9 +44,85 @@ pub struct Config {
     pub inherit_separator_colors: bool,
     #[serde(default = "default_separator")]
     pub default_separator: String,
+    /// Background for whole lines outside powerline mode, so padding and
+    /// separators share it. A widget's own `background_color` still wins.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_background: Option<String>,
+    /// "nerd", "emoji", "ascii" or "none"; see `IconSet`.
+    #[serde(default = "default_icon_set")]
+    pub icon_set: String,
+    /// "plain" leaves counts bare, "grouped" inserts `thousands_separator`.
+    #[serde(default = "default_number_format")]
+    pub number_format: String,
+    #[serde(default = "default_thousands_separator")]
+    pub thousands_separator: String,
+    /// Deadline for blocking widgets (git, custom-command); 0 disables it.
+    #[serde(default = "default_render_timeout_ms")]
+    pub render_timeout_ms: u64,
+    /// "shell" runs git for git widgets; "native" has `git-branch` read the
+    /// branch from the git directory and run git only for a detached HEAD,
+    /// reftable repositories and upstream counts.
+    #[serde(default = "default_git_backend", deserialize_with = "git_backend")]
+    pub git_backend: String,
+    /// Blocking widgets reuse their last output (per session, across runs)
+    /// when it is younger than this; 0 leaves only their own cache TTLs.
+    #[serde(default = "default_min_interval_ms")]
+    pub min_interval_ms: u64,
+    /// Strip unstyled spaces from line ends, unless powerline `auto_align`
+    /// is padding the lines to a common width.
+    #[serde(default)]
+    pub trim_trailing: bool,
+    /// Flag the output as stale once the transcript is this many seconds
+    /// old; 0 (the default) turns the check off.
+    #[serde(default)]
+    pub stale_after_secs: u64,
+    /// "marker" appends a dim "stale" note to the first line, "dim" dims
+    /// every line.
+    #[serde(default = "default_stale_style")]
+    pub stale_style: String,
+    /// Render at most this many (non-empty) lines; unset means no limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_lines: Option<usize>,
+    /// Appended to the last line when `max_lines` cuts any off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_lines_indicator: Option<String>,
+    /// Stands in for a line whose widgets are all hidden, so the line count
+    /// holds steady; unset drops such lines.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+    /// Widget types to skip wherever they appear in `lines`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled_widgets: Vec<String>,
+    /// Where `check-update` asks for the latest release; unset means
+    /// `DEFAULT_UPDATE_URL`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_url: Option<String>,
+    /// Named overrides, selected with `--config-profile` or `CLAUDE_STATUS_PROFILE`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A named `[profiles.<name>]` table. Set fields replace the top-level ones.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileConfig {
+    #[serde(
+        default,
+        with = "lines::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub lines: Option<Vec<Vec<LineWidgetConfig>>>,
+    pub line_options: Option<Vec<LineOptions>>,
+    pub theme: Option<String>,
+    pub powerline: Option<PowerlineConfig>,
+}
+
+/// Environment variable naming the active profile.
+pub const PROFILE_ENV: &str = "CLAUDE_STATUS_PROFILE";
+
+/// Config file names `default_path` looks for, in order.
+pub const CONFIG_FILES: [&str; 2] = ["config.toml", "config.json"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LineWidgetConfig {
     #[serde(rename = "type")]
     pub widget_type: String,
@@ -43,16 +136,68 @@ pub struct LineWidgetConfig {
     pub padding: Option<String>,
     #[serde(default)]
     pub merge_next: bool,
+    /// Extra emphasis for alert widgets: "blink" or "reverse".
+    #[serde(default)]
+    pub attention: Option<String>,
+    /// Overrides the widget's own priority for `sort = "priority"` lines.
     #[serde(default)]
+    pub priority: Option<u8>,
+    /// "right" holds the widget at the line's right edge, after the rest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub metadata: HashMap<String, String>,
 }
 
+impl LineWidgetConfig {
+    /// A widget of `widget_type` with every option at its default.
+    pub fn new(widget_type: &str) -> Self {
+        Self {
+            widget_type: widget_type.into(),
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineOptions {
+    /// "config" keeps the authored order, "priority" sorts widgets by
+    /// priority descending before overflow truncation.
+    #[serde(default = "default_sort")]
+    pub sort: String,
+    /// Emit the line as a data row for scripts: every widget's raw value,
+    /// joined by `raw_delimiter`, with no colors, padding or separators.
+    #[serde(default)]
+    pub raw_mode: bool,
+    #[serde(default = "default_raw_delimiter")]
+    pub raw_delimiter: String,
+    /// Emitted when every widget on the line is hidden; overrides the
+    /// top-level `placeholder`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+}
+
+impl Default for LineOptions {
+    fn default() -> Self {
+        Self {
+            sort: default_sort(),
+            raw_mode: false,
+            raw_delimiter: default_raw_delimiter(),
+            placeholder: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PowerlineConfig {
     #[serde(default)]
     pub enabled: bool,
     #[serde(default = "default_powerline_separator")]
     pub separator: String,
+    /// Separator pointing the other way, used where segments are
+    /// right-aligned after a flex fill.
+    #[serde(default = "default_powerline_separator_reverse")]
+    pub separator_reverse: String,
     #[serde(default)]
     pub separator_invert_background: bool,
     #[serde(default)]
@@ -61,6 +206,75 @@ pub struct PowerlineConfig {
     pub end_cap: Option<String>,
     #[serde(default)]
     pub auto_align: bool,
+    /// Swap a segment's foreground for black or white when it's too close
+    /// to the background to read (256-color and truecolor only).
+    #[serde(default)]
+    pub auto_contrast: bool,
+}
+
+/// Weekly spend limit in USD assumed when none is configured.
+pub const DEFAULT_WEEKLY_LIMIT: f64 = 200.0;
+
+/// The project's latest-release endpoint, read by `check-update`.
+pub const DEFAULT_UPDATE_URL: &str =
+    "https://api.github.com/repos/mstuart/ai-statusline/releases/latest";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsConfig {
+    /// Weekly spend limit in USD, shared by `stats`, cost-warning and burn-rate.
+    #[serde(default = "default_weekly_limit")]
+    pub weekly_limit: f64,
+    /// Monthly budget in USD; `stats` flags a projection that exceeds it.
+    #[serde(default)]
+    pub monthly_budget: Option<f64>,
+    /// Read and write the local cost database. Off means Pro analytics hide.
+    #[serde(default = "default_true")]
+    pub history: bool,
+}
+
+impl Default for StatsConfig {
+    fn default() -> Self {
+        Self {
+            weekly_limit: default_weekly_limit(),
+            monthly_budget: None,
+            history: true,
+        }
+    }
+}
+
+/// How money is shown. Amounts are tracked in USD; `rate` converts them to
+/// the display currency. Widget `symbol`, `decimals` and `rate` metadata
+/// override these per widget.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostConfig {
+    #[serde(default = "default_cost_symbol")]
+    pub symbol: String,
+    #[serde(default = "default_cost_decimals")]
+    pub decimals: usize,
+    #[serde(default = "default_cost_rate")]
+    pub rate: f64,
+}
+
+impl Default for CostConfig {
+    fn default() -> Self {
+        Self {
+            symbol: default_cost_symbol(),
+            decimals: default_cost_decimals(),
+            rate: default_cost_rate(),
+        }
+    }
+}
+
+impl CostConfig {
+    /// Format a USD amount: "$0.42", or "€0.39" with `symbol = "€"` and `rate = 0.92`.
+    pub fn format(&self, usd: f64) -> String {
+        self.format_places(usd, self.decimals)
+    }
+
+    /// Like `format` with a fixed number of decimal places.
+    pub fn format_places(&self, usd: f64, decimals: usize) -> String {
+        format!("{}{:.*}", self.symbol, decimals, usd * self.rate)
+    }
 }
 
 impl Default for PowerlineConfig {
@@ -68,10 +282,12 @@ impl Default for PowerlineConfig {
         Self {
             enabled: false,
             separator: default_powerline_separator(),
+            separator_reverse: default_powerline_separator_reverse(),
             separator_invert_background: false,
             start_cap: None,
             end_cap: None,
             auto_align: false,
+            auto_contrast: false,
         }
     }
 }
@@ -79,48 +295,24 @@ impl Default for PowerlineConfig {
 fn default_lines() -> Vec<Vec<LineWidgetConfig>> {
     vec![vec![
         LineWidgetConfig {
-            widget_type: "model".into(),
             id: "1".into(),
             color: Some("cyan".into()),
-            background_color: None,
-            bold: None,
-            raw_value: false,
-            padding: None,
-            merge_next: false,
-            metadata: HashMap::new(),
+            ..LineWidgetConfig::new("model")
         },
         LineWidgetConfig {
-            widget_type: "context-percentage".into(),
             id: "2".into(),
-            color: None,
-            background_color: None,
-            bold: None,
-            raw_value: false,
-            padding: None,
-            merge_next: false,
-            metadata: HashMap::new(),
+            ..LineWidgetConfig::new("context-percentage")
         },
         LineWidgetConfig {
-            widget_type: "session-cost".into(),
             id: "3".into(),
             color: Some("yellow".into()),
-            background_color: None,
-            bold: None,
             raw_value: true,
-            padding: None,
-            merge_next: false,
-            metadata: HashMap::new(),
+            ..LineWidgetConfig::new("session-cost")
         },
         LineWidgetConfig {
-            widget_type: "session-duration".into(),
             id: "4".into(),
-            color: None,
-            background_color: None,
-            bold: None,
             raw_value: true,
-            padding: None,
-            merge_next: false,
-            metadata: HashMap::new(),
+            ..LineWidgetConfig::new("session-duration")
         },
     ]]
 }
@@ -143,39 +335,215 @@ fn default_compact_threshold() -> u8 {
 fn default_separator() -> String {
     " | ".into()
 }
+fn default_icon_set() -> String {
+    "emoji".into()
+}
+fn default_number_format() -> String {
+    "plain".into()
+}
+fn default_thousands_separator() -> String {
+    ",".into()
+}
+fn default_stale_style() -> String {
+    "marker".into()
+}
+fn default_git_backend() -> String {
+    "shell".into()
+}
+/// Only the two backends, so a typo doesn't quietly mean "shell".
+fn git_backend<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let backend = String::deserialize(deserializer)?;
+    match backend.as_str() {
+        "shell" | "native" => Ok(backend),
+        _ => Err(D::Error::invalid_value(
+            Unexpected::Str(&backend),
+            &"\"shell\" or \"native\"",
+        )),
+    }
+}
+fn default_min_interval_ms() -> u64 {
+    1000
+}
+fn default_render_timeout_ms() -> u64 {
+    800
+}
+fn default_sort() -> String {
+    "config".into()
+}
+fn default_raw_delimiter() -> String {
+    "\t".into()
+}
+fn default_true() -> bool {
+    true
+}
+fn default_weekly_limit() -> f64 {
+    DEFAULT_WEEKLY_LIMIT
+}
+fn default_cost_symbol() -> String {
+    "$".into()
+}
+fn default_cost_decimals() -> usize {
+    2
+}
+fn default_cost_rate() -> f64 {
+    1.0
+}
 fn default_powerline_separator() -> String {
     "\u{E0B0}".into()
 }
+fn default_powerline_separator_reverse() -> String {
+    "\u{E0B2}".into()
+}
 
 impl Config {
+    /// Load the config, falling back to defaults. A config that can't be
+    /// read or parsed only shows up in the debug log.
     pub fn load(path: Option<&str>) -> Self {
-        let config_path = path.map(PathBuf::from).or_else(Self::default_path);
-
-        match config_path {
+        match Self::resolve_path(path) {
             Some(p) if p.exists() => {
-                let contents = std::fs::read_to_string(&p).unwrap_or_default();
-                toml::from_str(&contents).unwrap_or_default()
+                crate::debug_log!("config: {}", p.display());
+                Self::read(&p).unwrap_or_else(|e| {
+                    crate::debug_log!("config: {e}; using defaults");
+                    Self::default()
+                })
+            }
+            p => {
+                crate::debug_log!(
+                    "config: {} not found; using defaults",
+                    p.as_deref()
+                        .unwrap_or(Path::new("(no config dir)"))
+                        .display()
+                );
+                Self::default()
             }
-            _ => Self::default(),
         }
     }
 
+    /// Read and parse the config at `path`. Errors name the file.
+    pub fn read(path: &Path) -> Result<Self, String> {
+        std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| Self::parse(path, &contents))
+            .map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    /// Parse `contents` in the format `path`'s extension names: `.json` is
+    /// JSON, anything else TOML. YAML isn't supported yet and is an error
+    /// rather than a confusing TOML parse failure.
+    pub fn parse(path: &Path, contents: &str) -> Result<Self, String> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => serde_json::from_str(contents).map_err(|e| e.to_string()),
+            Some("yaml" | "yml") => Err("YAML configs are not supported; use TOML or JSON".into()),
+            _ => toml::from_str(contents).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Serialize in the format `path`'s extension names, as `parse` reads it.
+    pub fn serialize_for(&self, path: &Path) -> String {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => self.to_json(),
+            _ => self.to_toml(),
+        }
+    }
+
+    /// `load`, then overlay the active profile: `profile` if given, else
+    /// `$CLAUDE_STATUS_PROFILE`. An unknown name leaves the top level as is.
+    pub fn load_with_profile(path: Option<&str>, profile: Option<&str>) -> Self {
+        let mut config = Self::load(path);
+        if let Some(name) = Self::active_profile(profile) {
+            config.apply_profile(&name);
+        }
+        config
+    }
+
+    pub fn active_profile(profile: Option<&str>) -> Option<String> {
+        Self::pick_profile(profile, std::env::var(PROFILE_ENV).ok())
+    }
+
+    /// `active_profile` with the environment's value passed in: `explicit`
+    /// wins over `env`, and an empty name counts as none.
+    pub fn pick_profile(explicit: Option<&str>, env: Option<String>) -> Option<String> {
+        explicit
+            .map(str::to_string)
+            .or(env)
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Replace top-level settings with those the named profile sets.
+    /// Returns false if there is no such profile.
+    pub fn apply_profile(&mut self, name: &str) -> bool {
+        let Some(profile) = self.profiles.get(name).cloned() else {
+            return false;
+        };
+        if let Some(lines) = profile.lines {
+            self.lines = lines;
+        }
+        if let Some(line_options) = profile.line_options {
+            self.line_options = line_options;
+        }
+        if let Some(theme) = profile.theme {
+            self.theme = theme;
+        }
+        if let Some(powerline) = profile.powerline {
+            self.powerline = powerline;
+        }
+        true
+    }
+
+    /// The file `load` reads: an explicit `--config` path, else the default.
+    pub fn resolve_path(path: Option<&str>) -> Option<PathBuf> {
+        path.map(PathBuf::from).or_else(Self::default_path)
+    }
+
+    /// The first of `CONFIG_FILES` that exists under `$CLAUDE_CONFIG_DIR`,
+    /// then under the XDG config dir, else `config.toml` in the latter.
     pub fn default_path() -> Option<PathBuf> {
         // Check CLAUDE_CONFIG_DIR first
-        if let Ok(dir) = std::env::var("CLAUDE_CONFIG_DIR") {
-            let p = PathBuf::from(dir).join("claude-status").join("config.toml");
-            if p.exists() {
-                return Some(p);
-            }
+        if let Ok(dir) = std::env::var("CLAUDE_CONFIG_DIR")
+            && let Some(p) = Self::find_in(&PathBuf::from(dir).join("claude-status"))
+        {
+            return Some(p);
         }
         // XDG config
-        dirs::config_dir().map(|d| d.join("claude-status").join("config.toml"))
+        let dir = dirs::config_dir()?.join("claude-status");
+        Some(Self::find_in(&dir).unwrap_or_else(|| dir.join(CONFIG_FILES[0])))
+    }
+
+    fn find_in(dir: &Path) -> Option<PathBuf> {
+        CONFIG_FILES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|p| p.exists())
     }
 
     pub fn to_toml(&self) -> String {
         toml::to_string_pretty(self).unwrap_or_default()
     }
 
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn line_options(&self, line_idx: usize) -> LineOptions {
+        self.line_options.get(line_idx).cloned().unwrap_or_default()
+    }
+
+    pub fn is_disabled(&self, widget_type: &str) -> bool {
+        self.disabled_widgets.iter().any(|w| w == widget_type)
+    }
+
+    /// Keep only line `n` (1-indexed) and its options. Returns false if the
+    /// config has no such line.
+    pub fn select_line(&mut self, n: usize) -> bool {
+        if n == 0 || n > self.lines.len() {
+            return false;
+        }
+        let options = self.line_options(n - 1);
+        self.lines = vec![self.lines.swap_remove(n - 1)];
+        self.line_options = vec![options];
+        true
+    }
+
     pub fn to_widget_config(lwc: &LineWidgetConfig) -> WidgetConfig {
         WidgetConfig {
             widget_type: lwc.widget_type.clone(),
@@ -187,6 +555,26 @@ impl Config {
             padding: lwc.padding.clone(),
             merge_next: lwc.merge_next,
             metadata: lwc.metadata.clone(),
+            settings: RenderSettings::default(),
+        }
+    }
+
+    /// Like `to_widget_config`, plus the config-level render settings.
+    pub fn widget_config(&self, lwc: &LineWidgetConfig) -> WidgetConfig {
+        let mut wc = Self::to_widget_config(lwc);
+        wc.settings = self.render_settings();
+        wc
+    }
+
+    pub fn render_settings(&self) -> RenderSettings {
+        RenderSettings {
+            group_separator: (self.number_format == "grouped")
+                .then(|| self.thousands_separator.clone()),
+            icons: IconSet::from_name(&self.icon_set),
+            weekly_limit: self.stats.weekly_limit,
+            history: self.stats.history,
+            cost: self.cost.clone(),
+            native_git: self.git_backend == "native",
         }
     }
 }
@@ -195,8 +583,12 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             lines: default_lines(),
+            line_options: Vec::new(),
             theme: default_theme(),
             powerline: PowerlineConfig::default(),
+            stats: StatsConfig::default(),
+            cost: CostConfig::default(),
+            palette: BTreeMap::new(),
             color_level: default_color_level(),
             default_padding: default_padding(),
             flex_mode: default_flex_mode(),
@@ -204,6 +596,22 @@ impl Default for Config {
             global_bold: false,
             inherit_separator_colors: false,
             default_separator: default_separator(),
+            line_background: None,
+            icon_set: default_icon_set(),
+            number_format: default_number_format(),
+            thousands_separator: default_thousands_separator(),
+            render_timeout_ms: default_render_timeout_ms(),
+            git_backend: default_git_backend(),
+            min_interval_ms: default_min_interval_ms(),
+            trim_trailing: false,
+            stale_after_secs: 0,
+            stale_style: default_stale_style(),
+            max_lines: None,
+            max_lines_indicator: None,
+            placeholder: None,
+            disabled_widgets: Vec::new(),
+            update_url: None,
+            profiles: BTreeMap::new(),
         }
     }
 }
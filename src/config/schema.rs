@@ -0,0 +1,441 @@
+//! JSON Schema for the config file, printed by `dump-config-schema`.
+//!
+//! Written by hand, but the enums come from the same lists the code uses
+//! (registered widgets, themes, icon sets) so they can't drift.
+
+use serde_json::{Value, json};
+
+use super::Config;
+use crate::icons::IconSet;
+use crate::themes::Theme;
+use crate::widgets::WidgetRegistry;
+
+/// Widget metadata keys: (key, widgets that read it, description).
+const METADATA_KEYS: &[(&str, &str, &str)] = &[
+    (
+        "api_ratio",
+        "session-duration",
+        "\"true\" appends the API time percentage",
+    ),
+    (
+        "bar",
+        "context-percentage, context-remaining, block-timer",
+        "\"true\" shows a progress bar",
+    ),
+    (
+        "bar_width",
+        "block-timer, block-gauge",
+        "Progress bar width in cells",
+    ),
+    (
+        "block_limit",
+        "block-cost, block-gauge",
+        "Soft limit in USD per 5-hour block (default: weekly_limit / 11.2)",
+    ),
+    (
+        "burn_rate",
+        "session-cost",
+        "\"true\" appends the hourly burn rate",
+    ),
+    (
+        "char",
+        "separator, flex-separator",
+        "Separator or fill character",
+    ),
+    (
+        "command",
+        "custom-command",
+        "Shell command whose first output line is shown",
+    ),
+    (
+        "critical_pct",
+        "context-gauge",
+        "Context % above which the gauge turns red (default 80)",
+    ),
+    (
+        "critical_threshold",
+        "cost-warning",
+        "Fraction of the weekly limit shown as critical",
+    ),
+    (
+        "decimals",
+        "session-cost, block-cost, budget-remaining, burn-rate, cache-savings, last-message-cost, model-suggest",
+        "Overrides [cost] decimals",
+    ),
+    (
+        "fallback",
+        "project",
+        "\"true\" shows the directory name outside a git repo",
+    ),
+    (
+        "fish_style",
+        "cwd",
+        "\"true\" abbreviates parent directories fish-style",
+    ),
+    ("full", "cwd", "\"true\" shows the full path"),
+    ("glyph", "group-divider", "Divider glyph (default: ❯)"),
+    (
+        "hide_for_models",
+        "any widget",
+        "Comma-separated model names; hidden when the model id contains one (case-insensitive)",
+    ),
+    ("icon", "git-branch", "\"true\" prefixes the branch glyph"),
+    (
+        "input_price",
+        "cache-savings",
+        "USD per million uncached input tokens (default 3)",
+    ),
+    (
+        "inverse",
+        "context-percentage",
+        "\"true\" shows remaining instead of used",
+    ),
+    (
+        "left",
+        "group-divider",
+        "Text before the glyph (default: one space)",
+    ),
+    (
+        "max_width",
+        "cwd, custom-text, git-branch, project",
+        "Truncate to this many columns",
+    ),
+    (
+        "min_savings",
+        "model-suggest",
+        "Minimum savings in USD before suggesting a model",
+    ),
+    (
+        "only_for_models",
+        "any widget",
+        "Comma-separated model names; shown only when the model id contains one (case-insensitive)",
+    ),
+    (
+        "precision",
+        "percentage widgets",
+        "Decimal places for percentages (default 0, at most 3)",
+    ),
+    (
+        "rounding",
+        "percentage widgets",
+        "\"round\" (default), \"floor\" or \"ceil\"",
+    ),
+    (
+        "rate",
+        "session-cost, block-cost, budget-remaining, burn-rate, cache-savings, cost-warning, last-message-cost, model-suggest",
+        "Overrides [cost] rate",
+    ),
+    (
+        "right",
+        "group-divider",
+        "Text after the glyph (default: one space)",
+    ),
+    ("segments", "cwd", "Show the last N path segments"),
+    (
+        "source",
+        "session-duration",
+        "\"wallclock\" counts from when cost history first saw the session; \"payload\" (default) uses total_duration_ms",
+    ),
+    (
+        "size",
+        "transcript-size",
+        "\"true\" shows the file size instead of the message count",
+    ),
+    (
+        "stale_hours",
+        "git-age",
+        "Hours since the last commit before it shows in yellow (default 24)",
+    ),
+    (
+        "symbol",
+        "session-cost, block-cost, budget-remaining, burn-rate, cache-savings, cost-warning, last-message-cost, model-suggest",
+        "Overrides [cost] symbol",
+    ),
+    ("text", "custom-text", "Static text to display"),
+    (
+        "tier_colors",
+        "model",
+        "Colors per model tier, e.g. \"opus=magenta,sonnet=cyan,haiku=green\"",
+    ),
+    (
+        "threshold",
+        "compaction-warning, idle-time",
+        "Context usage percent (compaction-warning) or idle minutes (idle-time) before showing",
+    ),
+    (
+        "tokens",
+        "context-remaining",
+        "\"true\" shows remaining tokens instead of percent",
+    ),
+    (
+        "truncate",
+        "cwd, custom-text, git-branch, project",
+        "\"middle\" keeps head and tail; default cuts the end",
+    ),
+    (
+        "ttl",
+        "agent-count",
+        "Seconds a heartbeat counts as live (default 60)",
+    ),
+    (
+        "upstream",
+        "git-branch",
+        "\"true\" appends ahead/behind/synced state against the upstream",
+    ),
+    (
+        "warn_pct",
+        "context-gauge",
+        "Context % from which the gauge turns yellow (default 50)",
+    ),
+    (
+        "warn_threshold",
+        "cost-warning",
+        "Fraction of the weekly limit that triggers the warning",
+    ),
+    (
+        "weight",
+        "flex-separator",
+        "Share of the leftover width relative to the line's other flex separators (default 1)",
+    ),
+    (
+        "weekly_limit",
+        "cost-warning, burn-rate, budget-remaining",
+        "Overrides [stats] weekly_limit",
+    ),
+    ("window_minutes", "burn-rate", "Averaging window in minutes"),
+];
+
+impl Config {
+    /// A JSON Schema (draft 2020-12) describing every config key.
+    pub fn json_schema() -> Value {
+        let registry = WidgetRegistry::new();
+        let widget_types = registry.widget_types();
+        let line_options = json!({
+            "type": "object",
+            "properties": {
+                "sort": {
+                    "enum": ["config", "priority"],
+                    "default": "config",
+                    "description": "\"priority\" sorts widgets by priority before overflow truncation"
+                },
+                "raw_mode": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Emit raw widget values joined by raw_delimiter, without ANSI"
+                },
+                "raw_delimiter": { "type": "string", "default": "\t" },
+                "placeholder": {
+                    "type": "string",
+                    "description": "Shown when every widget on this line is hidden"
+                }
+            },
+            "additionalProperties": false
+        });
+        let lines = json!({
+            "type": "array",
+            "description": "Status lines, each a list of widgets",
+            "items": {
+                "oneOf": [
+                    { "type": "array", "items": { "$ref": "#/$defs/widget" } },
+                    {
+                        "type": "object",
+                        "properties": {
+                            "widgets": { "type": "array", "items": { "$ref": "#/$defs/widget" } }
+                        },
+                        "additionalProperties": false
+                    }
+                ]
+            }
+        });
+        let powerline = json!({
+            "type": "object",
+            "properties": {
+                "enabled": { "type": "boolean", "default": false },
+                "separator": { "type": "string", "default": "\u{E0B0}" },
+                "separator_reverse": { "type": "string", "default": "\u{E0B2}" },
+                "separator_invert_background": { "type": "boolean", "default": false },
+                "start_cap": { "type": "string" },
+                "end_cap": { "type": "string" },
+                "auto_align": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Pad lines to the widest one"
+                },
+                "auto_contrast": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Use black or white text on backgrounds too close to the foreground (256/truecolor)"
+                }
+            },
+            "additionalProperties": false
+        });
+        let theme = json!({ "enum": Theme::list(), "default": "default" });
+        let metadata: serde_json::Map<String, Value> = METADATA_KEYS
+            .iter()
+            .map(|(key, widgets, description)| {
+                (
+                    key.to_string(),
+                    json!({ "type": "string", "description": format!("{description} ({widgets})") }),
+                )
+            })
+            .collect();
+
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "claude-status config",
+            "type": "object",
+            "properties": {
+                "lines": lines,
+                "line_options": {
+                    "type": "array",
+                    "description": "Per-line settings, matched to lines by index",
+                    "items": line_options
+                },
+                "theme": theme,
+                "powerline": powerline,
+                "stats": {
+                    "type": "object",
+                    "properties": {
+                        "weekly_limit": { "type": "number", "default": super::DEFAULT_WEEKLY_LIMIT },
+                        "monthly_budget": { "type": "number" },
+                        "history": { "type": "boolean", "default": true }
+                    },
+                    "additionalProperties": false
+                },
+                "cost": {
+                    "type": "object",
+                    "description": "Currency for cost widgets and `stats`; widget metadata overrides it",
+                    "properties": {
+                        "symbol": { "type": "string", "default": "$" },
+                        "decimals": { "type": "integer", "minimum": 0, "default": 2 },
+                        "rate": {
+                            "type": "number",
+                            "default": 1.0,
+                            "description": "Multiplier from USD to the display currency"
+                        }
+                    },
+                    "additionalProperties": false
+                },
+                "palette": {
+                    "type": "object",
+                    "description": "Color aliases usable wherever a color is accepted",
+                    "additionalProperties": { "type": "string" }
+                },
+                "color_level": { "enum": ["auto", "none", "16", "256", "truecolor", "16m"], "default": "auto" },
+                "default_padding": { "type": "string", "default": " " },
+                "flex_mode": { "enum": ["full", "full-minus-40", "compact"], "default": "full-minus-40" },
+                "compact_threshold": { "type": "integer", "minimum": 0, "maximum": 255, "default": 60 },
+                "global_bold": { "type": "boolean", "default": false },
+                "inherit_separator_colors": { "type": "boolean", "default": false },
+                "default_separator": { "type": "string", "default": " | " },
+                "line_background": {
+                    "type": "string",
+                    "description": "Background for whole lines (not powerline); widget background_color overrides it"
+                },
+                "icon_set": { "enum": IconSet::names(), "default": "emoji" },
+                "number_format": { "enum": ["plain", "grouped"], "default": "plain" },
+                "thousands_separator": { "type": "string", "default": "," },
+                "render_timeout_ms": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "default": 800,
+                    "description": "Deadline for git and custom-command widgets; 0 disables it"
+                },
+                "git_backend": {
+                    "enum": ["shell", "native"],
+                    "default": "shell",
+                    "description": "native reads the git-branch name in-process, falling back to git"
+                },
+                "min_interval_ms": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "default": 1000,
+                    "description": "Reuse a git or custom-command widget's last output within this window (needs a session_id)"
+                },
+                "stale_after_secs": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "default": 0,
+                    "description": "Flag output as stale once the transcript is this old; 0 disables"
+                },
+                "stale_style": { "enum": ["marker", "dim"], "default": "marker" },
+                "max_lines": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Render at most this many lines; unset for no limit"
+                },
+                "max_lines_indicator": {
+                    "type": "string",
+                    "description": "Appended to the last line when max_lines cuts lines off"
+                },
+                "placeholder": {
+                    "type": "string",
+                    "description": "Shown for a line whose widgets are all hidden; unset drops the line"
+                },
+                "disabled_widgets": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Widget types to skip wherever they appear in lines"
+                },
+                "update_url": {
+                    "type": "string",
+                    "description": "Release endpoint for check-update; defaults to the project's GitHub releases API"
+                },
+                "trim_trailing": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Strip unstyled trailing spaces unless powerline auto_align pads the lines"
+                },
+                "profiles": {
+                    "type": "object",
+                    "description": "Named overrides selected with --config-profile or CLAUDE_STATUS_PROFILE",
+                    "additionalProperties": {
+                        "type": "object",
+                        "properties": {
+                            "lines": lines,
+                            "line_options": { "type": "array", "items": line_options },
+                            "theme": theme,
+                            "powerline": powerline
+                        },
+                        "additionalProperties": false
+                    }
+                }
+            },
+            "additionalProperties": false,
+            "$defs": {
+                "widget": {
+                    "type": "object",
+                    "required": ["type"],
+                    "properties": {
+                        "type": { "enum": widget_types },
+                        "id": { "type": "string" },
+                        "color": { "type": "string", "description": "Named, hex, or 256-color index" },
+                        "background_color": { "type": "string" },
+                        "bold": { "type": "boolean" },
+                        "raw_value": { "type": "boolean", "description": "Compact mode without labels" },
+                        "padding": { "type": "string" },
+                        "merge_next": { "type": "boolean", "description": "No separator before the next widget" },
+                        "attention": { "enum": ["blink", "reverse"] },
+                        "priority": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "maximum": 255,
+                            "description": "Overrides the widget's priority on sort = \"priority\" lines"
+                        },
+                        "pin": {
+                            "enum": ["right"],
+                            "description": "Hold the widget at the right edge of the line"
+                        },
+                        "metadata": {
+                            "type": "object",
+                            "description": "Widget-specific options; values are strings",
+                            "properties": metadata,
+                            "additionalProperties": { "type": "string" }
+                        }
+                    },
+                    "additionalProperties": false
+                }
+            }
+        })
+    }
+}
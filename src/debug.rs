@@ -0,0 +1,26 @@
+//! Opt-in diagnostics on stderr for tracing why a status line looks the way
+//! it does. Silent unless `CLAUDE_STATUS_DEBUG` is set; stdout carries the
+//! status line and is never written to.
+
+use std::sync::OnceLock;
+
+/// Environment variable that turns on debug logging. Any value but empty
+/// or "0" enables it.
+pub const DEBUG_ENV: &str = "CLAUDE_STATUS_DEBUG";
+
+/// Whether `DEBUG_ENV` is set, read once per process.
+pub fn enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| std::env::var(DEBUG_ENV).is_ok_and(|v| !v.is_empty() && v != "0"))
+}
+
+/// `eprintln!` behind `CLAUDE_STATUS_DEBUG`, prefixed so the lines stand out
+/// in a host's logs. The arguments aren't evaluated when logging is off.
+#[macro_export]
+macro_rules! debug_log {
+    ($($arg:tt)*) => {
+        if $crate::debug::enabled() {
+            eprintln!("claude-status[debug] {}", format_args!($($arg)*));
+        }
+    };
+}
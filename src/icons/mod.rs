@@ -0,0 +1,108 @@
+//! Semantic icon names mapped to glyphs for each supported font setup.
+
+/// Every icon: (name, Nerd Font glyph, ASCII fallback). The fallback is ""
+/// for purely decorative icons, which plain text leaves out.
+const ICONS: [(&str, &str, &str); 11] = [
+    ("warning", "\u{F071}", "!"),
+    ("critical", "\u{F06A}", "!!"),
+    ("hint", "\u{F0EB}", "*"),
+    ("branch", "\u{E0A0}", ""),
+    ("clean", "\u{F00C}", ""),
+    ("dirty", "\u{F111}", ""),
+    ("model", "\u{F544}", ""),
+    ("synced", "\u{F0C2}", ""),
+    ("ahead", "\u{F062}", ""),
+    ("behind", "\u{F063}", ""),
+    ("no-upstream", "\u{F127}", ""),
+];
+
+/// Powerline glyphs and what stands in for them without a patched font.
+const POWERLINE: [(char, &str); 8] = [
+    ('\u{E0B0}', ">"),
+    ('\u{E0B1}', ">"),
+    ('\u{E0B4}', ">"),
+    ('\u{E0B5}', ">"),
+    ('\u{E0B2}', "<"),
+    ('\u{E0B3}', "<"),
+    ('\u{E0B6}', "<"),
+    ('\u{E0B7}', "<"),
+];
+
+/// Whether the environment says a Nerd Font is installed (`NERD_FONT` or
+/// `NERDFONTS` set). Terminals can't be asked, so this is opt-in.
+pub fn nerd_font_hint() -> bool {
+    std::env::var_os("NERD_FONT").is_some() || std::env::var_os("NERDFONTS").is_some()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IconSet {
+    Nerd,
+    #[default]
+    Emoji,
+    Ascii,
+    None,
+}
+
+impl IconSet {
+    /// Parse the `icon_set` config value. "auto" is Nerd Fonts when
+    /// `nerd_font_hint` says so and ASCII otherwise. Unknown names fall back
+    /// to emoji.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "nerd" => Self::Nerd,
+            "auto" if nerd_font_hint() => Self::Nerd,
+            "auto" => Self::Ascii,
+            "ascii" => Self::Ascii,
+            "none" => Self::None,
+            _ => Self::Emoji,
+        }
+    }
+
+    pub fn names() -> &'static [&'static str] {
+        &["nerd", "emoji", "ascii", "none", "auto"]
+    }
+
+    /// Glyph for a semantic icon name, or "" when the set has none for it.
+    /// The emoji set only decorates alerts; there is no emoji for "branch"
+    /// or "model" that reads well in a status line. ASCII uses each icon's
+    /// fallback.
+    pub fn get(&self, name: &str) -> &'static str {
+        let Some(&(_, nerd, ascii)) = ICONS.iter().find(|(n, _, _)| *n == name) else {
+            return "";
+        };
+        match (self, name) {
+            (Self::Nerd, _) => nerd,
+            (Self::Ascii, _) => ascii,
+            (Self::Emoji, "warning") => "\u{26A0}\u{FE0F}",
+            (Self::Emoji, "critical") => "\u{1F534}",
+            (Self::Emoji, "hint") => "\u{1F4A1}",
+            _ => "",
+        }
+    }
+
+    /// `text` with Nerd Font and powerline glyphs the set can't draw swapped
+    /// for ASCII: powerline arrows become `>`/`<`, other private-use glyphs
+    /// a space. Only the ASCII set substitutes.
+    pub fn fallback_text(&self, text: &str) -> String {
+        if *self != Self::Ascii {
+            return text.to_string();
+        }
+        text.chars()
+            .map(|c| match POWERLINE.iter().find(|(glyph, _)| *glyph == c) {
+                Some((_, ascii)) => ascii.to_string(),
+                None if ('\u{E000}'..='\u{F8FF}').contains(&c) => " ".to_string(),
+                None => c.to_string(),
+            })
+            .collect()
+    }
+
+    /// Prefix `text` with the named icon, separated by a space. Returns the
+    /// text unchanged when the set has no glyph for `name`.
+    pub fn prefix(&self, name: &str, text: &str) -> String {
+        match (self.get(name), text) {
+            ("", _) => text.to_string(),
+            (icon, "") => icon.to_string(),
+            (icon, _) => format!("{icon} {text}"),
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Decode ANSI SGR sequences back into styled segments: the inverse of
+//! `to_ansi`, for text that arrives with its own escapes (custom command
+//! output, `custom-text`) and for the TUI preview.
+
+use super::RenderedSegment;
+use crate::render::ColorSpec;
+
+/// The 16 basic colors by SGR order (30–37, then 90–97), under the names
+/// `Renderer::parse_color` uses.
+pub const NAMED: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "brightBlack",
+    "brightRed",
+    "brightGreen",
+    "brightYellow",
+    "brightBlue",
+    "brightMagenta",
+    "brightCyan",
+    "brightWhite",
+];
+
+/// Split `s` into segments at each SGR sequence, each carrying the style
+/// in effect for its text. Other CSI sequences and OSC sequences
+/// (hyperlinks) are dropped; their visible text is kept.
+pub fn parse(s: &str) -> Vec<RenderedSegment> {
+    let mut segments = Vec::new();
+    let mut current = RenderedSegment::default();
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            current.text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                let mut params = String::new();
+                let mut terminator = None;
+                // Parameter and intermediate bytes, then one final byte
+                for c in chars.by_ref() {
+                    if (' '..='?').contains(&c) {
+                        params.push(c);
+                    } else {
+                        terminator = Some(c);
+                        break;
+                    }
+                }
+                let sgr = params.chars().all(|c| c.is_ascii_digit() || c == ';');
+                if terminator == Some('m') && sgr {
+                    if !current.text.is_empty() {
+                        segments.push(current.clone());
+                        current.text.clear();
+                    }
+                    apply_sgr(&mut current, &params);
+                }
+            }
+            Some(']') => {
+                // OSC runs to BEL or ST (ESC \).
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if !current.text.is_empty() {
+        segments.push(current);
+    }
+    segments
+}
+
+/// `s` without any escape sequences.
+pub fn strip(s: &str) -> String {
+    parse(s).into_iter().map(|segment| segment.text).collect()
+}
+
+fn apply_sgr(style: &mut RenderedSegment, params: &str) {
+    let codes: Vec<u16> = params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+    let named = |i: u16| ColorSpec::Named(NAMED[i as usize].into());
+    let mut i = 0;
+    while i < codes.len() {
+        match codes[i] {
+            0 => *style = RenderedSegment::default(),
+            1 => style.bold = true,
+            2 => style.dim = true,
+            5 => style.blink = true,
+            7 => style.reverse = true,
+            22 => {
+                style.bold = false;
+                style.dim = false;
+            }
+            25 => style.blink = false,
+            27 => style.reverse = false,
+            n @ 30..=37 => style.fg = Some(named(n - 30)),
+            n @ 90..=97 => style.fg = Some(named(n - 90 + 8)),
+            n @ 40..=47 => style.bg = Some(named(n - 40)),
+            n @ 100..=107 => style.bg = Some(named(n - 100 + 8)),
+            39 => style.fg = None,
+            49 => style.bg = None,
+            n @ (38 | 48) => {
+                let color = match codes.get(i + 1) {
+                    Some(5) => {
+                        let c = codes.get(i + 2).map(|&v| ColorSpec::Ansi256(v as u8));
+                        i += 2;
+                        c
+                    }
+                    Some(2) => {
+                        let rgb = codes.get(i + 2..i + 5);
+                        i += 4;
+                        rgb.map(|v| ColorSpec::Rgb(v[0] as u8, v[1] as u8, v[2] as u8))
+                    }
+                    _ => None,
+                };
+                if n == 38 {
+                    style.fg = color.or(style.fg.take());
+                } else {
+                    style.bg = color.or(style.bg.take());
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::to_ansi;
+    use crate::render::Renderer;
+
+    #[test]
+    fn test_plain_text_is_one_segment() {
+        let segments = parse("Opus | 42%");
+        assert_eq!(segments, vec![RenderedSegment::plain("Opus | 42%")]);
+    }
+
+    #[test]
+    fn test_sgr_colors_attributes_and_reset() {
+        let segments = parse("\x1b[1;91mOpus\x1b[22m!\x1b[0m \x1b[48;5;208;38;2;1;2;3m42%");
+        assert_eq!(segments.len(), 4);
+        assert_eq!(segments[0].text, "Opus");
+        assert!(segments[0].bold);
+        assert_eq!(segments[0].fg, Some(ColorSpec::Named("brightRed".into())));
+        assert!(!segments[1].bold);
+        assert_eq!(segments[1].fg, segments[0].fg);
+        assert!(segments[2].is_plain());
+        assert_eq!(segments[3].bg, Some(ColorSpec::Ansi256(208)));
+        assert_eq!(segments[3].fg, Some(ColorSpec::Rgb(1, 2, 3)));
+    }
+
+    #[test]
+    fn test_unknown_sequences_and_links_are_dropped() {
+        let s = "\x1b[2K\x1b[?25l\x1b]8;;https://example.com\x07main\x1b]8;;\x1b\\ \x1b[4mok";
+        assert_eq!(strip(s), "main ok");
+        assert!(parse(s).iter().all(|segment| segment.is_plain()));
+    }
+
+    #[test]
+    fn test_round_trips_renderer_output() {
+        let renderer = Renderer::detect("truecolor");
+        let segment = RenderedSegment {
+            text: "main".into(),
+            fg: Some(ColorSpec::Rgb(255, 135, 0)),
+            bg: Some(ColorSpec::Ansi256(236)),
+            bold: true,
+            dim: true,
+            ..RenderedSegment::default()
+        };
+        let ansi = to_ansi(std::slice::from_ref(&segment), &renderer);
+        assert_eq!(parse(&ansi), vec![segment]);
+    }
+}
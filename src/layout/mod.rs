@@ -1,14 +1,60 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+
 use unicode_width::UnicodeWidthStr;
 
 use crate::config::Config;
-use crate::render::Renderer;
+use crate::icons::IconSet;
+use crate::render::{ColorLevel, ColorSpec, MIN_CONTRAST, Renderer};
 use crate::themes::Theme;
 use crate::widgets::{SessionData, WidgetOutput, WidgetRegistry};
 
+pub mod ansi;
+mod segment;
+
+pub use segment::{RenderedSegment, to_ansi};
+
+/// How long one widget took to render, as reported by `--profile`.
+#[derive(Debug, Clone)]
+pub struct WidgetTiming {
+    pub widget_type: String,
+    pub elapsed: Duration,
+    /// False for hidden output and for blocking widgets that missed the deadline.
+    pub visible: bool,
+}
+
+/// Why one configured widget is or isn't on screen, as reported by `--explain`.
+#[derive(Debug, Clone)]
+pub struct WidgetExplanation {
+    /// 1-indexed, as `--line` counts.
+    pub line: usize,
+    pub widget_type: String,
+    pub visible: bool,
+    /// "shown", or why not: "hidden: not Pro", "dropped: overflow", ...
+    pub reason: String,
+}
+
+/// Terminal width assumed when the real one is unavailable or reported as 0.
+const FALLBACK_WIDTH: usize = 120;
+
+/// Narrowest width a flex mode may leave for widgets.
+const MIN_WIDTH: usize = 20;
+
+/// Largest flex separator `weight`; heavier ones count as this, which keeps
+/// the share arithmetic from overflowing.
+const MAX_FLEX_WEIGHT: usize = 1000;
+
 pub struct LayoutEngine<'a> {
     config: &'a Config,
     renderer: &'a Renderer,
     theme: Theme,
+    /// Decides whether powerline glyphs fall back to ASCII.
+    icons: IconSet,
+    /// Overrides the detected terminal width.
+    terminal_width: Option<usize>,
 }
 
 impl<'a> LayoutEngine<'a> {
@@ -18,65 +64,413 @@ impl<'a> LayoutEngine<'a> {
             config,
             renderer,
             theme,
+            icons: IconSet::from_name(&config.icon_set),
+            terminal_width: None,
         }
     }
 
+    /// Lay out for a terminal `width` columns wide instead of querying the tty.
+    pub fn with_terminal_width(mut self, width: usize) -> Self {
+        self.terminal_width = Some(width);
+        self
+    }
+
     pub fn render(
         &self,
         data: &SessionData,
         _config: &Config,
         registry: &WidgetRegistry,
     ) -> Vec<String> {
+        self.serialize(&self.render_lines(data, registry, None, None))
+    }
+
+    /// The lines `render` would print, as styled segments before ANSI
+    /// serialization.
+    pub fn render_segments(
+        &self,
+        data: &SessionData,
+        _config: &Config,
+        registry: &WidgetRegistry,
+    ) -> Vec<Vec<RenderedSegment>> {
+        self.render_lines(data, registry, None, None)
+    }
+
+    /// Like `render`, also returning each widget's render time in config order.
+    pub fn render_profiled(
+        &self,
+        data: &SessionData,
+        _config: &Config,
+        registry: &WidgetRegistry,
+    ) -> (Vec<String>, Vec<WidgetTiming>) {
+        let mut timings = Vec::new();
+        let lines = self.render_lines(data, registry, Some(&mut timings), None);
+        (self.serialize(&lines), timings)
+    }
+
+    /// Render, then report for every configured widget whether it made it
+    /// onto a line and why not, in config order.
+    pub fn explain(
+        &self,
+        data: &SessionData,
+        _config: &Config,
+        registry: &WidgetRegistry,
+    ) -> Vec<WidgetExplanation> {
+        let mut explained = Vec::new();
+        self.render_lines(data, registry, None, Some(&mut explained));
+        explained
+    }
+
+    /// Every shown widget's text without styling, in config order, keyed by
+    /// widget type (`type.2`, `type.3`, ... for repeats), for `--format kv`.
+    /// Separators and fill are left out, as are blocking widgets that miss
+    /// the render deadline.
+    pub fn values(
+        &self,
+        data: &SessionData,
+        _config: &Config,
+        registry: &WidgetRegistry,
+    ) -> Vec<(String, String)> {
+        let config = self.config;
+        let deadline = Instant::now() + Duration::from_millis(config.render_timeout_ms);
+        let mut pending = if config.render_timeout_ms > 0 {
+            self.spawn_blocking(data, registry)
+        } else {
+            HashMap::new()
+        };
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        let mut values = Vec::new();
+        for (line_idx, line_config) in config.lines.iter().enumerate() {
+            for (idx, wc) in line_config.iter().enumerate() {
+                if matches!(
+                    wc.widget_type.as_str(),
+                    "separator" | "group-divider" | "flex-separator"
+                ) || config.is_disabled(&wc.widget_type)
+                    || !model_allows(wc, data)
+                {
+                    continue;
+                }
+                let output = match pending.remove(&(line_idx, idx)) {
+                    Some(rx) => rx
+                        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                        .ok(),
+                    None => {
+                        let widget_config = self.widget_config(line_idx, wc);
+                        registry.render(&wc.widget_type, data, &widget_config)
+                    }
+                };
+                let Some(output) = output else {
+                    continue;
+                };
+                if !output.visible {
+                    continue;
+                }
+                let count = seen.entry(&wc.widget_type).or_default();
+                *count += 1;
+                let key = match *count {
+                    1 => wc.widget_type.clone(),
+                    n => format!("{}.{n}", wc.widget_type),
+                };
+                values.push((key, ansi::strip(&output.text)));
+            }
+        }
+        values
+    }
+
+    fn serialize(&self, lines: &[Vec<RenderedSegment>]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| to_ansi(line, self.renderer))
+            .collect()
+    }
+
+    fn render_lines(
+        &self,
+        data: &SessionData,
+        registry: &WidgetRegistry,
+        mut timings: Option<&mut Vec<WidgetTiming>>,
+        mut explained: Option<&mut Vec<WidgetExplanation>>,
+    ) -> Vec<Vec<RenderedSegment>> {
         let config = self.config;
-        let term_width = Self::terminal_width(config);
+        let term_width = self.available_width();
         let mut output_lines = Vec::new();
+        // Which output lines are `raw_mode` data rows, left unstyled.
+        let mut raw_lines = Vec::new();
+        let mut truncated = false;
+        // Visibility reasons are worked out for `--explain` and the debug log.
+        let tracing = explained.is_some() || crate::debug::enabled();
+
+        let started = Instant::now();
+        let deadline = started + Duration::from_millis(config.render_timeout_ms);
+        let mut pending = if config.render_timeout_ms > 0 {
+            self.spawn_blocking(data, registry)
+        } else {
+            HashMap::new()
+        };
 
-        for line_config in &config.lines {
+        for (line_idx, line_config) in config.lines.iter().enumerate() {
             if line_config.is_empty() {
                 continue;
             }
+            let options = config.line_options(line_idx);
+
+            // (index in line_config, explanation) for `--explain`; only
+            // filled in when asked for.
+            let mut report: Vec<(usize, WidgetExplanation)> = Vec::new();
+            let mut note = |idx: usize, wc: &crate::config::LineWidgetConfig, reason: String| {
+                if tracing {
+                    report.push((
+                        idx,
+                        WidgetExplanation {
+                            line: line_idx + 1,
+                            widget_type: wc.widget_type.clone(),
+                            visible: reason == "shown",
+                            reason,
+                        },
+                    ));
+                }
+            };
 
             let mut widgets: Vec<(WidgetOutput, &crate::config::LineWidgetConfig)> = Vec::new();
-            for wc in line_config {
-                let widget_config = Config::to_widget_config(wc);
-                if let Some(output) = registry.render(&wc.widget_type, data, &widget_config)
+            for (idx, wc) in line_config.iter().enumerate() {
+                // Powerline draws its own transitions between segments; an
+                // explicit separator would become a segment of its own.
+                if config.powerline.enabled
+                    && matches!(wc.widget_type.as_str(), "separator" | "group-divider")
+                {
+                    note(
+                        idx,
+                        wc,
+                        "skipped: powerline draws its own separators".into(),
+                    );
+                    continue;
+                }
+                // Likewise raw lines, which join fields with their delimiter.
+                if options.raw_mode
+                    && matches!(
+                        wc.widget_type.as_str(),
+                        "separator" | "group-divider" | "flex-separator"
+                    )
+                {
+                    note(idx, wc, "skipped: raw line uses raw_delimiter".into());
+                    continue;
+                }
+                if config.is_disabled(&wc.widget_type) {
+                    note(idx, wc, "disabled".into());
+                    continue;
+                }
+                if !model_allows(wc, data) {
+                    note(idx, wc, "hidden: not for this model".into());
+                    continue;
+                }
+                // Blocking widgets were all spawned at `started`, so their time
+                // includes any wait behind earlier widgets on the line.
+                let mut widget_start = started;
+                let spawned = pending.contains_key(&(line_idx, idx));
+                let output = match pending.remove(&(line_idx, idx)) {
+                    // Past the deadline this is a non-blocking poll, so a hung
+                    // widget is dropped and the rest of the line still renders.
+                    Some(rx) => rx
+                        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                        .ok(),
+                    None => {
+                        widget_start = Instant::now();
+                        let widget_config = self.widget_config(line_idx, wc);
+                        registry.render(&wc.widget_type, data, &widget_config)
+                    }
+                };
+                if let Some(timings) = timings.as_deref_mut() {
+                    timings.push(WidgetTiming {
+                        widget_type: wc.widget_type.clone(),
+                        elapsed: widget_start.elapsed(),
+                        visible: output.as_ref().is_some_and(|o| o.visible),
+                    });
+                }
+                if tracing {
+                    let reason = match &output {
+                        Some(o) if o.visible => "shown".into(),
+                        Some(_) => match registry.hidden_reason(
+                            &wc.widget_type,
+                            data,
+                            &self.widget_config(line_idx, wc),
+                        ) {
+                            Some(why) => format!("hidden: {why}"),
+                            None => "hidden".into(),
+                        },
+                        None if spawned => "timed out".into(),
+                        None => "unknown widget type".into(),
+                    };
+                    note(idx, wc, reason);
+                }
+                if let Some(mut output) = output
                     && output.visible
                 {
+                    if let Some(priority) = wc.priority {
+                        output.priority = priority;
+                    }
                     widgets.push((output, wc));
                 }
             }
 
-            if widgets.is_empty() {
+            let placeholder = options.placeholder.as_ref().or(config.placeholder.as_ref());
+            if widgets.is_empty() && placeholder.is_none() {
+                for (_, explanation) in report {
+                    log_explanation(&explanation);
+                    if let Some(explained) = explained.as_deref_mut() {
+                        explained.push(explanation);
+                    }
+                }
                 continue;
             }
 
-            let line = if config.powerline.enabled {
+            // Sorting happens after invisible widgets are dropped and before
+            // assembly, so overflow truncation cuts the lowest priorities.
+            // Flex separators stay put; each side is sorted on its own.
+            if options.sort == "priority" {
+                for side in widgets.split_mut(|(_, wc)| wc.widget_type == "flex-separator") {
+                    side.sort_by_key(|(output, _)| Reverse(output.priority));
+                }
+            }
+
+            if !options.raw_mode {
+                let compacted =
+                    self.compact_to_fit(&mut widgets, line_idx, data, registry, term_width);
+                for (idx, explanation) in &mut report {
+                    if explanation.visible && compacted.contains(idx) {
+                        explanation.reason = "shown: compact".into();
+                    }
+                }
+            }
+
+            let line = if widgets.is_empty() {
+                // Dim, so `trim_trailing` keeps even a blank placeholder.
+                vec![RenderedSegment {
+                    text: placeholder.cloned().unwrap_or_default(),
+                    dim: !options.raw_mode,
+                    ..RenderedSegment::default()
+                }]
+            } else if options.raw_mode {
+                self.assemble_raw_line(&widgets, &options.raw_delimiter)
+            } else if config.powerline.enabled {
                 self.assemble_powerline_line(&widgets, term_width)
             } else {
-                self.assemble_line(&widgets, term_width)
+                let pinned = widgets
+                    .iter()
+                    .any(|(_, wc)| wc.pin.as_deref() == Some("right"));
+                let mut line = if pinned {
+                    self.assemble_pinned_line(&widgets, term_width)
+                } else {
+                    self.assemble_line(&widgets, term_width)
+                };
+                if let Some(ref bg) = config.line_background {
+                    let bg = self.renderer.color(bg);
+                    for segment in &mut line {
+                        segment.bg.get_or_insert_with(|| bg.clone());
+                    }
+                }
+                line
             };
+            // Lines past `max_lines` are still rendered so `--explain` can
+            // say why they're missing.
+            let cut = config
+                .max_lines
+                .is_some_and(|max| output_lines.len() >= max);
+            for (idx, mut explanation) in report {
+                // Flex separators are fill, present even at zero width.
+                let placed = explanation.widget_type == "flex-separator"
+                    || line.iter().any(|segment| segment.widget == Some(idx));
+                if explanation.visible && cut {
+                    explanation.visible = false;
+                    explanation.reason = "dropped: max_lines".into();
+                } else if explanation.visible && !placed {
+                    explanation.visible = false;
+                    explanation.reason = "dropped: overflow".into();
+                }
+                log_explanation(&explanation);
+                if let Some(explained) = explained.as_deref_mut() {
+                    explained.push(explanation);
+                }
+            }
+            if cut {
+                truncated = true;
+                continue;
+            }
             output_lines.push(line);
+            raw_lines.push(options.raw_mode);
         }
 
-        if config.powerline.enabled && config.powerline.auto_align && output_lines.len() > 1 {
+        if truncated
+            && let Some(indicator) = &config.max_lines_indicator
+            && let (Some(last), Some(false)) = (output_lines.last_mut(), raw_lines.last())
+        {
+            last.push(RenderedSegment::plain(" "));
+            last.push(RenderedSegment {
+                text: indicator.clone(),
+                dim: true,
+                ..RenderedSegment::default()
+            });
+        }
+
+        if config.stale_after_secs > 0
+            && let Some(age) = data.transcript_age()
+            && age.as_secs() >= config.stale_after_secs
+        {
+            self.mark_stale(&mut output_lines, &raw_lines);
+        }
+
+        let aligning =
+            config.powerline.enabled && config.powerline.auto_align && output_lines.len() > 1;
+        if aligning {
             let max_display_width = output_lines
                 .iter()
-                .map(|l| UnicodeWidthStr::width(strip_ansi(l).as_str()))
+                .zip(&raw_lines)
+                .filter(|(_, raw)| !**raw)
+                .map(|(l, _)| line_width(l))
                 .max()
                 .unwrap_or(0);
 
-            for line in &mut output_lines {
-                let current_width = UnicodeWidthStr::width(strip_ansi(line).as_str());
+            for (line, _) in output_lines
+                .iter_mut()
+                .zip(&raw_lines)
+                .filter(|(_, raw)| !**raw)
+            {
+                let current_width = line_width(line);
                 if current_width < max_display_width {
                     let pad = max_display_width - current_width;
-                    line.push_str(&" ".repeat(pad));
+                    line.push(RenderedSegment::plain(" ".repeat(pad)));
                 }
             }
+        } else if config.trim_trailing {
+            for line in &mut output_lines {
+                trim_trailing_blank(line);
+            }
         }
 
         output_lines
     }
 
+    /// Show that the payload behind these lines is old: dim every line, or
+    /// append a dim "stale" marker to the first. Raw lines are left as data.
+    fn mark_stale(&self, lines: &mut [Vec<RenderedSegment>], raw_lines: &[bool]) {
+        let mut styled = lines
+            .iter_mut()
+            .zip(raw_lines)
+            .filter(|(_, raw)| !**raw)
+            .map(|(line, _)| line);
+        if self.config.stale_style == "dim" {
+            // Widget colors stay, dimmed.
+            for segment in styled.flatten() {
+                segment.dim = true;
+            }
+        } else if let Some(first) = styled.next() {
+            first.push(RenderedSegment::plain(" "));
+            first.push(RenderedSegment {
+                text: "stale".into(),
+                dim: true,
+                ..RenderedSegment::default()
+            });
+        }
+    }
+
     /// Resolve the foreground color for a widget using the priority chain:
     /// explicit config color > widget color_hint > theme role > None
     fn resolve_fg_color(
@@ -88,9 +482,11 @@ impl<'a> LayoutEngine<'a> {
         if let Some(ref color) = wc.color {
             return Some(color.clone());
         }
-        // 2. Widget color_hint (dynamic, e.g. context percentage)
+        // 2. Widget color_hint (dynamic, e.g. context percentage), in the
+        //    theme's color for that state when it has one
         if let Some(ref hint) = output.color_hint {
-            return Some(hint.clone());
+            let themed = self.theme.hint_color(&wc.widget_type, hint);
+            return Some(themed.unwrap_or(hint).to_string());
         }
         // 3. Theme role for this widget type
         if let Some(theme_color) = self.theme.role_for_widget(&wc.widget_type) {
@@ -103,7 +499,7 @@ impl<'a> LayoutEngine<'a> {
         &self,
         widgets: &[(WidgetOutput, &crate::config::LineWidgetConfig)],
         max_width: usize,
-    ) -> String {
+    ) -> Vec<RenderedSegment> {
         let config = self.config;
         let separator = &config.default_separator;
 
@@ -116,7 +512,7 @@ impl<'a> LayoutEngine<'a> {
             return self.assemble_line_with_flex(widgets, max_width);
         }
 
-        let mut parts: Vec<String> = Vec::new();
+        let mut parts: Vec<RenderedSegment> = Vec::new();
         let mut total_display_width = 0;
 
         for (i, (output, wc)) in widgets.iter().enumerate() {
@@ -127,7 +523,7 @@ impl<'a> LayoutEngine<'a> {
                 if total_display_width + sep_width + output.display_width > max_width {
                     break;
                 }
-                parts.push(separator.clone());
+                parts.push(self.styled_separator());
                 total_display_width += sep_width;
             }
 
@@ -136,20 +532,132 @@ impl<'a> LayoutEngine<'a> {
             }
 
             let padding = wc.padding.as_deref().unwrap_or(&config.default_padding);
-            let styled = self.apply_style(&output.text, wc, output);
-            parts.push(format!("{padding}{styled}{padding}"));
+            parts.push(RenderedSegment::plain(padding));
+            parts.push(self.apply_style(&output.text, wc, output));
+            parts.push(RenderedSegment::plain(padding));
             total_display_width += output.display_width + UnicodeWidthStr::width(padding) * 2;
         }
 
-        let result = parts.join("");
-        format!("{result}{}", self.renderer.reset())
+        parts
+    }
+
+    /// A line with `pin = "right"` widgets: the rest is assembled in the
+    /// width the pinned ones leave, then spaces run out to the right edge.
+    /// The first pinned widget sits at the edge; later ones stack leftward.
+    fn assemble_pinned_line(
+        &self,
+        widgets: &[(WidgetOutput, &crate::config::LineWidgetConfig)],
+        max_width: usize,
+    ) -> Vec<RenderedSegment> {
+        let (mut pinned, left): (Vec<_>, Vec<_>) = widgets
+            .iter()
+            .cloned()
+            .partition(|(_, wc)| wc.pin.as_deref() == Some("right"));
+        pinned.reverse();
+        let right = self.assemble_line(&pinned, max_width);
+        let right_width = line_width(&right);
+        // Keep a column between the two sides so they never touch.
+        let mut line = self.assemble_line(&left, max_width.saturating_sub(right_width + 1));
+        let gap = max_width.saturating_sub(line_width(&line) + right_width);
+        line.push(RenderedSegment::plain(" ".repeat(gap)));
+        line.extend(right);
+        line
+    }
+
+    /// Swap in compact forms (`Widget::render_compact`), lowest priority
+    /// first, until the line fits `max_width`, skipping widgets without one;
+    /// whatever still overflows is dropped at assembly. Blocking widgets keep
+    /// their output rather than render again. Returns the compacted widgets'
+    /// positions in their line.
+    fn compact_to_fit(
+        &self,
+        widgets: &mut [(WidgetOutput, &crate::config::LineWidgetConfig)],
+        line_idx: usize,
+        data: &SessionData,
+        registry: &WidgetRegistry,
+        max_width: usize,
+    ) -> Vec<usize> {
+        let mut compacted = Vec::new();
+        let mut order: Vec<usize> = (0..widgets.len()).collect();
+        order.sort_by_key(|&i| widgets[i].0.priority);
+        for i in order {
+            if self.line_demand(widgets) <= max_width {
+                break;
+            }
+            let wc = widgets[i].1;
+            if matches!(
+                wc.widget_type.as_str(),
+                "separator" | "group-divider" | "flex-separator"
+            ) || registry.is_blocking(&wc.widget_type)
+            {
+                continue;
+            }
+            let widget_config = self.widget_config(line_idx, wc);
+            let Some(mut compact) = registry.render_compact(&wc.widget_type, data, &widget_config)
+            else {
+                continue;
+            };
+            if compact.visible && compact.display_width < widgets[i].0.display_width {
+                compact.priority = widgets[i].0.priority;
+                widgets[i].0 = compact;
+                compacted.extend(self.config_position(wc));
+            }
+        }
+        compacted
+    }
+
+    /// Columns the line needs without flex fill: each widget with its
+    /// padding, plus separators between them.
+    fn line_demand(&self, widgets: &[(WidgetOutput, &crate::config::LineWidgetConfig)]) -> usize {
+        let config = self.config;
+        let separator = if config.powerline.enabled {
+            &config.powerline.separator
+        } else {
+            &config.default_separator
+        };
+        let mut width = 0;
+        for (i, (output, wc)) in widgets.iter().enumerate() {
+            if wc.widget_type == "flex-separator" {
+                continue;
+            }
+            let need_separator = i > 0
+                && !widgets[i - 1].1.merge_next
+                && widgets[i - 1].1.widget_type != "flex-separator";
+            if need_separator {
+                width += UnicodeWidthStr::width(separator.as_str());
+            }
+            let padding = wc.padding.as_deref().unwrap_or(&config.default_padding);
+            width += output.display_width + UnicodeWidthStr::width(padding) * 2;
+        }
+        width
+    }
+
+    /// A `raw_mode` line: each widget's text, unstyled and unpadded, joined
+    /// by `delimiter`.
+    fn assemble_raw_line(
+        &self,
+        widgets: &[(WidgetOutput, &crate::config::LineWidgetConfig)],
+        delimiter: &str,
+    ) -> Vec<RenderedSegment> {
+        let mut parts: Vec<RenderedSegment> = Vec::new();
+        for (output, wc) in widgets {
+            if !parts.is_empty() {
+                parts.push(RenderedSegment::plain(delimiter));
+            }
+            parts.push(RenderedSegment {
+                text: ansi::strip(&output.text),
+                widget: self.config_position(wc),
+                ..RenderedSegment::default()
+            });
+        }
+        parts
     }
 
     fn assemble_line_with_flex(
         &self,
         widgets: &[(WidgetOutput, &crate::config::LineWidgetConfig)],
         max_width: usize,
-    ) -> String {
+    ) -> Vec<RenderedSegment> {
         let config = self.config;
         let separator = &config.default_separator;
 
@@ -170,16 +678,16 @@ impl<'a> LayoutEngine<'a> {
         }
 
         let flex_width = max_width.saturating_sub(fixed_width);
+        let mut shares = flex_shares(widgets, flex_width).into_iter();
 
         // Second pass: build output
-        let mut parts: Vec<String> = Vec::new();
+        let mut parts: Vec<RenderedSegment> = Vec::new();
         for (i, (output, wc)) in widgets.iter().enumerate() {
             if wc.widget_type == "flex-separator" {
                 // output.text holds the fill character
                 let fill_char = &output.text;
-                let fill = fill_char.repeat(flex_width);
-                let styled = self.apply_style(&fill, wc, output);
-                parts.push(styled);
+                let fill = fill_char.repeat(shares.next().unwrap_or(0));
+                parts.push(self.apply_style(&fill, wc, output));
                 continue;
             }
 
@@ -187,25 +695,26 @@ impl<'a> LayoutEngine<'a> {
                 && !widgets[i - 1].1.merge_next
                 && widgets[i - 1].1.widget_type != "flex-separator";
             if need_separator {
-                parts.push(separator.clone());
+                parts.push(self.styled_separator());
             }
 
             let padding = wc.padding.as_deref().unwrap_or(&config.default_padding);
-            let styled = self.apply_style(&output.text, wc, output);
-            parts.push(format!("{padding}{styled}{padding}"));
+            parts.push(RenderedSegment::plain(padding));
+            parts.push(self.apply_style(&output.text, wc, output));
+            parts.push(RenderedSegment::plain(padding));
         }
 
-        let result = parts.join("");
-        format!("{result}{}", self.renderer.reset())
+        parts
     }
 
     fn assemble_powerline_line(
         &self,
         widgets: &[(WidgetOutput, &crate::config::LineWidgetConfig)],
         max_width: usize,
-    ) -> String {
+    ) -> Vec<RenderedSegment> {
         let config = self.config;
         let pl_sep = &config.powerline.separator;
+        let pl_rev = &config.powerline.separator_reverse;
         let default_bg = "black";
 
         // Check for flex-separator
@@ -223,7 +732,7 @@ impl<'a> LayoutEngine<'a> {
             widgets.iter().collect()
         };
 
-        let mut parts: Vec<String> = Vec::new();
+        let mut parts: Vec<RenderedSegment> = Vec::new();
         let mut total_display_width: usize = 0;
 
         // Start cap
@@ -232,13 +741,7 @@ impl<'a> LayoutEngine<'a> {
                 .first()
                 .and_then(|(_, wc)| wc.background_color.as_deref())
                 .unwrap_or(default_bg);
-            let bg_spec = Renderer::parse_color(first_bg);
-            parts.push(format!(
-                "{}{}{}",
-                self.renderer.fg(&bg_spec),
-                cap,
-                self.renderer.reset(),
-            ));
+            parts.push(self.transition(cap, first_bg, None));
             total_display_width += UnicodeWidthStr::width(cap.as_str());
         }
 
@@ -281,13 +784,7 @@ impl<'a> LayoutEngine<'a> {
                     .background_color
                     .as_deref()
                     .unwrap_or(default_bg);
-                let last_bg_spec = Renderer::parse_color(last_bg);
-                parts.push(format!(
-                    "{}{}{}",
-                    self.renderer.fg(&last_bg_spec),
-                    pl_sep,
-                    self.renderer.reset(),
-                ));
+                parts.push(self.transition(pl_sep, last_bg, None));
                 total_display_width += UnicodeWidthStr::width(pl_sep.as_str());
             }
 
@@ -302,13 +799,13 @@ impl<'a> LayoutEngine<'a> {
             }
             // Add start separator for right side
             if !right_widgets.is_empty() {
-                right_width += UnicodeWidthStr::width(pl_sep.as_str());
+                right_width += UnicodeWidthStr::width(pl_rev.as_str());
             }
 
             // Fill gap
             let fill_width = max_width.saturating_sub(total_display_width + right_width);
             if fill_width > 0 {
-                parts.push(" ".repeat(fill_width));
+                parts.push(RenderedSegment::plain(" ".repeat(fill_width)));
                 total_display_width += fill_width;
             }
 
@@ -319,14 +816,9 @@ impl<'a> LayoutEngine<'a> {
                     .first()
                     .and_then(|(_, wc)| wc.background_color.as_deref())
                     .unwrap_or(default_bg);
-                let first_bg_spec = Renderer::parse_color(first_bg);
-                parts.push(format!(
-                    "{}{}{}",
-                    self.renderer.fg(&first_bg_spec),
-                    "\u{E0B2}", // reverse powerline separator
-                    self.renderer.reset(),
-                ));
-                total_display_width += 1;
+                // Reverse powerline separator
+                parts.push(self.transition(pl_rev, first_bg, None));
+                total_display_width += UnicodeWidthStr::width(pl_rev.as_str());
 
                 self.render_powerline_segment(
                     &right_widgets,
@@ -355,23 +847,16 @@ impl<'a> LayoutEngine<'a> {
                 .last()
                 .and_then(|(_, wc)| wc.background_color.as_deref())
                 .unwrap_or(default_bg);
-            let last_bg_spec = Renderer::parse_color(last_bg);
-            parts.push(format!(
-                "{}{}{}",
-                self.renderer.fg(&last_bg_spec),
-                cap,
-                self.renderer.reset(),
-            ));
+            parts.push(self.transition(cap, last_bg, None));
         }
 
-        let result = parts.join("");
-        format!("{result}{}", self.renderer.reset())
+        parts
     }
 
     fn render_powerline_segment(
         &self,
         widgets: &[&(WidgetOutput, &crate::config::LineWidgetConfig)],
-        parts: &mut Vec<String>,
+        parts: &mut Vec<RenderedSegment>,
         total_display_width: &mut usize,
         max_width: usize,
         default_bg: &str,
@@ -381,7 +866,6 @@ impl<'a> LayoutEngine<'a> {
 
         for (i, (output, wc)) in widgets.iter().enumerate() {
             let this_bg = wc.background_color.as_deref().unwrap_or(default_bg);
-            let this_bg_spec = Renderer::parse_color(this_bg);
 
             if i > 0 && !widgets[i - 1].1.merge_next {
                 let prev_bg = widgets[i - 1]
@@ -389,20 +873,13 @@ impl<'a> LayoutEngine<'a> {
                     .background_color
                     .as_deref()
                     .unwrap_or(default_bg);
-                let prev_bg_spec = Renderer::parse_color(prev_bg);
 
                 let sep_width = UnicodeWidthStr::width(pl_sep.as_str());
                 if *total_display_width + sep_width + output.display_width > max_width {
                     break;
                 }
 
-                parts.push(format!(
-                    "{}{}{}{}",
-                    self.renderer.fg(&prev_bg_spec),
-                    self.renderer.bg(&this_bg_spec),
-                    pl_sep,
-                    self.renderer.reset(),
-                ));
+                parts.push(self.transition(pl_sep, prev_bg, Some(this_bg)));
                 *total_display_width += sep_width;
             }
 
@@ -411,100 +888,244 @@ impl<'a> LayoutEngine<'a> {
             }
 
             let padding = wc.padding.as_deref().unwrap_or(&config.default_padding);
-            let styled = self.apply_powerline_style(&output.text, wc, &this_bg_spec, output);
-            parts.push(styled);
+            let text = format!("{padding}{}{padding}", output.text);
+            let mut segment = self.apply_style(&text, wc, output);
+            // Always set background for powerline segments
+            segment.bg = Some(self.renderer.color(this_bg));
+            if config.powerline.auto_contrast {
+                self.ensure_contrast(&mut segment);
+            }
+            parts.push(segment);
 
             let padding_width = UnicodeWidthStr::width(padding) * 2;
             *total_display_width += output.display_width + padding_width;
         }
     }
 
-    fn apply_style(
-        &self,
-        text: &str,
-        wc: &crate::config::LineWidgetConfig,
-        output: &WidgetOutput,
-    ) -> String {
-        let config = self.config;
-        let mut styled = String::new();
-
-        if let Some(ref bg) = wc.background_color {
-            styled.push_str(&self.renderer.bg(&Renderer::parse_color(bg)));
+    /// Swap an unreadable foreground for black or white, whichever stands
+    /// out more. Only 256-color and truecolor output is adjusted: 16-color
+    /// codes take whatever RGB the terminal's palette gives them.
+    fn ensure_contrast(&self, segment: &mut RenderedSegment) {
+        if !matches!(
+            self.renderer.color_level,
+            ColorLevel::Color256 | ColorLevel::TrueColor
+        ) {
+            return;
         }
-
-        if let Some(fg) = self.resolve_fg_color(wc, output) {
-            styled.push_str(&self.renderer.fg(&Renderer::parse_color(&fg)));
+        let (Some(fg), Some(bg)) = (&segment.fg, &segment.bg) else {
+            return;
+        };
+        if fg.contrast(bg) >= MIN_CONTRAST {
+            return;
         }
+        let black = ColorSpec::Rgb(0, 0, 0);
+        let white = ColorSpec::Rgb(255, 255, 255);
+        segment.fg = Some(if black.contrast(bg) >= white.contrast(bg) {
+            black
+        } else {
+            white
+        });
+    }
 
-        if wc.bold.unwrap_or(config.global_bold) {
-            styled.push_str(self.renderer.bold());
+    /// A powerline glyph drawn in the color of the segment it leaves, over
+    /// the background of the one it enters (if any).
+    fn transition(&self, glyph: &str, from_bg: &str, to_bg: Option<&str>) -> RenderedSegment {
+        RenderedSegment {
+            text: self.icons.fallback_text(glyph),
+            fg: Some(self.renderer.color(from_bg)),
+            bg: to_bg.map(|bg| self.renderer.color(bg)),
+            ..RenderedSegment::default()
         }
+    }
 
-        styled.push_str(text);
-        styled.push_str(self.renderer.reset());
-        styled
+    /// The default separator, in the theme's `separator_fg` color if it has one.
+    fn styled_separator(&self) -> RenderedSegment {
+        RenderedSegment {
+            text: self.config.default_separator.clone(),
+            fg: self
+                .theme
+                .color("separator_fg")
+                .map(|color| self.renderer.color(color)),
+            ..RenderedSegment::default()
+        }
     }
 
-    fn apply_powerline_style(
+    fn apply_style(
         &self,
         text: &str,
         wc: &crate::config::LineWidgetConfig,
-        bg_spec: &crate::render::ColorSpec,
         output: &WidgetOutput,
-    ) -> String {
-        let config = self.config;
-        let padding = wc.padding.as_deref().unwrap_or(&config.default_padding);
-        let mut styled = String::new();
+    ) -> RenderedSegment {
+        let mut segment = RenderedSegment {
+            text: text.to_string(),
+            fg: self
+                .resolve_fg_color(wc, output)
+                .map(|fg| self.renderer.color(&fg)),
+            bg: wc
+                .background_color
+                .as_deref()
+                .map(|bg| self.renderer.color(bg)),
+            bold: wc.bold.unwrap_or(self.config.global_bold),
+            widget: self.config_position(wc),
+            ..RenderedSegment::default()
+        };
+        segment.set_attention(wc.attention.as_deref());
+        segment
+    }
 
-        // Always set background for powerline segments
-        styled.push_str(&self.renderer.bg(bg_spec));
+    /// The config `wc` renders with on line `line_idx`: raw lines force
+    /// every widget's raw value.
+    fn widget_config(
+        &self,
+        line_idx: usize,
+        wc: &crate::config::LineWidgetConfig,
+    ) -> crate::widgets::WidgetConfig {
+        let mut widget_config = self.config.widget_config(wc);
+        widget_config.raw_value |= self.config.line_options(line_idx).raw_mode;
+        widget_config
+    }
 
-        if let Some(fg) = self.resolve_fg_color(wc, output) {
-            styled.push_str(&self.renderer.fg(&Renderer::parse_color(&fg)));
-        }
+    /// Where `wc` sits in its line of `self.config`.
+    fn config_position(&self, wc: &crate::config::LineWidgetConfig) -> Option<usize> {
+        self.config
+            .lines
+            .iter()
+            .find_map(|line| line.iter().position(|w| std::ptr::eq(w, wc)))
+    }
 
-        if wc.bold.unwrap_or(config.global_bold) {
-            styled.push_str(self.renderer.bold());
+    /// Start every blocking widget up front so they run concurrently with each
+    /// other and with the inline ones, keyed by (line, position).
+    fn spawn_blocking(
+        &self,
+        data: &SessionData,
+        registry: &WidgetRegistry,
+    ) -> HashMap<(usize, usize), Receiver<WidgetOutput>> {
+        let mut pending = HashMap::new();
+        let mut shared: Option<Arc<SessionData>> = None;
+        for (line_idx, line_config) in self.config.lines.iter().enumerate() {
+            for (idx, wc) in line_config.iter().enumerate() {
+                if !registry.is_blocking(&wc.widget_type)
+                    || self.config.is_disabled(&wc.widget_type)
+                    || !model_allows(wc, data)
+                {
+                    continue;
+                }
+                let data = shared.get_or_insert_with(|| Arc::new(data.clone()));
+                let widget_config = self.widget_config(line_idx, wc);
+                if let Some(rx) =
+                    registry.spawn_render(&wc.widget_type, Arc::clone(data), widget_config)
+                {
+                    pending.insert((line_idx, idx), rx);
+                }
+            }
         }
-
-        styled.push_str(padding);
-        styled.push_str(text);
-        styled.push_str(padding);
-        styled.push_str(self.renderer.reset());
-        styled
+        pending
     }
 
-    fn terminal_width(config: &Config) -> usize {
-        let width = crossterm::terminal::size()
-            .map(|(w, _)| w as usize)
-            .unwrap_or(120);
+    /// Columns available to widgets under the configured flex mode. A width
+    /// of 0 (reported by some CI runners and pipes) counts as unknown.
+    fn available_width(&self) -> usize {
+        let width = self
+            .terminal_width
+            .or_else(|| crossterm::terminal::size().ok().map(|(w, _)| w as usize))
+            .filter(|&w| w > 0)
+            .unwrap_or(FALLBACK_WIDTH);
 
-        match config.flex_mode.as_str() {
+        let width = match self.config.flex_mode.as_str() {
             "full" => width,
             "full-minus-40" => width.saturating_sub(40),
             "compact" => 60,
             _ => width.saturating_sub(40),
-        }
+        };
+        width.max(MIN_WIDTH)
     }
 }
 
-/// Strip ANSI escape sequences from a string for display width calculation.
-fn strip_ansi(s: &str) -> String {
-    let mut out = String::with_capacity(s.len());
-    let mut in_escape = false;
-    for ch in s.chars() {
-        if in_escape {
-            if ch.is_ascii_alphabetic() {
-                in_escape = false;
-            }
-            continue;
-        }
-        if ch == '\x1b' {
-            in_escape = true;
-            continue;
+/// Whether the `only_for_models` / `hide_for_models` metadata lets `wc`
+/// render for the session's model. Each is a comma-separated list matched
+/// as case-insensitive substrings of the model id (the display name when
+/// there is no id), so "opus" covers every Opus release.
+fn model_allows(wc: &crate::config::LineWidgetConfig, data: &SessionData) -> bool {
+    let model = data
+        .model
+        .as_ref()
+        .and_then(|m| m.id.as_deref().or(m.display_name.as_deref()))
+        .unwrap_or_default()
+        .to_lowercase();
+    let matches = |list: &String| {
+        list.split(',')
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .any(|pattern| model.contains(&pattern.to_lowercase()))
+    };
+    wc.metadata.get("only_for_models").is_none_or(matches)
+        && !wc.metadata.get("hide_for_models").is_some_and(matches)
+}
+
+/// Split `width` columns of fill between a line's flex separators in
+/// proportion to their `weight` metadata (default 1, at most
+/// `MAX_FLEX_WEIGHT`). Columns left over from rounding go to the heaviest
+/// separator, the first on a tie.
+fn flex_shares(
+    widgets: &[(WidgetOutput, &crate::config::LineWidgetConfig)],
+    width: usize,
+) -> Vec<usize> {
+    let weights: Vec<usize> = widgets
+        .iter()
+        .filter(|(_, wc)| wc.widget_type == "flex-separator")
+        .map(|(_, wc)| {
+            wc.metadata
+                .get("weight")
+                .and_then(|w| w.trim().parse::<usize>().ok())
+                .map_or(1, |w| w.min(MAX_FLEX_WEIGHT))
+        })
+        .collect();
+    let total: usize = weights.iter().sum();
+    let mut shares: Vec<usize> = weights
+        .iter()
+        .map(|&w| (width * w).checked_div(total).unwrap_or(0))
+        .collect();
+    let heaviest = weights
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|&(_, w)| w)
+        .map(|(i, _)| i);
+    if let Some(i) = heaviest {
+        shares[i] += width - shares.iter().sum::<usize>();
+    }
+    shares
+}
+
+/// A widget's visibility decision, for the debug log.
+fn log_explanation(explanation: &WidgetExplanation) {
+    crate::debug_log!(
+        "line {}: {} {}",
+        explanation.line,
+        explanation.widget_type,
+        explanation.reason
+    );
+}
+
+/// Drop trailing spaces that sit outside any styling. Spaces inside a styled
+/// segment (a widget's padding on a background) are kept.
+fn trim_trailing_blank(line: &mut Vec<RenderedSegment>) {
+    while let Some(last) = line.last_mut()
+        && last.is_plain()
+        && !last.text.contains('\x1b')
+    {
+        let kept = last.text.trim_end_matches(' ').len();
+        last.text.truncate(kept);
+        if !last.text.is_empty() {
+            break;
         }
-        // Skip OSC sequences (\x1b]...\x07)
-        out.push(ch);
+        line.pop();
     }
-    out
+}
+
+/// Display width of a line of segments.
+fn line_width(line: &[RenderedSegment]) -> usize {
+    line.iter()
+        .map(|s| UnicodeWidthStr::width(ansi::strip(&s.text).as_str()))
+        .sum()
 }
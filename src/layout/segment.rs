@@ -0,0 +1,81 @@
+//! The layout engine's output before ANSI serialization: each line is a run
+//! of segments carrying their text and style.
+
+use crate::render::{ColorSpec, Renderer};
+
+/// A run of text drawn in one style. Plain segments (padding, fill, bare
+/// separators) have no colors or attributes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenderedSegment {
+    pub text: String,
+    pub fg: Option<ColorSpec>,
+    pub bg: Option<ColorSpec>,
+    pub bold: bool,
+    pub dim: bool,
+    pub blink: bool,
+    pub reverse: bool,
+    /// Position in its line's config of the widget this segment draws;
+    /// None for separators, padding and fill.
+    pub widget: Option<usize>,
+}
+
+impl RenderedSegment {
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_plain(&self) -> bool {
+        self.fg.is_none()
+            && self.bg.is_none()
+            && !self.bold
+            && !self.dim
+            && !self.blink
+            && !self.reverse
+    }
+
+    /// Set the attribute for a widget's `attention` mode ("blink" or "reverse").
+    pub(super) fn set_attention(&mut self, mode: Option<&str>) {
+        match mode {
+            Some("blink") => self.blink = true,
+            Some("reverse") => self.reverse = true,
+            _ => {}
+        }
+    }
+}
+
+/// Serialize one line of segments as ANSI. Styled segments reset after
+/// themselves, as do plain ones whose text carries its own escapes (custom
+/// command output, hyperlinks), so no style leaks into the next segment.
+pub fn to_ansi(segments: &[RenderedSegment], renderer: &Renderer) -> String {
+    let mut out = String::new();
+    for segment in segments {
+        if segment.is_plain() && !segment.text.contains('\x1b') {
+            out.push_str(&segment.text);
+            continue;
+        }
+        if segment.dim {
+            out.push_str(renderer.dim());
+        }
+        if let Some(ref bg) = segment.bg {
+            out.push_str(&renderer.bg(bg));
+        }
+        if let Some(ref fg) = segment.fg {
+            out.push_str(&renderer.fg(fg));
+        }
+        if segment.bold {
+            out.push_str(renderer.bold());
+        }
+        if segment.blink {
+            out.push_str(renderer.attention("blink"));
+        }
+        if segment.reverse {
+            out.push_str(renderer.attention("reverse"));
+        }
+        out.push_str(&segment.text);
+        out.push_str(renderer.reset());
+    }
+    out
+}
@@ -1,4 +1,9 @@
+// The config JSON Schema is one large `json!` literal.
+#![recursion_limit = "256"]
+
 pub mod config;
+pub mod debug;
+pub mod icons;
 pub mod layout;
 pub mod license;
 pub mod render;
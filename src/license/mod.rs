@@ -1,24 +1,58 @@
 mod storage;
 mod verify;
 
+use std::sync::OnceLock;
+
 pub use storage::LicenseStorage;
-pub use verify::{LicenseInfo, LicenseStatus, LicenseTier, LicenseValidator};
+pub use verify::{
+    DeactivationIntent, LicenseInfo, LicenseStatus, LicenseTier, LicenseValidator, days_left,
+    mask_key,
+};
 
 /// Check whether Pro features are currently available.
-/// Returns the license info if valid, None otherwise.
+/// Returns the license info if valid (or an unexpired trial), None otherwise.
 pub fn check_pro() -> Option<LicenseInfo> {
     let storage = LicenseStorage::new();
-    let key = storage.load_key()?;
     let validator = LicenseValidator::new();
-    let info = validator.validate(&key);
-    if info.status == LicenseStatus::Valid {
-        Some(info)
-    } else {
-        None
+    if let Some(key) = storage.load_key() {
+        let info = validator.validate(&key);
+        if info.status == LicenseStatus::Valid {
+            return Some(info);
+        }
     }
+    validator.trial()
 }
 
 /// Returns true if Pro features should be enabled.
+///
+/// Checked once per process: every Pro widget asks, and each check reads
+/// the license files.
 pub fn is_pro() -> bool {
-    check_pro().is_some()
+    static PRO: OnceLock<bool> = OnceLock::new();
+    *PRO.get_or_init(|| check_pro().is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storage::READS;
+
+    #[test]
+    fn test_pro_check_runs_once() {
+        let reads = || READS.with(|reads| reads.get());
+
+        // Storage is an empty scratch dir under test, so this is never Pro.
+        // Another test may have made the first call; either way, later
+        // calls read nothing.
+        assert!(!is_pro());
+        let after_first = reads();
+        for _ in 0..4 {
+            assert!(!is_pro());
+        }
+        assert_eq!(reads(), after_first);
+
+        // The counter does see a check that goes to storage.
+        check_pro();
+        assert!(reads() > after_first);
+    }
 }
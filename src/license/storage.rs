@@ -1,32 +1,68 @@
 use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use super::verify::ValidationCache;
+use super::verify::{DeactivationIntent, TrialRecord, ValidationCache};
 
 const LICENSE_DIR: &str = "claude-status";
 const LICENSE_FILE: &str = "license.key";
 const CACHE_FILE: &str = "license-cache.json";
+const PENDING_DEACTIVATION_FILE: &str = "pending-deactivation.json";
+const TRIAL_FILE: &str = "trial.json";
+
+#[cfg(test)]
+thread_local! {
+    /// License file reads made on this thread, so tests can check that Pro
+    /// status isn't re-read per widget.
+    pub(crate) static READS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+fn note_read() {
+    #[cfg(test)]
+    READS.with(|reads| reads.set(reads.get() + 1));
+}
 
 pub struct LicenseStorage {
     base_dir: PathBuf,
+    /// Where the second copy of the trial record lives, so removing the
+    /// license directory alone doesn't make the trial available again. The
+    /// platform data dir, ignoring `CLAUDE_STATUS_DATA_DIR`, so pointing that
+    /// at an empty dir doesn't either.
+    state_dir: PathBuf,
 }
 
 impl LicenseStorage {
     pub fn new() -> Self {
-        let base_dir = Self::default_dir();
-        Self { base_dir }
+        let (base_dir, state_dir) = Self::default_dirs();
+        Self {
+            base_dir,
+            state_dir,
+        }
     }
 
     #[cfg(test)]
     pub fn with_dir(dir: PathBuf) -> Self {
-        Self { base_dir: dir }
+        Self {
+            state_dir: dir.clone(),
+            base_dir: dir,
+        }
     }
 
-    fn default_dir() -> PathBuf {
-        dirs::config_dir()
+    #[cfg(not(test))]
+    fn default_dirs() -> (PathBuf, PathBuf) {
+        let base_dir = dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from(".config"))
-            .join(LICENSE_DIR)
+            .join(LICENSE_DIR);
+        (base_dir, crate::storage::data_dir_from(None))
+    }
+
+    /// Unit tests never see the developer's own license: `new` reads an
+    /// empty per-process scratch dir instead.
+    #[cfg(test)]
+    fn default_dirs() -> (PathBuf, PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("claude-status-license-{}", std::process::id()));
+        (dir.clone(), dir)
     }
 
     fn ensure_dir(&self) -> io::Result<()> {
@@ -41,8 +77,21 @@ impl LicenseStorage {
         self.base_dir.join(CACHE_FILE)
     }
 
+    fn trial_path(&self) -> PathBuf {
+        self.base_dir.join(TRIAL_FILE)
+    }
+
+    fn trial_marker_path(&self, machine_id: &str) -> PathBuf {
+        self.state_dir.join(format!(".trial-{machine_id}"))
+    }
+
+    fn pending_deactivation_path(&self) -> PathBuf {
+        self.base_dir.join(PENDING_DEACTIVATION_FILE)
+    }
+
     /// Load the stored license key, if any.
     pub fn load_key(&self) -> Option<String> {
+        note_read();
         fs::read_to_string(self.key_path())
             .ok()
             .map(|s| s.trim().to_string())
@@ -52,18 +101,7 @@ impl LicenseStorage {
     /// Save a license key to disk.
     pub fn save_key(&self, key: &str) -> io::Result<()> {
         self.ensure_dir()?;
-        let path = self.key_path();
-        fs::write(&path, key.trim())?;
-
-        // Set restrictive permissions on Unix
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let perms = fs::Permissions::from_mode(0o600);
-            fs::set_permissions(&path, perms)?;
-        }
-
-        Ok(())
+        write_private(&self.key_path(), key.trim())
     }
 
     /// Remove the stored license key.
@@ -77,6 +115,7 @@ impl LicenseStorage {
 
     /// Load the cached validation result.
     pub fn load_cache(&self) -> Option<ValidationCache> {
+        note_read();
         let data = fs::read_to_string(self.cache_path()).ok()?;
         serde_json::from_str(&data).ok()
     }
@@ -93,6 +132,56 @@ impl LicenseStorage {
     pub fn remove_cache(&self) {
         let _ = fs::remove_file(self.cache_path());
     }
+
+    /// Load this machine's trial record, from `trial.json` or its copy in
+    /// the data directory. It is kept after expiry (and across
+    /// deactivate/transfer) so a trial can only be started once; records
+    /// copied from another machine are ignored.
+    pub fn load_trial(&self, machine_id: &str) -> Option<TrialRecord> {
+        note_read();
+        [self.trial_path(), self.trial_marker_path(machine_id)]
+            .iter()
+            .filter_map(|path| fs::read_to_string(path).ok())
+            .filter_map(|data| serde_json::from_str::<TrialRecord>(&data).ok())
+            .find(|trial| trial.machine_id == machine_id)
+    }
+
+    /// Save the trial record to both places `load_trial` looks.
+    pub fn save_trial(&self, trial: &TrialRecord) -> io::Result<()> {
+        self.ensure_dir()?;
+        fs::create_dir_all(&self.state_dir)?;
+        let json = serde_json::to_string_pretty(trial).map_err(io::Error::other)?;
+        write_private(&self.trial_path(), &json)?;
+        write_private(&self.trial_marker_path(&trial.machine_id), &json)
+    }
+
+    /// Record a deactivation to send to the license server once online
+    /// validation is available. Holds the key, so it gets the key's permissions.
+    pub fn save_pending_deactivation(&self, intent: &DeactivationIntent) -> io::Result<()> {
+        self.ensure_dir()?;
+        let json = serde_json::to_string_pretty(intent).map_err(io::Error::other)?;
+        write_private(&self.pending_deactivation_path(), &json)
+    }
+
+    /// Load a recorded deactivation that hasn't been sent yet.
+    pub fn load_pending_deactivation(&self) -> Option<DeactivationIntent> {
+        let data = fs::read_to_string(self.pending_deactivation_path()).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+}
+
+fn write_private(path: &Path, contents: &str) -> io::Result<()> {
+    fs::write(path, contents)?;
+
+    // Set restrictive permissions on Unix
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let perms = fs::Permissions::from_mode(0o600);
+        fs::set_permissions(path, perms)?;
+    }
+
+    Ok(())
 }
 
 impl Default for LicenseStorage {
@@ -190,4 +279,53 @@ mod tests {
 
         let _ = fs::remove_dir_all(&dir);
     }
+
+    #[test]
+    fn test_save_and_load_pending_deactivation() {
+        let dir = std::env::temp_dir().join(format!("claude-status-test-pending-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let storage = LicenseStorage::with_dir(dir.clone());
+        assert!(storage.load_pending_deactivation().is_none());
+
+        let intent = DeactivationIntent {
+            key: "CS-PRO-AAAA-BBBB-CCCC-DDDD".to_string(),
+            machine_id: "test123".to_string(),
+            requested_at: Utc::now(),
+        };
+        storage.save_pending_deactivation(&intent).unwrap();
+        let loaded = storage.load_pending_deactivation().unwrap();
+        assert_eq!(loaded.key, intent.key);
+        assert_eq!(loaded.machine_id, "test123");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_trial_survives_removing_trial_json() {
+        let dir = std::env::temp_dir().join(format!("claude-status-test-trial-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let storage = LicenseStorage::with_dir(dir.clone());
+        assert!(storage.load_trial("m1").is_none());
+
+        let trial = TrialRecord::new("m1".to_string(), Utc::now());
+        storage.save_trial(&trial).unwrap();
+        assert!(storage.load_trial("m2").is_none());
+
+        fs::remove_file(dir.join(TRIAL_FILE)).unwrap();
+        let loaded = storage.load_trial("m1").unwrap();
+        assert_eq!(loaded.expires, trial.expires);
+        assert!(loaded.is_authentic());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(storage.trial_marker_path("m1"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
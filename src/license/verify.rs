@@ -15,6 +15,13 @@ const OFFLINE_GRACE_DAYS: i64 = 7;
 /// How often to re-validate with the server (hours)
 const REVALIDATION_HOURS: i64 = 24;
 
+/// Length of the one-time Pro trial
+const TRIAL_DAYS: i64 = 7;
+
+/// Key for the trial record's HMAC. It ships in the binary, so it only
+/// stops hand edits: a record whose dates were changed no longer verifies.
+const TRIAL_SIGNING_KEY: &[u8] = b"claude-status trial record v1";
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum LicenseTier {
     Free,
@@ -39,6 +46,9 @@ pub struct LicenseInfo {
     pub features: Vec<String>,
     pub last_validated: Option<DateTime<Utc>>,
     pub machine_id: String,
+    /// A keyless Pro trial rather than an activated license.
+    #[serde(default)]
+    pub trial: bool,
 }
 
 /// Cached validation result stored locally
@@ -51,6 +61,54 @@ pub struct ValidationCache {
     pub validated_at: DateTime<Utc>,
 }
 
+/// The one-time Pro trial, bound to the machine it was started on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrialRecord {
+    pub machine_id: String,
+    pub started_at: DateTime<Utc>,
+    pub expires: DateTime<Utc>,
+    /// HMAC-SHA256 over the fields above, hex.
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl TrialRecord {
+    /// A signed trial for `machine_id` starting at `now`.
+    pub fn new(machine_id: String, now: DateTime<Utc>) -> Self {
+        let mut trial = Self {
+            machine_id,
+            started_at: now,
+            expires: now + Duration::days(TRIAL_DAYS),
+            signature: String::new(),
+        };
+        trial.signature = trial.expected_signature();
+        trial
+    }
+
+    /// Whether the record is as `new` wrote it.
+    pub fn is_authentic(&self) -> bool {
+        self.signature == self.expected_signature()
+    }
+
+    fn expected_signature(&self) -> String {
+        let message = format!(
+            "{}|{}|{}",
+            self.machine_id,
+            self.started_at.timestamp(),
+            self.expires.timestamp()
+        );
+        hex::encode(hmac_sha256(TRIAL_SIGNING_KEY, message.as_bytes()))
+    }
+}
+
+/// A license released from this machine, kept until the server is told.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeactivationIntent {
+    pub key: String,
+    pub machine_id: String,
+    pub requested_at: DateTime<Utc>,
+}
+
 pub struct LicenseValidator {
     storage: LicenseStorage,
 }
@@ -77,6 +135,7 @@ impl LicenseValidator {
                 features: vec![],
                 last_validated: None,
                 machine_id,
+                trial: false,
             };
         }
 
@@ -94,6 +153,7 @@ impl LicenseValidator {
                     features: cache.features,
                     last_validated: Some(cache.validated_at),
                     machine_id,
+                    trial: false,
                 };
             }
 
@@ -107,6 +167,7 @@ impl LicenseValidator {
                     features: cache.features,
                     last_validated: Some(cache.validated_at),
                     machine_id,
+                    trial: false,
                 };
             }
 
@@ -154,6 +215,7 @@ impl LicenseValidator {
             features: pro_features(),
             last_validated: Some(Utc::now()),
             machine_id,
+            trial: false,
         })
     }
 
@@ -166,6 +228,50 @@ impl LicenseValidator {
         Ok(())
     }
 
+    /// Start the one-time Pro trial. Fails if a trial was ever started here.
+    pub fn start_trial(&self) -> Result<LicenseInfo, String> {
+        if self.trial_used() {
+            return Err("The Pro trial has already been used on this machine".to_string());
+        }
+        let now = Utc::now();
+        let trial = TrialRecord::new(self.machine_id(), now);
+        self.storage
+            .save_trial(&trial)
+            .map_err(|e| format!("Failed to save trial: {e}"))?;
+        trial_info(trial, now).ok_or_else(|| "Trial expired immediately".to_string())
+    }
+
+    /// The active trial, if one was started here, hasn't expired and wasn't
+    /// edited.
+    pub fn trial(&self) -> Option<LicenseInfo> {
+        trial_info(self.storage.load_trial(&self.machine_id())?, Utc::now())
+    }
+
+    /// Whether a trial was ever started on this machine, counting records
+    /// that fail verification.
+    pub fn trial_used(&self) -> bool {
+        self.storage.load_trial(&self.machine_id()).is_some()
+    }
+
+    /// Release the license from this machine so it can be activated elsewhere:
+    /// deactivate locally (key and machine-bound cache) and record the intent
+    /// for the server.
+    pub fn transfer(&self) -> Result<(), String> {
+        let key = self
+            .storage
+            .load_key()
+            .ok_or_else(|| "No license is active on this machine".to_string())?;
+        let intent = DeactivationIntent {
+            key,
+            machine_id: self.machine_id(),
+            requested_at: Utc::now(),
+        };
+        self.storage
+            .save_pending_deactivation(&intent)
+            .map_err(|e| format!("Failed to record deactivation: {e}"))?;
+        self.deactivate()
+    }
+
     /// Validate license key format: CS-PRO-XXXX-XXXX-XXXX-XXXX
     pub fn validate_format(key: &str) -> bool {
         let key = key.trim();
@@ -198,6 +304,7 @@ impl LicenseValidator {
                 features: pro_features(),
                 last_validated: None,
                 machine_id: machine_id.to_string(),
+                trial: false,
             }
         } else {
             LicenseInfo {
@@ -208,6 +315,7 @@ impl LicenseValidator {
                 features: vec![],
                 last_validated: None,
                 machine_id: machine_id.to_string(),
+                trial: false,
             }
         }
     }
@@ -336,6 +444,78 @@ pub fn generate_key() -> String {
     format!("CS-PRO-{seg1}-{seg2}-{seg3}-{seg4}")
 }
 
+/// HMAC-SHA256 (RFC 2104).
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK_LEN: usize = 64;
+    let mut block = [0u8; BLOCK_LEN];
+    if key.len() > BLOCK_LEN {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let pad = |byte: u8| block.map(|b| b ^ byte);
+    let inner = Sha256::new()
+        .chain_update(pad(0x36))
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+/// A trial has no key; it is valid Pro until `expires`, if its record
+/// verifies.
+fn trial_info(trial: TrialRecord, now: DateTime<Utc>) -> Option<LicenseInfo> {
+    if trial.expires <= now || !trial.is_authentic() {
+        return None;
+    }
+    Some(LicenseInfo {
+        tier: LicenseTier::Pro,
+        status: LicenseStatus::Valid,
+        key: String::new(),
+        expires: Some(trial.expires),
+        features: pro_features(),
+        last_validated: Some(trial.started_at),
+        machine_id: trial.machine_id,
+        trial: true,
+    })
+}
+
+/// Whole days left before `expires`, rounding a partial day up.
+pub fn days_left(expires: DateTime<Utc>) -> i64 {
+    let hours = (expires - Utc::now()).num_hours().max(0);
+    (hours + 23) / 24
+}
+
+/// Mask a key for display, keeping the "CS-PRO-XXXX" prefix and last four chars.
+/// Keys too short for those to stay apart are masked whole.
+pub fn mask_key(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() <= 15 {
+        return "*".repeat(chars.len());
+    }
+    let head: String = chars.iter().take(11).collect();
+    let tail: String = chars[chars.len().saturating_sub(4)..].iter().collect();
+    format!("{head}...{tail}")
+}
+
+impl LicenseInfo {
+    /// Whether this is the keyless Pro trial.
+    pub fn is_trial(&self) -> bool {
+        self.trial
+    }
+
+    /// A copy with the key masked, safe to print or hand to other tools.
+    pub fn masked(&self) -> Self {
+        Self {
+            key: mask_key(&self.key),
+            ..self.clone()
+        }
+    }
+}
+
 fn pro_features() -> Vec<String> {
     vec![
         "cost_tracking".to_string(),
@@ -438,10 +618,69 @@ mod tests {
             features: pro_features(),
             last_validated: Some(Utc::now()),
             machine_id: "test123".to_string(),
+            trial: false,
         };
         let json = serde_json::to_string(&info).unwrap();
         let deserialized: LicenseInfo = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized.tier, LicenseTier::Pro);
         assert_eq!(deserialized.status, LicenseStatus::Valid);
     }
+
+    #[test]
+    fn test_mask_key() {
+        assert_eq!(mask_key("CS-PRO-AAAA-BBBB-CCCC-DDDD"), "CS-PRO-AAAA...DDDD");
+        assert_eq!(mask_key("abc"), "***");
+        assert_eq!(mask_key("CS-PRO-AAAA-BBB"), "*".repeat(15));
+    }
+
+    #[test]
+    fn test_trial_info_honors_expiry() {
+        let now = Utc::now();
+        let trial = TrialRecord::new("m".into(), now);
+        let info = trial_info(trial.clone(), now).unwrap();
+        assert_eq!(info.tier, LicenseTier::Pro);
+        assert_eq!(info.status, LicenseStatus::Valid);
+        assert!(info.is_trial());
+
+        let later = now + Duration::days(TRIAL_DAYS) + Duration::seconds(1);
+        assert!(trial_info(trial, later).is_none());
+    }
+
+    #[test]
+    fn test_edited_trial_record_is_rejected() {
+        let now = Utc::now();
+        let trial = TrialRecord::new("m".into(), now);
+        assert!(trial.is_authentic());
+
+        let mut extended = trial.clone();
+        extended.expires += Duration::days(365);
+        assert!(!extended.is_authentic());
+        assert!(trial_info(extended, now).is_none());
+
+        let mut moved = trial.clone();
+        moved.machine_id = "other".into();
+        assert!(trial_info(moved, now).is_none());
+
+        let unsigned = TrialRecord {
+            signature: String::new(),
+            ..trial
+        };
+        assert!(trial_info(unsigned, now).is_none());
+    }
+
+    #[test]
+    fn test_hmac_sha256_matches_rfc_4231() {
+        assert_eq!(
+            hex::encode(hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Keys longer than a block are hashed first.
+        assert_eq!(
+            hex::encode(hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
 }
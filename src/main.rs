@@ -1,14 +1,18 @@
-use std::io::{self, Read};
+use std::io::{self, IsTerminal, Read, Write};
 use std::process;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
 
 use clap::Parser;
 
 mod cli;
 
 use claude_status::config::Config;
-use claude_status::layout::LayoutEngine;
-use claude_status::render::Renderer;
-use claude_status::widgets::{SessionData, WidgetRegistry};
+use claude_status::layout::{LayoutEngine, WidgetExplanation, WidgetTiming};
+use claude_status::render::{self, Renderer};
+use claude_status::themes::Theme;
+use claude_status::widgets::{RenderCache, SessionData, WidgetRegistry};
 
 #[derive(Parser)]
 #[command(
@@ -24,38 +28,282 @@ struct Cli {
     #[arg(long)]
     config: Option<String>,
 
-    /// Color level override: auto, none, 16, 256, truecolor
-    #[arg(long, default_value = "auto")]
+    /// Render with the named `[profiles.<name>]` (overrides CLAUDE_STATUS_PROFILE)
+    #[arg(long, value_name = "NAME")]
+    config_profile: Option<String>,
+
+    /// Color level override: auto, none, 16, 256, truecolor (or 16m)
+    #[arg(
+        long,
+        default_value = "auto",
+        value_parser = ["auto", "none", "16", "256", "truecolor", "16m"]
+    )]
     color_level: String,
+
+    /// Render with this theme instead of the configured one (not saved)
+    #[arg(
+        long,
+        value_name = "NAME",
+        value_parser = clap::builder::PossibleValuesParser::new(Theme::list())
+    )]
+    theme: Option<String>,
+
+    /// Don't read or write the local cost history database
+    #[arg(long)]
+    no_history: bool,
+
+    /// Print per-widget render times to stderr after the status line
+    #[arg(long)]
+    profile: bool,
+
+    /// Instead of the status line, print why each widget is shown or hidden
+    #[arg(long)]
+    explain: bool,
+
+    /// Render only the Nth configured line (1-indexed)
+    #[arg(long, value_name = "N")]
+    line: Option<usize>,
+
+    /// Collapse redundant color codes in the output (same display, fewer bytes)
+    #[arg(long)]
+    minify: bool,
+
+    /// Output format: the status line, a Starship custom module (lines joined
+    /// by spaces, no trailing newline), or one `widget=value` line per widget
+    #[arg(
+        long,
+        default_value = "line",
+        value_parser = ["line", "starship", "kv"]
+    )]
+    format: String,
+
+    /// Exit non-zero on unreadable or malformed input instead of rendering a fallback line
+    #[arg(long)]
+    strict: bool,
+
+    /// Treat empty stdin as bad input instead of rendering an empty session
+    #[arg(long)]
+    require_input: bool,
+
+    /// Give up on stdin after this many milliseconds and render the fallback
+    /// line (0 waits forever)
+    #[arg(long, value_name = "MS", default_value_t = 2000)]
+    stdin_timeout: u64,
 }
 
 fn main() {
     let cli = Cli::parse();
 
     match cli.command {
-        Some(cmd) => cli::handle_command(cmd),
+        Some(cmd) => cli::handle_command(
+            cmd,
+            cli.config.as_deref(),
+            cli.config_profile.as_deref(),
+            cli.no_history,
+            &cli.color_level,
+        ),
         None => render_statusline(&cli),
     }
 }
 
 fn render_statusline(cli: &Cli) {
-    let mut input = String::new();
-    if io::stdin().read_to_string(&mut input).is_err() {
-        process::exit(1);
-    }
+    // Starship embeds the output in a prompt, where NO_COLOR beats any
+    // explicit color level.
+    let renderer = if cli.format == "starship" && std::env::var_os("NO_COLOR").is_some() {
+        Renderer::detect("none")
+    } else {
+        Renderer::detect(&cli.color_level)
+    };
+
+    // A terminal on stdin means nobody is piping a session in; don't wait
+    // for one.
+    let input = if io::stdin().is_terminal() {
+        String::new()
+    } else {
+        match read_stdin(cli.stdin_timeout) {
+            Some(Ok(input)) => input,
+            Some(Err(_)) => render_fallback(cli, &renderer, "unreadable input"),
+            None => render_fallback(cli, &renderer, "input timed out"),
+        }
+    };
 
-    let data: SessionData = match serde_json::from_str(&input) {
-        Ok(d) => d,
-        Err(_) => process::exit(1),
+    // No input renders the config against an empty session, e.g. to check
+    // a config without piping JSON.
+    let data: SessionData = if input.trim().is_empty() {
+        if cli.require_input {
+            render_fallback(cli, &renderer, "no input");
+        }
+        SessionData::default()
+    } else {
+        match serde_json::from_str(&input) {
+            Ok(d) => d,
+            Err(_) => render_fallback(cli, &renderer, "bad input"),
+        }
     };
 
-    let config = Config::load(cli.config.as_deref());
-    let renderer = Renderer::detect(&cli.color_level);
-    let registry = WidgetRegistry::new();
+    let mut config =
+        Config::load_with_profile(cli.config.as_deref(), cli.config_profile.as_deref());
+    if cli.no_history {
+        config.stats.history = false;
+    }
+    if let Some(theme) = &cli.theme {
+        config.theme = theme.clone();
+    }
+    if let Some(n) = cli.line
+        && !config.select_line(n)
+    {
+        eprintln!(
+            "claude-status: --line {n} is out of range (config has {} lines)",
+            config.lines.len()
+        );
+        process::exit(1);
+    }
+    let registry = WidgetRegistry::new()
+        .with_cache(RenderCache::open())
+        .with_min_interval(Duration::from_millis(config.min_interval_ms));
+    if !cli.explain {
+        record_history(&data, &config, &registry);
+    }
+    let renderer = renderer.with_palette(config.palette.clone());
     let engine = LayoutEngine::new(&config, &renderer);
 
-    let lines = engine.render(&data, &config, &registry);
-    for line in &lines {
+    if cli.explain {
+        print_explanation(&engine.explain(&data, &config, &registry));
+        return;
+    }
+
+    if cli.format == "kv" {
+        for (key, value) in engine.values(&data, &config, &registry) {
+            println!("{key}={value}");
+        }
+        return;
+    }
+
+    if !cli.profile {
+        let lines = engine.render(&data, &config, &registry);
+        print_lines(cli, &lines);
+        return;
+    }
+
+    let (lines, timings) = engine.render_profiled(&data, &config, &registry);
+    print_lines(cli, &lines);
+    print_profile(&timings);
+}
+
+/// Save the payload's plan limits for `stats` and, when a configured widget
+/// reads the cost history, record the session there. Both are Pro-only. The
+/// writes run on their own thread and are waited for only until the render
+/// deadline, so a busy history DB can't hold up the status line.
+fn record_history(data: &SessionData, config: &Config, registry: &WidgetRegistry) {
+    if !config.stats.history || !claude_status::license::is_pro() {
+        return;
+    }
+    let limits = data.limits.clone();
+    let data = config
+        .lines
+        .iter()
+        .flatten()
+        .any(|wc| registry.reads_history(&wc.widget_type))
+        .then(|| data.clone());
+    if limits.is_none() && data.is_none() {
+        return;
+    }
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        if let Some(limits) = limits {
+            limits.save();
+        }
+        if let Some(data) = data {
+            data.record_cost();
+        }
+        let _ = tx.send(());
+    });
+    if config.render_timeout_ms == 0 {
+        let _ = rx.recv();
+    } else {
+        let _ = rx.recv_timeout(Duration::from_millis(config.render_timeout_ms));
+    }
+}
+
+/// Read all of stdin, giving up after `timeout_ms` (0 for no limit) so a
+/// host that opens the pipe and never closes it can't leave us hanging.
+/// None on timeout; the reader thread dies with the process.
+fn read_stdin(timeout_ms: u64) -> Option<io::Result<String>> {
+    let read = || {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input).map(|_| input)
+    };
+    if timeout_ms == 0 {
+        return Some(read());
+    }
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(read());
+    });
+    rx.recv_timeout(Duration::from_millis(timeout_ms)).ok()
+}
+
+fn print_lines(cli: &Cli, lines: &[String]) {
+    let lines: Vec<String> = if cli.minify {
+        lines.iter().map(|line| render::minify(line)).collect()
+    } else {
+        lines.to_vec()
+    };
+    if cli.format == "starship" {
+        // Starship adds its own spacing and newline.
+        print!("{}", lines.join(" "));
+        let _ = io::stdout().flush();
+        return;
+    }
+    for line in lines {
         println!("{line}");
     }
 }
+
+fn print_profile(timings: &[WidgetTiming]) {
+    let width = timings
+        .iter()
+        .map(|t| t.widget_type.len())
+        .max()
+        .unwrap_or(0)
+        .max("widget".len());
+    eprintln!("{:<width$}  {:>10}  visible", "widget", "µs");
+    for t in timings {
+        eprintln!(
+            "{:<width$}  {:>10}  {}",
+            t.widget_type,
+            t.elapsed.as_micros(),
+            if t.visible { "yes" } else { "no" }
+        );
+    }
+}
+
+fn print_explanation(explained: &[WidgetExplanation]) {
+    let width = explained
+        .iter()
+        .map(|e| e.widget_type.len())
+        .max()
+        .unwrap_or(0)
+        .max("widget".len());
+    println!("line  {:<width$}  status", "widget");
+    for e in explained {
+        println!("{:>4}  {:<width$}  {}", e.line, e.widget_type, e.reason);
+    }
+}
+
+/// Print a dim placeholder so a broken payload is visible in the host UI
+/// rather than leaving the status line blank.
+fn render_fallback(cli: &Cli, renderer: &Renderer, reason: &str) -> ! {
+    if cli.strict {
+        process::exit(1);
+    }
+    let line = format!(
+        "{}claude-status: {reason}{}",
+        renderer.dim(),
+        renderer.reset()
+    );
+    if writeln!(io::stdout(), "{line}").is_err() {
+        process::exit(1);
+    }
+    process::exit(0);
+}
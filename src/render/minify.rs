@@ -0,0 +1,234 @@
+//! `--minify`: collapse redundant SGR sequences in a rendered line.
+//!
+//! The layout engine resets after every widget and restates styles freely.
+//! This pass tracks the style each piece of text is drawn in and emits only
+//! the transitions between them, so the line displays identically with
+//! fewer bytes. Lines are assumed to start from the default style, as the
+//! layout engine's output does.
+
+/// The SGR state this pass models: the attributes and colors the renderer
+/// emits. Colors are kept as their parameter strings ("31", "38;5;208").
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Style {
+    bold: bool,
+    dim: bool,
+    blink: bool,
+    reverse: bool,
+    fg: Option<String>,
+    bg: Option<String>,
+}
+
+impl Style {
+    /// Apply one SGR parameter list. Returns None for codes this pass
+    /// doesn't model.
+    fn apply(&mut self, params: &str) -> Option<()> {
+        let codes: Vec<&str> = params.split(';').collect();
+        let mut i = 0;
+        while i < codes.len() {
+            let code: u16 = match codes[i] {
+                "" => 0,
+                c => c.parse().ok()?,
+            };
+            match code {
+                0 => *self = Style::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                5 => self.blink = true,
+                7 => self.reverse = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                25 => self.blink = false,
+                27 => self.reverse = false,
+                30..=37 | 90..=97 => self.fg = Some(code.to_string()),
+                39 => self.fg = None,
+                40..=47 | 100..=107 => self.bg = Some(code.to_string()),
+                49 => self.bg = None,
+                38 | 48 => {
+                    let len = match codes.get(i + 1).copied()? {
+                        "5" => 3,
+                        "2" => 5,
+                        _ => return None,
+                    };
+                    let spec = codes.get(i..i + len)?.join(";");
+                    if code == 38 {
+                        self.fg = Some(spec);
+                    } else {
+                        self.bg = Some(spec);
+                    }
+                    i += len;
+                    continue;
+                }
+                _ => return None,
+            }
+            i += 1;
+        }
+        Some(())
+    }
+
+    /// The shortest sequence taking the terminal from `self` to `to`.
+    fn transition(&self, to: &Style) -> String {
+        if self == to {
+            return String::new();
+        }
+        let clears = (self.bold && !to.bold)
+            || (self.dim && !to.dim)
+            || (self.blink && !to.blink)
+            || (self.reverse && !to.reverse)
+            || (self.fg.is_some() && to.fg.is_none())
+            || (self.bg.is_some() && to.bg.is_none());
+        // Turning anything off costs a reset and restating the rest.
+        let from = if clears {
+            Style::default()
+        } else {
+            self.clone()
+        };
+
+        let mut codes: Vec<&str> = Vec::new();
+        if clears {
+            codes.push("0");
+        }
+        for (on, was, code) in [
+            (to.bold, from.bold, "1"),
+            (to.dim, from.dim, "2"),
+            (to.blink, from.blink, "5"),
+            (to.reverse, from.reverse, "7"),
+        ] {
+            if on && !was {
+                codes.push(code);
+            }
+        }
+        if to.fg != from.fg
+            && let Some(fg) = &to.fg
+        {
+            codes.push(fg);
+        }
+        if to.bg != from.bg
+            && let Some(bg) = &to.bg
+        {
+            codes.push(bg);
+        }
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+/// Write `text`, first switching the terminal to `wanted` if it's visible.
+fn flush(out: &mut String, current: &mut Style, wanted: &Style, text: &str) {
+    if !text.is_empty() {
+        out.push_str(&current.transition(wanted));
+        *current = wanted.clone();
+        out.push_str(text);
+    }
+}
+
+/// Rewrite `line` with the minimal SGR sequences. A line containing codes
+/// this pass doesn't understand is returned unchanged.
+pub fn minify(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    // What the terminal is drawing in, and what the next text should use.
+    let mut current = Style::default();
+    let mut wanted = Style::default();
+
+    let mut rest = line;
+    while let Some(pos) = rest.find("\x1b[") {
+        let (text, seq) = rest.split_at(pos);
+        let body = &seq[2..];
+        let end = body
+            .find(|c: char| !(c.is_ascii_digit() || c == ';'))
+            .unwrap_or(body.len());
+        if body[end..].starts_with('m') {
+            flush(&mut out, &mut current, &wanted, text);
+            if wanted.apply(&body[..end]).is_none() {
+                return line.to_string();
+            }
+            rest = &body[end + 1..];
+        } else {
+            // Not SGR: the sequence passes through as text.
+            flush(&mut out, &mut current, &wanted, &rest[..pos + 2]);
+            rest = body;
+        }
+    }
+    flush(&mut out, &mut current, &wanted, rest);
+    // Leave the terminal in the state the original line did.
+    out.push_str(&current.transition(&wanted));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::layout::LayoutEngine;
+    use crate::render::Renderer;
+    use crate::tui::ansi::to_spans;
+    use crate::widgets::{SessionData, WidgetRegistry};
+
+    /// Each visible character with the style it's drawn in.
+    fn styled_chars(line: &str) -> Vec<(char, ratatui::style::Style)> {
+        to_spans(line)
+            .iter()
+            .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+            .collect()
+    }
+
+    #[test]
+    fn test_repeated_resets_collapse() {
+        assert_eq!(minify("\x1b[0m\x1b[0mOpus\x1b[0m\x1b[0m"), "Opus");
+        assert_eq!(minify("\x1b[31mOpus\x1b[0m\x1b[0m"), "\x1b[31mOpus\x1b[0m");
+    }
+
+    #[test]
+    fn test_overwritten_color_is_dropped() {
+        assert_eq!(
+            minify("\x1b[31m\x1b[38;5;208m\x1b[1mOpus\x1b[0m"),
+            "\x1b[1;38;5;208mOpus\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_reset_between_same_styles_is_dropped() {
+        assert_eq!(
+            minify("\x1b[32mOpus\x1b[0m\x1b[32m 42%\x1b[0m"),
+            "\x1b[32mOpus 42%\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_color_change_needs_no_reset() {
+        assert_eq!(
+            minify("\x1b[1;32mA\x1b[0m\x1b[1;31mB\x1b[0m"),
+            "\x1b[1;32mA\x1b[31mB\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_unknown_code_leaves_line_alone() {
+        let line = "\x1b[3mitalic\x1b[0m\x1b[0m";
+        assert_eq!(minify(line), line);
+    }
+
+    #[test]
+    fn test_links_and_plain_text_pass_through() {
+        let link = "\x1b]8;;https://example.com\x07main\x1b]8;;\x07";
+        assert_eq!(minify(link), link);
+        assert_eq!(minify("Opus | 42%"), "Opus | 42%");
+    }
+
+    #[test]
+    fn test_rendered_lines_display_the_same() {
+        let data = SessionData::sample();
+        let registry = WidgetRegistry::new();
+        let renderer = Renderer::detect("truecolor");
+        for powerline in [false, true] {
+            let mut config = Config::default();
+            config.powerline.enabled = powerline;
+            let engine = LayoutEngine::new(&config, &renderer).with_terminal_width(200);
+            for line in engine.render(&data, &config, &registry) {
+                let small = minify(&line);
+                assert_eq!(styled_chars(&small), styled_chars(&line), "{line:?}");
+                assert!(small.len() <= line.len(), "{line:?}");
+            }
+        }
+    }
+}
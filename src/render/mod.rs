@@ -1,3 +1,8 @@
+mod minify;
+
+pub use minify::minify;
+
+use std::collections::BTreeMap;
 use std::env;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -8,15 +13,113 @@ pub enum ColorLevel {
     TrueColor,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ColorSpec {
     Named(String),
     Ansi256(u8),
     Rgb(u8, u8, u8),
 }
 
+/// Below this WCAG contrast ratio text is hard to read against its background.
+pub const MIN_CONTRAST: f64 = 3.0;
+
+impl ColorSpec {
+    /// Approximate sRGB for contrast math. Named colors and the first 16
+    /// indexed ones use xterm's default palette.
+    pub fn to_rgb(&self) -> (u8, u8, u8) {
+        match self {
+            ColorSpec::Rgb(r, g, b) => (*r, *g, *b),
+            ColorSpec::Ansi256(n) => ansi256_rgb(*n),
+            ColorSpec::Named(name) => {
+                let index = NAMED_COLORS.iter().position(|n| n == name).unwrap_or(7);
+                ansi256_rgb(index as u8)
+            }
+        }
+    }
+
+    /// WCAG relative luminance, from 0.0 (black) to 1.0 (white).
+    pub fn luminance(&self) -> f64 {
+        let (r, g, b) = self.to_rgb();
+        let linear = |c: u8| {
+            let c = f64::from(c) / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+    }
+
+    /// WCAG contrast ratio between two colors, from 1.0 to 21.0.
+    pub fn contrast(&self, other: &ColorSpec) -> f64 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+}
+
+/// Named colors in ANSI index order.
+const NAMED_COLORS: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "brightBlack",
+    "brightRed",
+    "brightGreen",
+    "brightYellow",
+    "brightBlue",
+    "brightMagenta",
+    "brightCyan",
+    "brightWhite",
+];
+
+/// xterm's default RGB for a 256-color index.
+fn ansi256_rgb(n: u8) -> (u8, u8, u8) {
+    const BASIC: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match n {
+        0..=15 => BASIC[n as usize],
+        16..=231 => {
+            let i = n - 16;
+            (
+                LEVELS[(i / 36) as usize],
+                LEVELS[(i / 6 % 6) as usize],
+                LEVELS[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let v = 8 + 10 * (n - 232);
+            (v, v, v)
+        }
+    }
+}
+
 pub struct Renderer {
     pub color_level: ColorLevel,
+    /// `[palette]` aliases, resolved by `color` before named/hex parsing.
+    palette: BTreeMap<String, String>,
 }
 
 impl Renderer {
@@ -25,10 +128,25 @@ impl Renderer {
             "none" => ColorLevel::None,
             "16" => ColorLevel::Basic16,
             "256" => ColorLevel::Color256,
-            "truecolor" => ColorLevel::TrueColor,
+            "truecolor" | "16m" => ColorLevel::TrueColor,
             _ => Self::detect_color_level(),
         };
-        Self { color_level }
+        crate::debug_log!("color level: {color_level:?} (requested {override_level:?})");
+        Self {
+            color_level,
+            palette: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_palette(mut self, palette: BTreeMap<String, String>) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Parse a config color, resolving `[palette]` aliases first.
+    pub fn color(&self, name: &str) -> ColorSpec {
+        let name = self.palette.get(name).map_or(name, String::as_str);
+        Self::parse_color(name)
     }
 
     fn detect_color_level() -> ColorLevel {
@@ -74,6 +192,26 @@ impl Renderer {
         }
     }
 
+    /// SGR for an attention mode: "blink" (5) or "reverse" (7). Cleared by `reset`.
+    pub fn attention(&self, mode: &str) -> &str {
+        if self.color_level == ColorLevel::None {
+            return "";
+        }
+        match mode {
+            "blink" => "\x1b[5m",
+            "reverse" => "\x1b[7m",
+            _ => "",
+        }
+    }
+
+    pub fn dim(&self) -> &str {
+        if self.color_level == ColorLevel::None {
+            ""
+        } else {
+            "\x1b[2m"
+        }
+    }
+
     pub fn reset(&self) -> &str {
         if self.color_level == ColorLevel::None {
             ""
@@ -1,6 +1,38 @@
-use std::path::PathBuf;
-
-use rusqlite::{params, Connection, Result as SqlResult};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use rusqlite::{
+    Connection, ErrorCode, Result as SqlResult, Transaction, TransactionBehavior, params,
+};
+
+/// How long a statement waits on another process's lock before SQLite
+/// reports the database busy.
+const BUSY_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Further attempts at a write that still found the database busy, with
+/// the wait doubling from `RETRY_BACKOFF` each time.
+const WRITE_RETRIES: u32 = 4;
+const RETRY_BACKOFF: Duration = Duration::from_millis(10);
+
+/// Run `write`, retrying with backoff while another connection holds the
+/// database, so concurrent renders (several panes or agents) take turns
+/// instead of dropping their writes.
+fn with_retry<T>(mut write: impl FnMut() -> SqlResult<T>) -> SqlResult<T> {
+    let mut backoff = RETRY_BACKOFF;
+    for _ in 0..WRITE_RETRIES {
+        match write() {
+            Err(rusqlite::Error::SqliteFailure(e, _))
+                if matches!(e.code, ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked) =>
+            {
+                thread::sleep(backoff);
+                backoff *= 2;
+            }
+            result => return result,
+        }
+    }
+    write()
+}
 
 /// A recorded session with aggregate cost data.
 #[derive(Debug, Clone)]
@@ -35,10 +67,17 @@ impl CostTracker {
     /// Open (or create) the history database at the default location.
     pub fn open() -> SqlResult<Self> {
         let path = Self::db_path();
+        Self::open_at(&path)
+            .inspect_err(|e| crate::debug_log!("history: can't open {}: {e}", path.display()))
+    }
+
+    /// Open (or create) the history database at `path`.
+    pub fn open_at(path: &Path) -> SqlResult<Self> {
         if let Some(parent) = path.parent() {
             let _ = std::fs::create_dir_all(parent);
         }
-        let conn = Connection::open(&path)?;
+        let conn = Connection::open(path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
         let tracker = Self { conn };
         tracker.init_schema()?;
         Ok(tracker)
@@ -53,17 +92,20 @@ impl CostTracker {
         Ok(tracker)
     }
 
+    /// Database location: `history.db` under [`super::data_dir`].
     fn db_path() -> PathBuf {
-        dirs::data_dir()
-            .or_else(dirs::config_dir)
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("claude-status")
-            .join("history.db")
+        super::data_dir().join("history.db")
+    }
+
+    #[cfg(test)]
+    fn db_path_from(override_dir: Option<PathBuf>) -> PathBuf {
+        super::data_dir_from(override_dir).join("history.db")
     }
 
     fn init_schema(&self) -> SqlResult<()> {
-        self.conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS sessions (
+        with_retry(|| {
+            self.conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS sessions (
                 id TEXT PRIMARY KEY,
                 start_time INTEGER NOT NULL,
                 end_time INTEGER,
@@ -84,54 +126,135 @@ impl CostTracker {
                 FOREIGN KEY (session_id) REFERENCES sessions(id)
             );
 
+            CREATE TABLE IF NOT EXISTS session_starts (
+                id TEXT PRIMARY KEY,
+                start_time INTEGER NOT NULL
+            );
+
             CREATE INDEX IF NOT EXISTS idx_sessions_time ON sessions(start_time);
             CREATE INDEX IF NOT EXISTS idx_events_session ON events(session_id);
             CREATE INDEX IF NOT EXISTS idx_events_timestamp ON events(timestamp);",
-        )
+            )
+        })
     }
 
-    /// Insert or update a session record.
+    /// Insert or update a session record. A start already recorded by
+    /// `session_start` wins over `session.start_time`.
     pub fn upsert_session(&self, session: &SessionRecord) -> SqlResult<()> {
-        self.conn.execute(
-            "INSERT INTO sessions (id, start_time, end_time, model, total_cost, tokens_input, tokens_output, tokens_cached)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
-             ON CONFLICT(id) DO UPDATE SET
-                end_time = excluded.end_time,
-                model = excluded.model,
-                total_cost = excluded.total_cost,
-                tokens_input = excluded.tokens_input,
-                tokens_output = excluded.tokens_output,
-                tokens_cached = excluded.tokens_cached",
-            params![
-                session.id,
-                session.start_time,
-                session.end_time,
-                session.model,
-                session.total_cost,
-                session.tokens_input as i64,
-                session.tokens_output as i64,
-                session.tokens_cached as i64,
-            ],
-        )?;
+        with_retry(|| {
+            self.conn.execute(
+                "INSERT INTO sessions (id, start_time, end_time, model, total_cost, tokens_input, tokens_output, tokens_cached)
+                 VALUES (?1, COALESCE((SELECT start_time FROM session_starts WHERE id = ?1), ?2), ?3, ?4, ?5, ?6, ?7, ?8)
+                 ON CONFLICT(id) DO UPDATE SET
+                    end_time = excluded.end_time,
+                    model = excluded.model,
+                    total_cost = excluded.total_cost,
+                    tokens_input = excluded.tokens_input,
+                    tokens_output = excluded.tokens_output,
+                    tokens_cached = excluded.tokens_cached",
+                params![
+                    session.id,
+                    session.start_time,
+                    session.end_time,
+                    session.model,
+                    session.total_cost,
+                    session.tokens_input as i64,
+                    session.tokens_output as i64,
+                    session.tokens_cached as i64,
+                ],
+            )
+        })?;
         Ok(())
     }
 
+    /// When `session_id` was first seen, recording `now` (Unix seconds) as its
+    /// start if this is the first time. Later `upsert_session` calls keep it.
+    /// Starts live in their own table so sessions with no cost recorded yet
+    /// stay out of the stats.
+    pub fn session_start(&self, session_id: &str, now: i64) -> SqlResult<i64> {
+        with_retry(|| {
+            self.conn.execute(
+                "INSERT INTO session_starts (id, start_time)
+                 VALUES (?1, COALESCE((SELECT start_time FROM sessions WHERE id = ?1), ?2))
+                 ON CONFLICT(id) DO NOTHING",
+                params![session_id, now],
+            )
+        })?;
+        self.conn.query_row(
+            "SELECT start_time FROM session_starts WHERE id = ?1",
+            params![session_id],
+            |row| row.get(0),
+        )
+    }
+
     /// Record a cost event.
     pub fn insert_event(&self, event: &CostEvent) -> SqlResult<()> {
-        self.conn.execute(
-            "INSERT INTO events (session_id, timestamp, event_type, cost, metadata)
+        with_retry(|| {
+            self.conn.execute(
+                "INSERT INTO events (session_id, timestamp, event_type, cost, metadata)
              VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![
-                event.session_id,
-                event.timestamp,
-                event.event_type,
-                event.cost,
-                event.metadata,
-            ],
-        )?;
+                params![
+                    event.session_id,
+                    event.timestamp,
+                    event.event_type,
+                    event.cost,
+                    event.metadata,
+                ],
+            )
+        })?;
         Ok(())
     }
 
+    /// Record a render's running session total: stores the session and
+    /// inserts a `message` event for the spend since the last recorded
+    /// total. The first render records the full total; an unchanged or
+    /// lower total (a reset) records nothing. Returns the event's cost.
+    ///
+    /// Runs as one write transaction, so two renders recording the same
+    /// total can't both count the delta.
+    pub fn record_total(&self, session: &SessionRecord) -> SqlResult<Option<f64>> {
+        with_retry(|| {
+            let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+            let previous = self.get_session(&session.id).map_or(0.0, |s| s.total_cost);
+            let delta = session.total_cost - previous;
+            if delta <= 0.0 {
+                return Ok(None);
+            }
+            self.upsert_session(session)?;
+            self.insert_event(&CostEvent {
+                id: None,
+                session_id: session.id.clone(),
+                timestamp: session.end_time.unwrap_or(session.start_time),
+                event_type: "message".into(),
+                cost: delta,
+                metadata: None,
+            })?;
+            tx.commit()?;
+            Ok(Some(delta))
+        })
+    }
+
+    /// The most recent event recorded for a session.
+    pub fn latest_event(&self, session_id: &str) -> Option<CostEvent> {
+        self.conn
+            .query_row(
+                "SELECT id, session_id, timestamp, event_type, cost, metadata
+                 FROM events WHERE session_id = ?1 ORDER BY id DESC LIMIT 1",
+                params![session_id],
+                |row| {
+                    Ok(CostEvent {
+                        id: row.get(0)?,
+                        session_id: row.get(1)?,
+                        timestamp: row.get(2)?,
+                        event_type: row.get(3)?,
+                        cost: row.get(4)?,
+                        metadata: row.get(5)?,
+                    })
+                },
+            )
+            .ok()
+    }
+
     /// Get events since a given timestamp (Unix seconds).
     pub fn events_since(&self, since: i64) -> Vec<CostEvent> {
         let mut stmt = self
@@ -180,6 +303,18 @@ impl CostTracker {
             .unwrap_or(0.0)
     }
 
+    /// Per-day session cost for the `days` days ending at `end` (exclusive),
+    /// oldest first.
+    pub fn daily_costs(&self, end: i64, days: u32) -> Vec<f64> {
+        (0..days as i64)
+            .rev()
+            .map(|i| {
+                let day_end = end - i * 86400;
+                self.session_cost_range(day_end - 86400, day_end)
+            })
+            .collect()
+    }
+
     /// Get sessions in a time range ordered by cost (descending).
     pub fn top_sessions(&self, from: i64, to: i64, limit: u32) -> Vec<SessionRecord> {
         let mut stmt = self
@@ -269,6 +404,41 @@ mod tests {
         assert_eq!(fetched.tokens_input, 5000);
     }
 
+    #[test]
+    fn test_session_start_keeps_first_seen_time() {
+        let tracker = CostTracker::open_in_memory().unwrap();
+
+        assert_eq!(tracker.session_start("s1", 1000).unwrap(), 1000);
+        assert_eq!(tracker.session_start("s1", 5000).unwrap(), 1000);
+        // Only first seen so far: not a session for the stats yet
+        assert_eq!(tracker.session_count_range(0, 10_000), 0);
+        assert!(tracker.get_session("s1").is_none());
+
+        let session = SessionRecord {
+            id: "s1".into(),
+            start_time: 4000,
+            end_time: None,
+            model: "claude-opus-4-6".into(),
+            total_cost: 1.0,
+            tokens_input: 0,
+            tokens_output: 0,
+            tokens_cached: 0,
+        };
+        tracker.upsert_session(&session).unwrap();
+        assert_eq!(tracker.session_start("s1", 6000).unwrap(), 1000);
+        assert_eq!(tracker.get_session("s1").unwrap().start_time, 1000);
+        assert_eq!(tracker.session_count_range(0, 10_000), 1);
+
+        // A session recorded before its start was tracked keeps its own start
+        let earlier = SessionRecord {
+            id: "s2".into(),
+            start_time: 2000,
+            ..session
+        };
+        tracker.upsert_session(&earlier).unwrap();
+        assert_eq!(tracker.session_start("s2", 7000).unwrap(), 2000);
+    }
+
     #[test]
     fn test_insert_events_and_query() {
         let tracker = CostTracker::open_in_memory().unwrap();
@@ -305,6 +475,36 @@ mod tests {
         assert!((total - 0.50).abs() < 0.001);
     }
 
+    #[test]
+    fn test_record_total_stores_deltas() {
+        let tracker = CostTracker::open_in_memory().unwrap();
+        let mut session = SessionRecord {
+            id: "s1".into(),
+            start_time: 100,
+            end_time: Some(100),
+            model: "opus".into(),
+            total_cost: 0.5,
+            tokens_input: 0,
+            tokens_output: 0,
+            tokens_cached: 0,
+        };
+        assert_eq!(tracker.record_total(&session).unwrap(), Some(0.5));
+
+        session.end_time = Some(200);
+        session.total_cost = 0.75;
+        assert_eq!(tracker.record_total(&session).unwrap(), Some(0.25));
+        let latest = tracker.latest_event("s1").unwrap();
+        assert_eq!((latest.timestamp, latest.cost), (200, 0.25));
+
+        // Unchanged and reset totals record nothing.
+        assert_eq!(tracker.record_total(&session).unwrap(), None);
+        session.total_cost = 0.1;
+        assert_eq!(tracker.record_total(&session).unwrap(), None);
+        assert_eq!(tracker.events_since(0).len(), 2);
+        assert_eq!(tracker.get_session("s1").unwrap().total_cost, 0.75);
+        assert!(tracker.latest_event("s2").is_none());
+    }
+
     #[test]
     fn test_top_sessions() {
         let tracker = CostTracker::open_in_memory().unwrap();
@@ -366,4 +566,89 @@ mod tests {
         let cost = tracker.session_cost_range(0, 2000);
         assert!((cost - 15.0).abs() < 0.001);
     }
+
+    #[test]
+    fn test_daily_costs_oldest_first() {
+        let tracker = CostTracker::open_in_memory().unwrap();
+
+        for (id, start_time, total_cost) in [("a", 100, 1.0), ("b", 86400 + 100, 2.0)] {
+            tracker
+                .upsert_session(&SessionRecord {
+                    id: id.into(),
+                    start_time,
+                    end_time: None,
+                    model: "opus".into(),
+                    total_cost,
+                    tokens_input: 0,
+                    tokens_output: 0,
+                    tokens_cached: 0,
+                })
+                .unwrap();
+        }
+
+        let days = tracker.daily_costs(3 * 86400, 3);
+        assert_eq!(days, vec![1.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_concurrent_writers_all_land() {
+        let dir =
+            std::env::temp_dir().join(format!("claude-status-history-{}", std::process::id()));
+        let path = dir.join("history.db");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let writers: Vec<_> = (0..8)
+            .map(|writer| {
+                let path = path.clone();
+                thread::spawn(move || {
+                    let tracker = CostTracker::open_at(&path).unwrap();
+                    for i in 0..25 {
+                        let session = SessionRecord {
+                            id: format!("w{writer}-s{i}"),
+                            start_time: 1000,
+                            end_time: Some(1000 + i),
+                            model: "opus".into(),
+                            total_cost: 0.5,
+                            tokens_input: 0,
+                            tokens_output: 0,
+                            tokens_cached: 0,
+                        };
+                        tracker.upsert_session(&session).unwrap();
+                        // Every writer bumps the same shared session too
+                        let shared = SessionRecord {
+                            id: "shared".into(),
+                            total_cost: (writer * 25 + i + 1) as f64,
+                            ..session
+                        };
+                        tracker.record_total(&shared).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let tracker = CostTracker::open_at(&path).unwrap();
+        assert_eq!(tracker.session_count_range(0, i64::MAX), 8 * 25 + 1);
+        // Deltas against the stored total add up to the highest total seen
+        let shared: f64 = tracker
+            .events_since(0)
+            .iter()
+            .filter(|e| e.session_id == "shared")
+            .map(|e| e.cost)
+            .sum();
+        assert_eq!(shared, 200.0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_db_path_override() {
+        let path = CostTracker::db_path_from(Some(PathBuf::from("/tmp/cs-data")));
+        assert_eq!(path, PathBuf::from("/tmp/cs-data/history.db"));
+
+        let default = CostTracker::db_path_from(Some(PathBuf::new()));
+        assert!(default.ends_with("claude-status/history.db"));
+    }
 }
@@ -1,3 +1,24 @@
 mod history;
 
+use std::path::PathBuf;
+
 pub use history::{CostEvent, CostTracker, SessionRecord};
+
+/// Directory for local state (history database, render cache), in order of
+/// precedence:
+/// 1. `$CLAUDE_STATUS_DATA_DIR`
+/// 2. `<data dir>/claude-status` (e.g. `~/.local/share` on Linux)
+/// 3. `<config dir>/claude-status`
+pub fn data_dir() -> PathBuf {
+    data_dir_from(std::env::var_os("CLAUDE_STATUS_DATA_DIR").map(PathBuf::from))
+}
+
+pub(crate) fn data_dir_from(override_dir: Option<PathBuf>) -> PathBuf {
+    match override_dir {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => dirs::data_dir()
+            .or_else(dirs::config_dir)
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("claude-status"),
+    }
+}
@@ -1,5 +1,19 @@
 use std::collections::HashMap;
 
+/// Theme color roles with display labels, in the order previews list them.
+pub const ROLES: [(&str, &str); 10] = [
+    ("model", "Model color"),
+    ("context_ok", "Context OK"),
+    ("context_warn", "Context Warning"),
+    ("context_critical", "Context Critical"),
+    ("git_branch", "Git branch"),
+    ("git_clean", "Git clean"),
+    ("git_dirty", "Git dirty"),
+    ("cost", "Cost"),
+    ("duration", "Duration"),
+    ("separator_fg", "Separator"),
+];
+
 #[derive(Debug, Clone)]
 pub struct Theme {
     pub name: String,
@@ -19,6 +33,7 @@ impl Theme {
             "one-dark" => Self::one_dark(),
             "tokyo-night" => Self::tokyo_night(),
             "catppuccin" => Self::catppuccin(),
+            "colorblind" => Self::colorblind(),
             _ => Self::default_theme(),
         }
     }
@@ -36,6 +51,7 @@ impl Theme {
             "one-dark",
             "tokyo-night",
             "catppuccin",
+            "colorblind",
         ]
     }
 
@@ -47,12 +63,29 @@ impl Theme {
         let role = match widget_type {
             "model" => "model",
             "context-percentage" | "context-length" => "context_ok",
+            "compaction-warning" => "context_critical",
             "git-branch" => "git_branch",
             "git-status" => "git_clean",
             "git-worktree" => "git_branch",
             "session-cost" | "block-timer" => "cost",
             "session-duration" | "api-duration" => "duration",
-            "separator" => "separator_fg",
+            "separator" | "group-divider" => "separator_fg",
+            _ => return None,
+        };
+        self.colors.get(role).map(|s| s.as_str())
+    }
+
+    /// The theme's color for a widget's red/yellow/green state hint, so
+    /// themes recolor states instead of only idle widgets.
+    pub fn hint_color(&self, widget_type: &str, hint: &str) -> Option<&str> {
+        let role = match (hint, widget_type) {
+            // Model tier colors come straight from the user's config.
+            (_, "model") => return None,
+            ("green", "git-status") => "git_clean",
+            ("yellow", "git-branch" | "git-status" | "git-age") => "git_dirty",
+            ("green", _) => "context_ok",
+            ("yellow", _) => "context_warn",
+            ("red", _) => "context_critical",
             _ => return None,
         };
         self.colors.get(role).map(|s| s.as_str())
@@ -255,4 +288,24 @@ impl Theme {
             ]),
         }
     }
+
+    /// Okabe-Ito colors: blue, yellow and orange differ in luminance as well
+    /// as hue, so states read without red-green discrimination.
+    fn colorblind() -> Self {
+        Self {
+            name: "colorblind".into(),
+            colors: HashMap::from([
+                ("model".into(), "#56b4e9".into()),
+                ("context_ok".into(), "#0072b2".into()),
+                ("context_warn".into(), "#f0e442".into()),
+                ("context_critical".into(), "#d55e00".into()),
+                ("git_branch".into(), "#cc79a7".into()),
+                ("git_clean".into(), "#0072b2".into()),
+                ("git_dirty".into(), "#e69f00".into()),
+                ("cost".into(), "#f0e442".into()),
+                ("duration".into(), "#d0d0d0".into()),
+                ("separator_fg".into(), "#6c6c6c".into()),
+            ]),
+        }
+    }
 }
@@ -0,0 +1,107 @@
+//! Translate the layout engine's ANSI output into styled ratatui spans, so
+//! the preview shows the same colors the terminal would.
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Span;
+
+use crate::layout::{RenderedSegment, ansi};
+use crate::render::ColorSpec;
+
+const NAMED: [Color; 8] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+];
+
+const BRIGHT: [Color; 8] = [
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// Split `s` into spans at each SGR sequence. OSC sequences (hyperlinks)
+/// are dropped; their visible text is kept.
+pub fn to_spans(s: &str) -> Vec<Span<'static>> {
+    ansi::parse(s)
+        .into_iter()
+        .map(|segment| {
+            let style = style_of(&segment);
+            Span::styled(segment.text, style)
+        })
+        .collect()
+}
+
+fn style_of(segment: &RenderedSegment) -> Style {
+    let mut style = Style {
+        fg: segment.fg.as_ref().and_then(color),
+        bg: segment.bg.as_ref().and_then(color),
+        ..Style::default()
+    };
+    for (on, modifier) in [
+        (segment.bold, Modifier::BOLD),
+        (segment.dim, Modifier::DIM),
+        (segment.blink, Modifier::SLOW_BLINK),
+        (segment.reverse, Modifier::REVERSED),
+    ] {
+        if on {
+            style = style.add_modifier(modifier);
+        }
+    }
+    style
+}
+
+fn color(spec: &ColorSpec) -> Option<Color> {
+    match spec {
+        ColorSpec::Named(name) => {
+            let i = ansi::NAMED.iter().position(|n| n == name)?;
+            Some(if i < 8 { NAMED[i] } else { BRIGHT[i - 8] })
+        }
+        ColorSpec::Ansi256(n) => Some(Color::Indexed(*n)),
+        ColorSpec::Rgb(r, g, b) => Some(Color::Rgb(*r, *g, *b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text_is_one_span() {
+        let spans = to_spans("Opus | 42%");
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].content, "Opus | 42%");
+        assert_eq!(spans[0].style, Style::default());
+    }
+
+    #[test]
+    fn test_sgr_colors_and_reset() {
+        let spans = to_spans("\x1b[1m\x1b[38;2;255;135;0mOpus\x1b[0m \x1b[48;5;208m42%\x1b[0m");
+        assert_eq!(spans.len(), 3);
+        assert_eq!(spans[0].content, "Opus");
+        assert_eq!(
+            spans[0].style,
+            Style::default()
+                .fg(Color::Rgb(255, 135, 0))
+                .add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(spans[1].style, Style::default());
+        assert_eq!(spans[2].style, Style::default().bg(Color::Indexed(208)));
+    }
+
+    #[test]
+    fn test_osc8_link_keeps_text() {
+        let spans = to_spans("\x1b]8;;https://example.com\x07main\x1b]8;;\x07");
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].content, "main");
+    }
+}
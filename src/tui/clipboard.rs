@@ -0,0 +1,36 @@
+//! Copy text to the system clipboard through the platform's clipboard tool.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Tried in order; the first one that runs wins.
+const TOOLS: &[(&str, &[&str])] = &[
+    ("pbcopy", &[]),
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("clip.exe", &[]),
+];
+
+/// Copy `text`, returning the name of the tool that took it.
+pub fn copy(text: &str) -> Result<&'static str, String> {
+    for (tool, args) in TOOLS {
+        let Ok(mut child) = Command::new(tool)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        let written = child
+            .stdin
+            .take()
+            .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+        if child.wait().is_ok_and(|s| s.success()) && written {
+            return Ok(tool);
+        }
+    }
+    Err("no clipboard tool found (install wl-copy, xclip or xsel)".into())
+}
@@ -1,4 +1,7 @@
+pub(crate) mod ansi;
+mod clipboard;
 mod preview;
+mod settings_panel;
 mod theme_panel;
 mod widget_list;
 
@@ -20,6 +23,7 @@ use crate::config::{Config, LineWidgetConfig};
 use crate::themes::Theme;
 
 use preview::draw_preview;
+use settings_panel::{SETTINGS, draw_settings_panel};
 use theme_panel::draw_theme_panel;
 use widget_list::draw_widget_list;
 
@@ -29,6 +33,7 @@ enum Tab {
     Theme,
     Powerline,
     Layout,
+    Settings,
     Preview,
 }
 
@@ -39,7 +44,8 @@ impl Tab {
             Tab::Theme => 1,
             Tab::Powerline => 2,
             Tab::Layout => 3,
-            Tab::Preview => 4,
+            Tab::Settings => 4,
+            Tab::Preview => 5,
         }
     }
 
@@ -49,13 +55,25 @@ impl Tab {
             1 => Tab::Theme,
             2 => Tab::Powerline,
             3 => Tab::Layout,
-            4 => Tab::Preview,
+            4 => Tab::Settings,
+            5 => Tab::Preview,
             _ => Tab::Widgets,
         }
     }
 
     fn count() -> usize {
-        5
+        6
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Tab::Widgets => "Widgets",
+            Tab::Theme => "Theme",
+            Tab::Powerline => "Powerline",
+            Tab::Layout => "Layout",
+            Tab::Settings => "Settings",
+            Tab::Preview => "Preview",
+        }
     }
 }
 
@@ -71,8 +89,16 @@ pub struct TuiState {
     powerline_cursor: usize,
     // Layout tab state
     layout_cursor: usize,
+    // Settings tab state
+    settings_cursor: usize,
+    /// Text being typed for a string setting; keys go here while it's set.
+    settings_edit: Option<String>,
+    // Preview tab state
+    show_ruler: bool,
     // Dirty flag
     modified: bool,
+    /// One-shot feedback shown in the status bar until the next key press.
+    message: Option<String>,
 }
 
 impl TuiState {
@@ -85,7 +111,11 @@ impl TuiState {
             theme_cursor: 0,
             powerline_cursor: 0,
             layout_cursor: 0,
+            settings_cursor: 0,
+            settings_edit: None,
+            show_ruler: false,
             modified: false,
+            message: None,
         }
     }
 }
@@ -119,6 +149,11 @@ fn run_loop<B: ratatui::backend::Backend>(
         if event::poll(std::time::Duration::from_millis(100))?
             && let Event::Key(key) = event::read()?
         {
+            state.message = None;
+            if state.settings_edit.is_some() {
+                handle_settings_edit(state, key.code);
+                continue;
+            }
             match key.code {
                 KeyCode::Char('q') => {
                     return Ok(());
@@ -127,6 +162,12 @@ fn run_loop<B: ratatui::backend::Backend>(
                     save_config(&state.config);
                     state.modified = false;
                 }
+                KeyCode::Char('y') => {
+                    state.message = Some(match clipboard::copy(&state.config.to_toml()) {
+                        Ok(tool) => format!("Config copied to clipboard ({tool})"),
+                        Err(e) => format!("Copy failed: {e}"),
+                    });
+                }
                 KeyCode::Tab => {
                     let next = (state.active_tab.index() + 1) % Tab::count();
                     state.active_tab = Tab::from_index(next);
@@ -139,6 +180,9 @@ fn run_loop<B: ratatui::backend::Backend>(
                     };
                     state.active_tab = Tab::from_index(prev);
                 }
+                KeyCode::Char(c @ '1'..='6') => {
+                    state.active_tab = Tab::from_index(c as usize - '1' as usize);
+                }
                 _ => handle_tab_input(state, key.code),
             }
         }
@@ -151,7 +195,12 @@ fn handle_tab_input(state: &mut TuiState, key: KeyCode) {
         Tab::Theme => handle_theme_input(state, key),
         Tab::Powerline => handle_powerline_input(state, key),
         Tab::Layout => handle_layout_input(state, key),
-        Tab::Preview => {}
+        Tab::Settings => handle_settings_input(state, key),
+        Tab::Preview => {
+            if key == KeyCode::Char('r') {
+                state.show_ruler = !state.show_ruler;
+            }
+        }
     }
 }
 
@@ -193,7 +242,7 @@ fn handle_widgets_input(state: &mut TuiState, key: KeyCode) {
                     .iter()
                     .find(|t| !line.iter().any(|w| w.widget_type == **t))
                     .unwrap_or(&"custom-text");
-                line.push(default_widget(next_type));
+                line.push(LineWidgetConfig::new(next_type));
                 state.modified = true;
             }
         }
@@ -276,11 +325,19 @@ fn handle_powerline_input(state: &mut TuiState, key: KeyCode) {
                     state.modified = true;
                 }
                 1 => {
-                    // Cycle separator
-                    let seps = ["\u{E0B0}", "\u{E0B4}", "\u{E0BC}", "/", "|"];
+                    // Cycle separator pairs (forward, reverse)
+                    let seps = [
+                        ("\u{E0B0}", "\u{E0B2}"),
+                        ("\u{E0B4}", "\u{E0B6}"),
+                        ("\u{E0BC}", "\u{E0BE}"),
+                        ("/", "/"),
+                        ("|", "|"),
+                    ];
                     let current = state.config.powerline.separator.as_str();
-                    let idx = seps.iter().position(|s| *s == current).unwrap_or(0);
-                    state.config.powerline.separator = seps[(idx + 1) % seps.len()].to_string();
+                    let idx = seps.iter().position(|(s, _)| *s == current).unwrap_or(0);
+                    let (sep, rev) = seps[(idx + 1) % seps.len()];
+                    state.config.powerline.separator = sep.to_string();
+                    state.config.powerline.separator_reverse = rev.to_string();
                     state.modified = true;
                 }
                 2 => {
@@ -342,6 +399,44 @@ fn handle_layout_input(state: &mut TuiState, key: KeyCode) {
     }
 }
 
+fn handle_settings_input(state: &mut TuiState, key: KeyCode) {
+    match key {
+        KeyCode::Up if state.settings_cursor > 0 => state.settings_cursor -= 1,
+        KeyCode::Down if state.settings_cursor < SETTINGS.len() - 1 => {
+            state.settings_cursor += 1;
+        }
+        KeyCode::Enter | KeyCode::Char(' ') => {
+            let setting = SETTINGS[state.settings_cursor];
+            if setting.is_text() {
+                state.settings_edit = Some(setting.value(&state.config));
+            } else {
+                setting.activate(&mut state.config);
+                state.modified = true;
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_settings_edit(state: &mut TuiState, key: KeyCode) {
+    let Some(buffer) = state.settings_edit.as_mut() else {
+        return;
+    };
+    match key {
+        KeyCode::Char(c) => buffer.push(c),
+        KeyCode::Backspace => {
+            buffer.pop();
+        }
+        KeyCode::Enter => {
+            let text = state.settings_edit.take().unwrap_or_default();
+            SETTINGS[state.settings_cursor].set_text(&mut state.config, text);
+            state.modified = true;
+        }
+        KeyCode::Esc => state.settings_edit = None,
+        _ => {}
+    }
+}
+
 fn draw_ui(f: &mut ratatui::Frame, state: &TuiState) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -359,6 +454,7 @@ fn draw_ui(f: &mut ratatui::Frame, state: &TuiState) {
         Tab::Theme => draw_theme_panel(f, state, chunks[1]),
         Tab::Powerline => draw_powerline_panel(f, state, chunks[1]),
         Tab::Layout => draw_layout_panel(f, state, chunks[1]),
+        Tab::Settings => draw_settings_panel(f, state, chunks[1]),
         Tab::Preview => draw_preview(f, state, chunks[1]),
     }
 
@@ -366,9 +462,8 @@ fn draw_ui(f: &mut ratatui::Frame, state: &TuiState) {
 }
 
 fn draw_tabs(f: &mut ratatui::Frame, state: &TuiState, area: Rect) {
-    let titles: Vec<Line> = ["Widgets", "Theme", "Powerline", "Layout", "Preview"]
-        .iter()
-        .map(|t| Line::from(*t))
+    let titles: Vec<Line> = (0..Tab::count())
+        .map(|i| Line::from(format!("{} {}", i + 1, Tab::from_index(i).title())))
         .collect();
     let tabs = Tabs::new(titles)
         .block(
@@ -486,9 +581,17 @@ fn draw_layout_panel(f: &mut ratatui::Frame, state: &TuiState, area: Rect) {
 }
 
 fn draw_status_bar(f: &mut ratatui::Frame, state: &TuiState, area: Rect) {
+    if let Some(ref message) = state.message {
+        let bar = Paragraph::new(Line::from(Span::styled(
+            format!(" {message}"),
+            Style::default().fg(Color::Yellow),
+        )));
+        f.render_widget(bar, area);
+        return;
+    }
     let modified = if state.modified { " [modified]" } else { "" };
     let help = format!(
-        " Tab/Shift-Tab: switch tabs | arrows: navigate | Enter: select | q: quit | Ctrl-s: save{}",
+        " Tab/Shift-Tab/1-6: switch tabs | arrows: navigate | Enter: select | y: copy TOML | q: quit | Ctrl-s: save{}",
         modified
     );
     let bar = Paragraph::new(Line::from(Span::styled(
@@ -510,14 +613,19 @@ fn save_config(config: &Config) {
         let _ = std::fs::create_dir_all(parent);
     }
 
-    let _ = std::fs::write(&path, config.to_toml());
+    let _ = std::fs::write(&path, config.serialize_for(&path));
 }
 
 fn available_widget_types() -> Vec<&'static str> {
     vec![
         "model",
         "context-percentage",
+        "context-remaining",
         "context-length",
+        "context-trend",
+        "context-gauge",
+        "headroom",
+        "window-size",
         "tokens-input",
         "tokens-output",
         "tokens-cached",
@@ -528,33 +636,26 @@ fn available_widget_types() -> Vec<&'static str> {
         "git-branch",
         "git-status",
         "git-worktree",
+        "git-age",
+        "project",
         "cwd",
         "lines-changed",
         "version",
         "session-id",
         "vim-mode",
         "agent-name",
+        "agent-count",
         "output-style",
         "exceeds-tokens",
+        "compaction-warning",
         "api-duration",
         "custom-command",
         "custom-text",
         "separator",
+        "group-divider",
         "flex-separator",
         "terminal-width",
+        "transcript-size",
+        "idle-time",
     ]
 }
-
-fn default_widget(widget_type: &str) -> LineWidgetConfig {
-    LineWidgetConfig {
-        widget_type: widget_type.to_string(),
-        id: String::new(),
-        color: None,
-        background_color: None,
-        bold: None,
-        raw_value: false,
-        padding: None,
-        merge_next: false,
-        metadata: std::collections::HashMap::new(),
-    }
-}
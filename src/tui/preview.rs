@@ -5,57 +5,39 @@ use ratatui::widgets::{Block, Borders, Paragraph};
 
 use crate::layout::LayoutEngine;
 use crate::render::Renderer;
-use crate::widgets::data::*;
 use crate::widgets::{SessionData, WidgetRegistry};
 
 use super::TuiState;
+use super::ansi;
 
-fn mock_session() -> SessionData {
-    SessionData {
-        cwd: Some("/Users/demo/project".into()),
-        session_id: Some("abc12345-def6-7890".into()),
-        transcript_path: None,
-        model: Some(Model {
-            id: Some("claude-opus-4-6".into()),
-            display_name: Some("Opus".into()),
-        }),
-        workspace: Some(Workspace {
-            current_dir: Some("/Users/demo/project".into()),
-            project_dir: Some("/Users/demo/project".into()),
-        }),
-        version: Some("2.1.31".into()),
-        output_style: Some(OutputStyle {
-            name: Some("default".into()),
-        }),
-        cost: Some(Cost {
-            total_cost_usd: Some(0.42),
-            total_duration_ms: Some(345000),
-            total_api_duration_ms: Some(156000),
-            total_lines_added: Some(234),
-            total_lines_removed: Some(56),
-        }),
-        context_window: Some(ContextWindow {
-            total_input_tokens: Some(50000),
-            total_output_tokens: Some(12000),
-            context_window_size: Some(200000),
-            used_percentage: Some(65.0),
-            remaining_percentage: Some(35.0),
-            current_usage: Some(CurrentUsage {
-                input_tokens: Some(25000),
-                output_tokens: Some(8000),
-                cache_creation_input_tokens: Some(10000),
-                cache_read_input_tokens: Some(5000),
-            }),
-        }),
-        exceeds_200k_tokens: Some(false),
-        vim: None,
-        agent: None,
+/// Label in front of each preview line; the ruler is indented to match.
+fn line_label(i: usize) -> String {
+    format!("  Line {}: ", i + 1)
+}
+
+/// A column ruler `width` cells wide: the column number every 10 cells and
+/// a tick in between, e.g. `0····5····10···`.
+fn ruler(width: usize) -> String {
+    let mut ruler = String::with_capacity(width);
+    let mut col = 0;
+    while col < width {
+        let label = col.to_string();
+        if col % 10 == 0 && col + label.len() <= width {
+            ruler.push_str(&label);
+            col += label.len();
+        } else {
+            ruler.push(if col % 5 == 0 { '+' } else { '·' });
+            col += 1;
+        }
     }
+    ruler
 }
 
 pub fn draw_preview(f: &mut ratatui::Frame, state: &TuiState, area: Rect) {
-    let data = mock_session();
-    let renderer = Renderer::detect("none");
+    let data = SessionData::sample();
+    // Render as truecolor and translate the ANSI back into styled spans, so
+    // the preview matches what the terminal will show.
+    let renderer = Renderer::detect("truecolor").with_palette(state.config.palette.clone());
     let registry = WidgetRegistry::new();
 
     // Use a modified config with full flex mode for preview
@@ -79,18 +61,29 @@ pub fn draw_preview(f: &mut ratatui::Frame, state: &TuiState, area: Rect) {
             Style::default().fg(Color::Yellow),
         )));
     } else {
-        for (i, line) in rendered.iter().enumerate() {
+        if state.show_ruler {
+            // Inside the borders, after the line label.
+            let indent = line_label(0).len();
+            let width = (area.width as usize).saturating_sub(2 + indent);
             lines.push(Line::from(Span::styled(
-                format!("  Line {}: {}", i + 1, line),
-                Style::default().fg(Color::White),
+                format!("{}{}", " ".repeat(indent), ruler(width)),
+                Style::default().fg(Color::DarkGray),
             )));
         }
+        for (i, line) in rendered.iter().enumerate() {
+            let mut spans = vec![Span::styled(
+                line_label(i),
+                Style::default().fg(Color::White),
+            )];
+            spans.extend(ansi::to_spans(line));
+            lines.push(Line::from(spans));
+        }
     }
 
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
         format!(
-            "  Theme: {} | Powerline: {} | Flex: {}",
+            "  Theme: {} | Powerline: {} | Flex: {} | r: ruler",
             state.config.theme,
             if state.config.powerline.enabled {
                 "ON"
@@ -106,3 +99,17 @@ pub fn draw_preview(f: &mut ratatui::Frame, state: &TuiState, area: Rect) {
     let paragraph = Paragraph::new(lines).block(block);
     f.render_widget(paragraph, area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ruler_marks_every_ten_columns() {
+        assert_eq!(ruler(25), "0····+····10···+····20···");
+        assert_eq!(ruler(25).chars().count(), 25);
+        // A label that doesn't fit is left out.
+        assert_eq!(ruler(11), "0····+····+");
+        assert_eq!(ruler(0), "");
+    }
+}
@@ -0,0 +1,189 @@
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use crate::config::Config;
+use crate::icons::IconSet;
+
+use super::TuiState;
+
+/// A row in the Settings tab. Text settings are edited in place; the rest
+/// toggle or cycle on Enter.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Setting {
+    FlexMode,
+    Separator,
+    Padding,
+    GlobalBold,
+    InheritSeparatorColors,
+    IconSet,
+    NumberFormat,
+    PowerlineSeparator,
+    PowerlineSeparatorReverse,
+    StartCap,
+    EndCap,
+}
+
+pub const SETTINGS: [Setting; 11] = [
+    Setting::FlexMode,
+    Setting::Separator,
+    Setting::Padding,
+    Setting::GlobalBold,
+    Setting::InheritSeparatorColors,
+    Setting::IconSet,
+    Setting::NumberFormat,
+    Setting::PowerlineSeparator,
+    Setting::PowerlineSeparatorReverse,
+    Setting::StartCap,
+    Setting::EndCap,
+];
+
+const FLEX_MODES: [&str; 3] = ["full-minus-40", "full", "compact"];
+
+fn cycle(options: &[&str], current: &str) -> String {
+    let idx = options.iter().position(|o| *o == current).unwrap_or(0);
+    options[(idx + 1) % options.len()].to_string()
+}
+
+fn on_off(b: bool) -> String {
+    if b { "ON" } else { "OFF" }.to_string()
+}
+
+impl Setting {
+    fn label(self) -> &'static str {
+        match self {
+            Setting::FlexMode => "Flex mode",
+            Setting::Separator => "Separator",
+            Setting::Padding => "Padding",
+            Setting::GlobalBold => "Global bold",
+            Setting::InheritSeparatorColors => "Inherit separator colors",
+            Setting::IconSet => "Icon set",
+            Setting::NumberFormat => "Number format",
+            Setting::PowerlineSeparator => "Powerline separator",
+            Setting::PowerlineSeparatorReverse => "Powerline reverse separator",
+            Setting::StartCap => "Powerline start cap",
+            Setting::EndCap => "Powerline end cap",
+        }
+    }
+
+    pub fn is_text(self) -> bool {
+        matches!(
+            self,
+            Setting::Separator
+                | Setting::Padding
+                | Setting::PowerlineSeparator
+                | Setting::PowerlineSeparatorReverse
+                | Setting::StartCap
+                | Setting::EndCap
+        )
+    }
+
+    pub fn value(self, config: &Config) -> String {
+        match self {
+            Setting::FlexMode => config.flex_mode.clone(),
+            Setting::Separator => config.default_separator.clone(),
+            Setting::Padding => config.default_padding.clone(),
+            Setting::GlobalBold => on_off(config.global_bold),
+            Setting::InheritSeparatorColors => on_off(config.inherit_separator_colors),
+            Setting::IconSet => config.icon_set.clone(),
+            Setting::NumberFormat => config.number_format.clone(),
+            Setting::PowerlineSeparator => config.powerline.separator.clone(),
+            Setting::PowerlineSeparatorReverse => config.powerline.separator_reverse.clone(),
+            Setting::StartCap => config.powerline.start_cap.clone().unwrap_or_default(),
+            Setting::EndCap => config.powerline.end_cap.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Toggle or cycle a non-text setting.
+    pub fn activate(self, config: &mut Config) {
+        match self {
+            Setting::FlexMode => config.flex_mode = cycle(&FLEX_MODES, &config.flex_mode),
+            Setting::GlobalBold => config.global_bold = !config.global_bold,
+            Setting::InheritSeparatorColors => {
+                config.inherit_separator_colors = !config.inherit_separator_colors
+            }
+            Setting::IconSet => config.icon_set = cycle(IconSet::names(), &config.icon_set),
+            Setting::NumberFormat => {
+                config.number_format = cycle(&["plain", "grouped"], &config.number_format)
+            }
+            _ => {}
+        }
+    }
+
+    /// Store an edited text value. An empty cap removes it.
+    pub fn set_text(self, config: &mut Config, text: String) {
+        match self {
+            Setting::Separator => config.default_separator = text,
+            Setting::Padding => config.default_padding = text,
+            Setting::PowerlineSeparator => config.powerline.separator = text,
+            Setting::PowerlineSeparatorReverse => config.powerline.separator_reverse = text,
+            Setting::StartCap => config.powerline.start_cap = Some(text).filter(|t| !t.is_empty()),
+            Setting::EndCap => config.powerline.end_cap = Some(text).filter(|t| !t.is_empty()),
+            _ => {}
+        }
+    }
+}
+
+pub fn draw_settings_panel(f: &mut ratatui::Frame, state: &TuiState, area: Rect) {
+    let text: Vec<Line> = SETTINGS
+        .iter()
+        .enumerate()
+        .map(|(i, setting)| {
+            let selected = i == state.settings_cursor;
+            let value = match &state.settings_edit {
+                Some(buffer) if selected => format!("\"{buffer}_\""),
+                _ if setting.is_text() => format!("\"{}\"", setting.value(&state.config)),
+                _ => setting.value(&state.config),
+            };
+            let (marker, style) = if selected {
+                (
+                    ">",
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                (" ", Style::default().fg(Color::White))
+            };
+            Line::from(Span::styled(
+                format!("  {marker} {}: {value}", setting.label()),
+                style,
+            ))
+        })
+        .collect();
+
+    let title = if state.settings_edit.is_some() {
+        "Global Settings (typing: Enter to apply, Esc to cancel)"
+    } else {
+        "Global Settings (Enter to toggle/cycle/edit)"
+    };
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let paragraph = Paragraph::new(text).block(block);
+    f.render_widget(paragraph, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flex_mode_cycles() {
+        let mut config = Config::default();
+        assert_eq!(config.flex_mode, "full-minus-40");
+        Setting::FlexMode.activate(&mut config);
+        assert_eq!(config.flex_mode, "full");
+        Setting::FlexMode.activate(&mut config);
+        Setting::FlexMode.activate(&mut config);
+        assert_eq!(config.flex_mode, "full-minus-40");
+    }
+
+    #[test]
+    fn test_empty_cap_clears_it() {
+        let mut config = Config::default();
+        Setting::StartCap.set_text(&mut config, "\u{E0B6}".into());
+        assert_eq!(config.powerline.start_cap.as_deref(), Some("\u{E0B6}"));
+        Setting::StartCap.set_text(&mut config, String::new());
+        assert_eq!(config.powerline.start_cap, None);
+    }
+}
@@ -3,7 +3,7 @@ use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph};
 
-use crate::themes::Theme;
+use crate::themes::{ROLES, Theme};
 
 use super::TuiState;
 
@@ -54,20 +54,7 @@ fn draw_theme_preview(f: &mut ratatui::Frame, state: &TuiState, area: Rect) {
     let theme_name = themes.get(state.theme_cursor).unwrap_or(&"default");
     let theme = Theme::get(theme_name);
 
-    let roles = [
-        ("model", "Model color"),
-        ("context_ok", "Context OK"),
-        ("context_warn", "Context Warning"),
-        ("context_critical", "Context Critical"),
-        ("git_branch", "Git branch"),
-        ("git_clean", "Git clean"),
-        ("git_dirty", "Git dirty"),
-        ("cost", "Cost"),
-        ("duration", "Duration"),
-        ("separator_fg", "Separator"),
-    ];
-
-    let lines: Vec<Line> = roles
+    let lines: Vec<Line> = ROLES
         .iter()
         .map(|(role, label)| {
             let color_str = theme.color(role).unwrap_or("(none)");
@@ -92,6 +79,8 @@ fn parse_preview_color(s: &str) -> Color {
         let g = u8::from_str_radix(&s[3..5], 16).unwrap_or(255);
         let b = u8::from_str_radix(&s[5..7], 16).unwrap_or(255);
         Color::Rgb(r, g, b)
+    } else if let Ok(index) = s.parse::<u8>() {
+        Color::Indexed(index)
     } else {
         match s {
             "black" => Color::Black,
@@ -114,3 +103,16 @@ fn parse_preview_color(s: &str) -> Color {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_color_ansi256() {
+        assert_eq!(parse_preview_color("208"), Color::Indexed(208));
+        assert_eq!(parse_preview_color("0"), Color::Indexed(0));
+        assert_eq!(parse_preview_color("256"), Color::White);
+        assert_eq!(parse_preview_color("#ff8700"), Color::Rgb(255, 135, 0));
+    }
+}
@@ -0,0 +1,140 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::cache::short_hash;
+use super::data::SessionData;
+use super::traits::{Widget, WidgetConfig, WidgetOutput};
+
+/// Heartbeats older than this (seconds) are not counted, unless the
+/// widget's `ttl` metadata says otherwise.
+const DEFAULT_TTL_SECS: u64 = 60;
+
+/// How many agents are running at once, counted from heartbeats: every
+/// render touches a file for its session and agent under
+/// `<data dir>/agents`, and files touched within the TTL are live.
+pub struct AgentCountWidget;
+
+fn heartbeat_dir() -> PathBuf {
+    crate::storage::data_dir().join("agents")
+}
+
+/// One heartbeat file per session and agent name, named by a stable hash
+/// so the file survives upgrades.
+fn heartbeat_name(session: &str, agent: Option<&str>) -> String {
+    short_hash(&format!("{session}\0{}", agent.unwrap_or_default()))
+}
+
+/// Touch `name` in `dir` and count heartbeats younger than `ttl`, deleting
+/// the stale ones.
+fn beat_and_count(dir: &Path, name: &str, ttl: Duration) -> usize {
+    let _ = fs::create_dir_all(dir);
+    let _ = fs::write(dir.join(name), "");
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let now = SystemTime::now();
+    let mut live = 0;
+    for entry in entries.flatten() {
+        let age = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .map(|t| now.duration_since(t).unwrap_or_default());
+        match age {
+            Some(age) if age < ttl => live += 1,
+            _ => {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+    live
+}
+
+impl Widget for AgentCountWidget {
+    fn name(&self) -> &str {
+        "agent-count"
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let hidden = WidgetOutput {
+            text: String::new(),
+            display_width: 0,
+            priority: 45,
+            visible: false,
+            color_hint: None,
+        };
+        // Without a session id this invocation can't be told apart from others.
+        let Some(session) = data.session_id.as_deref() else {
+            return hidden;
+        };
+        let agent = data.agent.as_ref().and_then(|a| a.name.as_deref());
+        let ttl = config
+            .metadata
+            .get("ttl")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_TTL_SECS);
+
+        let count = beat_and_count(
+            &heartbeat_dir(),
+            &heartbeat_name(session, agent),
+            Duration::from_secs(ttl),
+        );
+        // A lone agent isn't worth the space.
+        if count < 2 {
+            return hidden;
+        }
+
+        let text = if config.raw_value {
+            count.to_string()
+        } else {
+            format!("{count} agents")
+        };
+        let display_width = text.len();
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 45,
+            visible: true,
+            color_hint: None,
+        }
+    }
+
+    fn hidden_reason(&self, data: &SessionData, _config: &WidgetConfig) -> Option<String> {
+        Some(match data.session_id {
+            None => "no session id".into(),
+            Some(_) => "no other live agents".into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_fresh_heartbeats_and_drops_stale() {
+        let dir = std::env::temp_dir().join(format!("claude-status-agents-{}", std::process::id()));
+        let ttl = Duration::from_secs(60);
+        assert_eq!(beat_and_count(&dir, "a", ttl), 1);
+        assert_eq!(beat_and_count(&dir, "b", ttl), 2);
+        assert_eq!(beat_and_count(&dir, "a", ttl), 2);
+
+        // With a zero TTL every other heartbeat is stale and removed.
+        assert_eq!(beat_and_count(&dir, "c", Duration::ZERO), 0);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_heartbeat_is_per_session_and_agent() {
+        assert_eq!(heartbeat_name("s1", None), heartbeat_name("s1", None));
+        assert_ne!(heartbeat_name("s1", None), heartbeat_name("s2", None));
+        assert_ne!(
+            heartbeat_name("s1", None),
+            heartbeat_name("s1", Some("reviewer"))
+        );
+        // Pinned: a different name after an upgrade would orphan live beats.
+        assert_eq!(heartbeat_name("s1", None), "aa54c8e424fb8595");
+    }
+}
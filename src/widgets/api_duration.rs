@@ -1,4 +1,5 @@
 use super::data::SessionData;
+use super::format;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
 
 pub struct ApiDurationWidget;
@@ -48,7 +49,7 @@ impl Widget for ApiDurationWidget {
             }
         };
 
-        let pct = (api_ms as f64 / total_ms as f64 * 100.0) as u64;
+        let pct = format::percent_text(api_ms as f64 / total_ms as f64 * 100.0, config);
         let pct_str = format!("{}%", pct);
 
         let text = if config.raw_value {
@@ -0,0 +1,92 @@
+use chrono::Utc;
+use unicode_width::UnicodeWidthStr;
+
+use super::block_timer::block_elapsed_ms;
+use super::data::SessionData;
+use super::format;
+use super::traits::{Widget, WidgetConfig, WidgetOutput, pro_history_enabled, pro_history_reason};
+use crate::storage::CostTracker;
+
+/// Blocks per week at the burn-rate widget's safe pace (8 hours a day).
+const BLOCKS_PER_WEEK: f64 = 56.0 / 5.0;
+
+/// Spend recorded since the current 5-hour block started, across sessions.
+pub struct BlockCostWidget;
+
+/// Spend recorded since the current block started, and the block's soft
+/// limit (`block_limit`, or the weekly limit spread over a week's blocks).
+/// None without a block or the history database.
+pub(super) fn block_spend(data: &SessionData, config: &WidgetConfig) -> Option<(f64, f64)> {
+    let elapsed_ms = block_elapsed_ms(data)?;
+    let tracker = CostTracker::open().ok()?;
+    let block_start = Utc::now().timestamp() - (elapsed_ms / 1000) as i64;
+    let spent = tracker.total_cost_since(block_start);
+    let block_limit: f64 = config
+        .metadata
+        .get("block_limit")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_else(|| {
+            data.weekly_limit().unwrap_or(config.settings.weekly_limit) / BLOCKS_PER_WEEK
+        });
+    Some((spent, block_limit))
+}
+
+/// Color for spend as a fraction of the block's soft limit.
+fn color_for(fraction: f64) -> &'static str {
+    if fraction < 0.75 {
+        "green"
+    } else if fraction < 1.0 {
+        "yellow"
+    } else {
+        "red"
+    }
+}
+
+impl Widget for BlockCostWidget {
+    fn name(&self) -> &str {
+        "block-cost"
+    }
+
+    fn reads_history(&self) -> bool {
+        true
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let hidden = WidgetOutput {
+            text: String::new(),
+            display_width: 0,
+            priority: 65,
+            visible: false,
+            color_hint: None,
+        };
+        if !pro_history_enabled(config) {
+            return hidden;
+        }
+        let Some((spent, block_limit)) = block_spend(data, config) else {
+            return hidden;
+        };
+        if spent <= 0.0 {
+            return hidden;
+        }
+
+        let amount = format::money(spent, config);
+        let text = if config.raw_value {
+            amount
+        } else {
+            format!("{amount} this block")
+        };
+
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 65,
+            visible: true,
+            color_hint: (block_limit > 0.0).then(|| color_for(spent / block_limit).to_string()),
+        }
+    }
+
+    fn hidden_reason(&self, _data: &SessionData, config: &WidgetConfig) -> Option<String> {
+        pro_history_reason(config)
+    }
+}
@@ -0,0 +1,103 @@
+use unicode_width::UnicodeWidthStr;
+
+use super::block_cost::block_spend;
+use super::block_timer::{BLOCK_DURATION_MS, block_elapsed_ms};
+use super::data::SessionData;
+use super::format;
+use super::traits::{Widget, WidgetConfig, WidgetOutput, pro_history_enabled, pro_history_reason};
+
+/// Pacing within the current 5-hour block: one mini-bar for time elapsed,
+/// one for the block's cost budget used. Green while spend keeps pace with
+/// time, yellow when it runs ahead, red over budget.
+pub struct BlockGaugeWidget;
+
+/// Color for the block's pace, from the fractions of its time and budget
+/// used: red once the budget is gone, yellow while spend outruns time.
+fn pace_color(time: f64, budget: f64) -> &'static str {
+    if budget >= 1.0 {
+        "red"
+    } else if budget > time {
+        "yellow"
+    } else {
+        "green"
+    }
+}
+
+impl Widget for BlockGaugeWidget {
+    fn name(&self) -> &str {
+        "block-gauge"
+    }
+
+    fn reads_history(&self) -> bool {
+        true
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let hidden = WidgetOutput {
+            text: String::new(),
+            display_width: 0,
+            priority: 60,
+            visible: false,
+            color_hint: None,
+        };
+        if !pro_history_enabled(config) {
+            return hidden;
+        }
+        let (Some(elapsed_ms), Some((spent, block_limit))) =
+            (block_elapsed_ms(data), block_spend(data, config))
+        else {
+            return hidden;
+        };
+        if block_limit <= 0.0 {
+            return hidden;
+        }
+
+        let time = elapsed_ms as f64 / BLOCK_DURATION_MS as f64;
+        let budget = spent / block_limit;
+        let text = if config.raw_value {
+            format!(
+                "{}% {}%",
+                format::percent(time * 100.0, config),
+                format::percent(budget * 100.0, config)
+            )
+        } else {
+            let width: usize = config
+                .metadata
+                .get("bar_width")
+                .and_then(|w| w.parse().ok())
+                .unwrap_or(8);
+            format!(
+                "{} {}",
+                format::bar(time, width),
+                format::bar(budget.min(1.0), width)
+            )
+        };
+
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 60,
+            visible: true,
+            color_hint: Some(pace_color(time, budget).into()),
+        }
+    }
+
+    fn hidden_reason(&self, _data: &SessionData, config: &WidgetConfig) -> Option<String> {
+        pro_history_reason(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pace_color() {
+        assert_eq!(pace_color(0.5, 0.3), "green");
+        assert_eq!(pace_color(0.5, 0.5), "green");
+        assert_eq!(pace_color(0.5, 0.6), "yellow");
+        assert_eq!(pace_color(0.2, 1.0), "red");
+        assert_eq!(pace_color(1.0, 1.2), "red");
+    }
+}
@@ -1,19 +1,16 @@
 use super::data::SessionData;
+use super::format;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
+use unicode_width::UnicodeWidthStr;
 
-const BLOCK_DURATION_MS: u64 = 18_000_000; // 5 hours
+pub(super) const BLOCK_DURATION_MS: u64 = 18_000_000; // 5 hours
 
 pub struct BlockTimerWidget;
 
-fn format_hm(ms: u64) -> String {
-    let total_mins = ms / 60_000;
-    let hours = total_mins / 60;
-    let mins = total_mins % 60;
-    if hours > 0 {
-        format!("{}h{}m", hours, mins)
-    } else {
-        format!("{}m", mins)
-    }
+/// Milliseconds into the current 5-hour block, counted from session start.
+pub(super) fn block_elapsed_ms(data: &SessionData) -> Option<u64> {
+    let duration_ms = data.cost.as_ref()?.total_duration_ms?;
+    Some(duration_ms % BLOCK_DURATION_MS)
 }
 
 impl Widget for BlockTimerWidget {
@@ -22,21 +19,8 @@ impl Widget for BlockTimerWidget {
     }
 
     fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
-        let cost = match &data.cost {
-            Some(c) => c,
-            None => {
-                return WidgetOutput {
-                    text: String::new(),
-                    display_width: 0,
-                    priority: 55,
-                    visible: false,
-                    color_hint: None,
-                };
-            }
-        };
-
-        let duration_ms = match cost.total_duration_ms {
-            Some(d) => d,
+        let block_elapsed = match block_elapsed_ms(data) {
+            Some(e) => e,
             None => {
                 return WidgetOutput {
                     text: String::new(),
@@ -48,9 +32,8 @@ impl Widget for BlockTimerWidget {
             }
         };
 
-        let block_elapsed = duration_ms % BLOCK_DURATION_MS;
         let block_remaining = BLOCK_DURATION_MS - block_elapsed;
-        let remaining_str = format_hm(block_remaining);
+        let remaining_str = format::elapsed(block_remaining / 1000);
 
         let text = if config
             .metadata
@@ -64,20 +47,12 @@ impl Widget for BlockTimerWidget {
                 .and_then(|w| w.parse().ok())
                 .unwrap_or(16);
             let fraction = block_elapsed as f64 / BLOCK_DURATION_MS as f64;
-            let filled = (fraction * bar_width as f64).round() as usize;
-            let filled = filled.min(bar_width);
-            let empty = bar_width - filled;
-            format!(
-                "{}{} {}",
-                "▓".repeat(filled),
-                "░".repeat(empty),
-                remaining_str
-            )
+            format!("{} {}", format::bar(fraction, bar_width), remaining_str)
         } else {
             format!("Block: {} left", remaining_str)
         };
 
-        let display_width = text.len();
+        let display_width = UnicodeWidthStr::width(text.as_str());
         WidgetOutput {
             text,
             display_width,
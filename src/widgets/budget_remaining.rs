@@ -0,0 +1,88 @@
+use unicode_width::UnicodeWidthStr;
+
+use super::cost_warning::{spent_this_week, weekly_limit};
+use super::data::SessionData;
+use super::format;
+use super::traits::{Widget, WidgetConfig, WidgetOutput, pro_history_enabled, pro_history_reason};
+
+/// Dollars left in this week's budget (e.g. "$142 left"), or how far over
+/// it the week has gone.
+pub struct BudgetRemainingWidget;
+
+/// Color for what's left as a fraction of the limit: greener with room to
+/// spare, red near zero, magenta once over.
+fn color_for(left_fraction: f64) -> &'static str {
+    if left_fraction < 0.0 {
+        "magenta"
+    } else if left_fraction < 0.2 {
+        "red"
+    } else if left_fraction < 0.5 {
+        "yellow"
+    } else {
+        "green"
+    }
+}
+
+impl Widget for BudgetRemainingWidget {
+    fn name(&self) -> &str {
+        "budget-remaining"
+    }
+
+    fn reads_history(&self) -> bool {
+        true
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let hidden = WidgetOutput {
+            text: String::new(),
+            display_width: 0,
+            priority: 75,
+            visible: false,
+            color_hint: None,
+        };
+        if !pro_history_enabled(config) {
+            return hidden;
+        }
+        let limit = weekly_limit(data, config);
+        if limit <= 0.0 {
+            return hidden;
+        }
+        let Some(spent) = spent_this_week() else {
+            return hidden;
+        };
+
+        let left = limit - spent;
+        let text = match (left >= 0.0, config.raw_value) {
+            (true, true) => format::money_whole(left, config),
+            (true, false) => format!("{} left", format::money_whole(left, config)),
+            (false, true) => format!("-{}", format::money_whole(-left, config)),
+            (false, false) => format!("{} over", format::money_whole(-left, config)),
+        };
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 75,
+            visible: true,
+            color_hint: Some(color_for(left / limit).into()),
+        }
+    }
+
+    fn hidden_reason(&self, _data: &SessionData, config: &WidgetConfig) -> Option<String> {
+        pro_history_reason(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_reddens_toward_zero() {
+        assert_eq!(color_for(0.8), "green");
+        assert_eq!(color_for(0.3), "yellow");
+        assert_eq!(color_for(0.05), "red");
+        assert_eq!(color_for(0.0), "red");
+        assert_eq!(color_for(-0.1), "magenta");
+    }
+}
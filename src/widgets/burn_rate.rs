@@ -1,8 +1,10 @@
 use super::data::SessionData;
-use super::traits::{Widget, WidgetConfig, WidgetOutput};
+use super::format;
+use super::traits::{Widget, WidgetConfig, WidgetOutput, pro_history_enabled, pro_history_reason};
 use crate::storage::CostTracker;
 
 use chrono::Utc;
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum BurnStatus {
@@ -70,9 +72,12 @@ impl Widget for BurnRateWidget {
         "burn-rate"
     }
 
-    fn render(&self, _data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
-        // Pro-only: gracefully hidden if not Pro
-        if !crate::license::is_pro() {
+    fn reads_history(&self) -> bool {
+        true
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        if !pro_history_enabled(config) {
             return WidgetOutput {
                 text: String::new(),
                 display_width: 0,
@@ -88,11 +93,14 @@ impl Widget for BurnRateWidget {
             .and_then(|v| v.parse().ok())
             .unwrap_or(60);
 
-        let weekly_limit: f64 = config
-            .metadata
-            .get("weekly_limit")
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(200.0);
+        // The plan's own limit wins over configured ones.
+        let weekly_limit: f64 = data.weekly_limit().unwrap_or_else(|| {
+            config
+                .metadata
+                .get("weekly_limit")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(config.settings.weekly_limit)
+        });
 
         let (rate, status, hours_left) = match Self::calculate(window_minutes, weekly_limit) {
             Some(v) => v,
@@ -112,14 +120,19 @@ impl Widget for BurnRateWidget {
         } else if rate < 0.01 {
             "Burn: idle".into()
         } else if hours_left.is_infinite() || hours_left > 168.0 {
-            format!("Burn: ${:.2}/hr", rate)
+            format!("Burn: {}/hr", format::money(rate, config))
         } else {
             let hours = hours_left as u64;
             let mins = ((hours_left - hours as f64) * 60.0) as u64;
-            format!("Burn: ${:.2}/hr -> limit in {}h {}m", rate, hours, mins)
+            format!(
+                "Burn: {}/hr -> limit in {}h {}m",
+                format::money(rate, config),
+                hours,
+                mins
+            )
         };
 
-        let display_width = text.len();
+        let display_width = UnicodeWidthStr::width(text.as_str());
         WidgetOutput {
             text,
             display_width,
@@ -128,4 +141,8 @@ impl Widget for BurnRateWidget {
             color_hint: status.color_hint(),
         }
     }
+
+    fn hidden_reason(&self, _data: &SessionData, config: &WidgetConfig) -> Option<String> {
+        pro_history_reason(config)
+    }
 }
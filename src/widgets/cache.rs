@@ -0,0 +1,137 @@
+//! Disk-backed memo for widgets that opt in via `Widget::cache_ttl`.
+//!
+//! The status line is re-run on every refresh, so slow widgets (git, custom
+//! commands) would otherwise recompute identical output many times a minute.
+//! Entries live under `<data dir>/cache/<session>/<key>.json`, one file per
+//! key so widgets rendering on separate threads never rewrite each other.
+//! Hosts that send no session_id share one bucket per working directory.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::data::SessionData;
+use super::traits::{WidgetConfig, WidgetOutput};
+
+/// Session directories untouched for this long are removed when a new
+/// session starts caching.
+const STALE_SESSION: Duration = Duration::from_secs(24 * 3600);
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// Unix time in milliseconds.
+    stored_at: i64,
+    output: WidgetOutput,
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderCache {
+    dir: PathBuf,
+}
+
+impl RenderCache {
+    /// Cache under the default data directory.
+    pub fn open() -> Self {
+        Self::at(crate::storage::data_dir().join("cache"))
+    }
+
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The stored output for this widget and its inputs, if younger than `ttl`.
+    pub fn get(
+        &self,
+        data: &SessionData,
+        config: &WidgetConfig,
+        ttl: Duration,
+    ) -> Option<WidgetOutput> {
+        let path = self.entry_path(data, config)?;
+        let contents = fs::read_to_string(path).ok()?;
+        let entry: Entry = serde_json::from_str(&contents).ok()?;
+        let age = chrono::Utc::now().timestamp_millis() - entry.stored_at;
+        (age >= 0 && (age as u128) < ttl.as_millis()).then_some(entry.output)
+    }
+
+    /// Store `output`. Failures are ignored; a missed write only costs a
+    /// recompute on the next refresh.
+    pub fn put(&self, data: &SessionData, config: &WidgetConfig, output: &WidgetOutput) {
+        let Some(path) = self.entry_path(data, config) else {
+            return;
+        };
+        let Some(session_dir) = path.parent() else {
+            return;
+        };
+        if !session_dir.exists() {
+            self.prune();
+            let _ = fs::create_dir_all(session_dir);
+        }
+        let entry = Entry {
+            stored_at: chrono::Utc::now().timestamp_millis(),
+            output: output.clone(),
+        };
+        let Ok(json) = serde_json::to_string(&entry) else {
+            return;
+        };
+        // Write then rename so a concurrent reader never sees a partial file.
+        let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+        if fs::write(&tmp, json).is_ok() {
+            let _ = fs::rename(&tmp, &path);
+        }
+    }
+
+    fn entry_path(&self, data: &SessionData, config: &WidgetConfig) -> Option<PathBuf> {
+        // The NUL keeps a directory bucket from colliding with a session id.
+        let bucket = match data.session_id.as_deref() {
+            Some(session) => short_hash(session),
+            None => short_hash(&format!("\0{}", data.effective_dir()?.display())),
+        };
+        Some(self.dir.join(bucket).join(format!(
+            "{}-{}.json",
+            config.widget_type,
+            inputs_hash(data, config)
+        )))
+    }
+
+    fn prune(&self) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            if is_stale(&entry.path()) {
+                let _ = fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+}
+
+/// Everything a cacheable widget may depend on: its config and the effective
+/// working directory. Widgets that read other payload fields must not opt in.
+fn inputs_hash(data: &SessionData, config: &WidgetConfig) -> String {
+    let cwd = data.effective_dir();
+    let metadata: BTreeMap<_, _> = config.metadata.iter().collect();
+    short_hash(&format!(
+        "{}\0{}\0{:?}\0{:?}\0{:?}",
+        config.widget_type, config.raw_value, metadata, config.settings, cwd
+    ))
+}
+
+/// First 8 bytes of the SHA-256 of `s`, hex: a file name that stays the
+/// same across builds.
+pub(super) fn short_hash(s: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(s.as_bytes());
+    hex::encode(&hasher.finalize()[..8])
+}
+
+fn is_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok())
+        .is_some_and(|age| age > STALE_SESSION)
+}
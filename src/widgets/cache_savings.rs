@@ -0,0 +1,89 @@
+use unicode_width::UnicodeWidthStr;
+
+use super::data::SessionData;
+use super::format;
+use super::traits::{Widget, WidgetConfig, WidgetOutput};
+
+/// USD per million input tokens when no `input_price` is configured; the
+/// payload carries no pricing.
+const DEFAULT_INPUT_PRICE: f64 = 3.0;
+
+/// Cache reads are billed at this fraction of the input price.
+const CACHE_READ_FACTOR: f64 = 0.1;
+
+/// Estimated spend that prompt caching saved on the last request: cache
+/// reads priced as uncached input, minus what they actually cost.
+pub struct CacheSavingsWidget;
+
+fn savings(cache_read_tokens: u64, input_price: f64) -> f64 {
+    cache_read_tokens as f64 * input_price * (1.0 - CACHE_READ_FACTOR) / 1_000_000.0
+}
+
+impl Widget for CacheSavingsWidget {
+    fn name(&self) -> &str {
+        "cache-savings"
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let hidden = WidgetOutput {
+            text: String::new(),
+            display_width: 0,
+            priority: 50,
+            visible: false,
+            color_hint: None,
+        };
+        // Pro-only: gracefully hidden if not Pro
+        if !crate::license::is_pro() {
+            return hidden;
+        }
+        let cache_read = data
+            .context_window
+            .as_ref()
+            .and_then(|cw| cw.current_usage.as_ref())
+            .and_then(|u| u.cache_read_input_tokens)
+            .unwrap_or(0);
+        if cache_read == 0 {
+            return hidden;
+        }
+        let input_price = config
+            .metadata
+            .get("input_price")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_INPUT_PRICE);
+
+        let amount = format::money(savings(cache_read, input_price), config);
+        let text = if config.raw_value {
+            amount
+        } else {
+            format!("saved {amount}")
+        };
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 50,
+            visible: true,
+            color_hint: Some("green".into()),
+        }
+    }
+
+    fn hidden_reason(&self, _data: &SessionData, _config: &WidgetConfig) -> Option<String> {
+        Some(if crate::license::is_pro() {
+            "no cache reads".into()
+        } else {
+            "not Pro".into()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_savings_are_ninety_percent_of_input_price() {
+        assert!((savings(1_000_000, 3.0) - 2.7).abs() < 1e-9);
+        assert!((savings(30_000, 3.0) - 0.081).abs() < 1e-9);
+        assert_eq!(savings(0, 3.0), 0.0);
+    }
+}
@@ -0,0 +1,47 @@
+use super::data::SessionData;
+use super::traits::{Widget, WidgetConfig, WidgetOutput};
+use unicode_width::UnicodeWidthStr;
+
+/// Binary alert shown once context usage crosses the point where Claude Code
+/// is likely to auto-compact. Colored via the theme's `context_critical` role.
+pub struct CompactionWarningWidget;
+
+impl Widget for CompactionWarningWidget {
+    fn name(&self) -> &str {
+        "compaction-warning"
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let threshold: f64 = config
+            .metadata
+            .get("threshold")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(85.0);
+
+        let used = data
+            .context_window
+            .as_ref()
+            .and_then(|cw| cw.used_percentage)
+            .unwrap_or(0.0);
+
+        if used < threshold {
+            return WidgetOutput {
+                text: String::new(),
+                display_width: 0,
+                priority: 98,
+                visible: false,
+                color_hint: None,
+            };
+        }
+
+        let text = config.settings.icons.prefix("warning", "compaction soon");
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 98,
+            visible: true,
+            color_hint: None,
+        }
+    }
+}
@@ -1,10 +1,18 @@
-use super::data::SessionData;
+use super::data::{ContextWindow, SessionData};
+use super::format;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
+use unicode_width::UnicodeWidthStr;
 
-fn context_color_hint(pct: f64) -> Option<String> {
-    if pct < 50.0 {
+pub(super) fn context_color_hint(pct: f64) -> Option<String> {
+    context_color_between(pct, 50.0, 80.0)
+}
+
+/// `context_color_hint` with other thresholds: yellow from `warn`%, red
+/// above `critical`%.
+pub(super) fn context_color_between(pct: f64, warn: f64, critical: f64) -> Option<String> {
+    if pct < warn {
         Some("green".into())
-    } else if pct <= 80.0 {
+    } else if pct <= critical {
         Some("yellow".into())
     } else {
         Some("red".into())
@@ -62,20 +70,16 @@ impl Widget for ContextPercentageWidget {
             .map(|v| v == "true")
             .unwrap_or(false)
         {
-            let filled = ((display_pct / 100.0) * 10.0).round() as usize;
-            let filled = filled.min(10);
-            let empty = 10 - filled;
             format!(
-                "{}{} {}%",
-                "▓".repeat(filled),
-                "░".repeat(empty),
-                display_pct as u64,
+                "{} {}%",
+                format::bar(display_pct / 100.0, 10),
+                format::percent_text(display_pct, config)
             )
         } else {
-            format!("{}%", display_pct as u64)
+            format!("{}%", format::percent_text(display_pct, config))
         };
 
-        let display_width = text.len();
+        let display_width = UnicodeWidthStr::width(text.as_str());
         WidgetOutput {
             text,
             display_width,
@@ -84,6 +88,123 @@ impl Widget for ContextPercentageWidget {
             color_hint: context_color_hint(pct),
         }
     }
+
+    fn hidden_reason(&self, data: &SessionData, _config: &WidgetConfig) -> Option<String> {
+        let pct = data
+            .context_window
+            .as_ref()
+            .and_then(|cw| cw.used_percentage);
+        pct.is_none().then(|| "no context data".into())
+    }
+}
+
+/// Headroom left in the context window. The bar's filled part is what
+/// remains, so consumed context eats into it from the right.
+pub struct ContextRemainingWidget;
+
+impl Widget for ContextRemainingWidget {
+    fn name(&self) -> &str {
+        "context-remaining"
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let remaining = data.context_window.as_ref().and_then(|cw| {
+            cw.remaining_percentage
+                .or_else(|| cw.used_percentage.map(|used| 100.0 - used))
+        });
+        let Some(remaining) = remaining else {
+            return WidgetOutput {
+                text: String::new(),
+                display_width: 0,
+                priority: 85,
+                visible: false,
+                color_hint: None,
+            };
+        };
+        let remaining = remaining.clamp(0.0, 100.0);
+
+        let text = if config.metadata.get("tokens").map(|v| v == "true") == Some(true)
+            && let Some(left) = data.context_window.as_ref().and_then(remaining_tokens)
+        {
+            format!("{} left", ContextLengthWidget::format_compact(left))
+        } else if config.metadata.get("bar").map(|v| v == "true") == Some(true) {
+            format!(
+                "{} {}%",
+                format::bar(remaining / 100.0, 10),
+                format::percent_text(remaining, config)
+            )
+        } else if config.raw_value {
+            format!("{}%", format::percent_text(remaining, config))
+        } else {
+            format!("{}% left", format::percent_text(remaining, config))
+        };
+
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 85,
+            visible: true,
+            // Inverted: plenty left is green, little left is red
+            color_hint: context_color_hint(100.0 - remaining),
+        }
+    }
+}
+
+fn remaining_tokens(cw: &ContextWindow) -> Option<u64> {
+    let size = cw.context_window_size?;
+    let usage = cw.current_usage.as_ref()?;
+    let used = usage.input_tokens.unwrap_or(0)
+        + usage.cache_creation_input_tokens.unwrap_or(0)
+        + usage.cache_read_input_tokens.unwrap_or(0);
+    Some(size.saturating_sub(used))
+}
+
+/// Below this fraction of the window left, headroom is shown in red.
+const LOW_HEADROOM: f64 = 0.1;
+
+/// Absolute tokens left before the context window fills.
+pub struct HeadroomWidget;
+
+impl Widget for HeadroomWidget {
+    fn name(&self) -> &str {
+        "headroom"
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let window = data.context_window.as_ref().and_then(|cw| {
+            let size = cw.context_window_size.filter(|&s| s > 0)?;
+            Some((size, cw.total_input_tokens?))
+        });
+        let Some((size, used)) = window else {
+            return WidgetOutput {
+                text: String::new(),
+                display_width: 0,
+                priority: 70,
+                visible: false,
+                color_hint: None,
+            };
+        };
+        let left = size.saturating_sub(used);
+
+        let compact = ContextLengthWidget::format_compact(left);
+        let text = if config.raw_value {
+            compact
+        } else {
+            format!("{compact} left")
+        };
+
+        let low =
+            (left as f64) < size as f64 * LOW_HEADROOM || data.exceeds_200k_tokens == Some(true);
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 70,
+            visible: true,
+            color_hint: low.then(|| "red".to_string()),
+        }
+    }
 }
 
 pub struct ContextLengthWidget;
@@ -139,12 +260,12 @@ impl Widget for ContextLengthWidget {
             + usage.cache_read_input_tokens.unwrap_or(0);
 
         let text = if config.raw_value {
-            total.to_string()
+            format::count(total, config)
         } else {
             Self::format_compact(total)
         };
 
-        let display_width = text.len();
+        let display_width = UnicodeWidthStr::width(text.as_str());
         WidgetOutput {
             text,
             display_width,
@@ -154,3 +275,45 @@ impl Widget for ContextLengthWidget {
         }
     }
 }
+
+/// The model's context window capacity (e.g. "200K"), the ceiling the other
+/// context widgets measure against.
+pub struct WindowSizeWidget;
+
+impl Widget for WindowSizeWidget {
+    fn name(&self) -> &str {
+        "window-size"
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let Some(size) = data
+            .context_window
+            .as_ref()
+            .and_then(|cw| cw.context_window_size)
+            .filter(|&s| s > 0)
+        else {
+            return WidgetOutput {
+                text: String::new(),
+                display_width: 0,
+                priority: 40,
+                visible: false,
+                color_hint: None,
+            };
+        };
+
+        let text = if config.raw_value {
+            format::count(size, config)
+        } else {
+            ContextLengthWidget::format_compact(size)
+        };
+
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 40,
+            visible: true,
+            color_hint: None,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use unicode_width::UnicodeWidthStr;
+
+use super::context::{context_color_between, context_color_hint};
+use super::data::SessionData;
+use super::format;
+use super::traits::{Widget, WidgetConfig, WidgetOutput};
+use crate::icons::IconSet;
+
+/// Empty to full, one glyph per quarter.
+const GAUGE: [&str; 5] = ["○", "◔", "◑", "◕", "●"];
+
+/// Context usage as a single gauge glyph, for lines with no room to spare.
+/// Without Nerd Font icons it shows the percentage instead.
+pub struct ContextGaugeWidget;
+
+/// The gauge glyph nearest to `pct`.
+fn glyph(pct: f64) -> &'static str {
+    let bucket = (pct.clamp(0.0, 100.0) / 25.0).round() as usize;
+    GAUGE[bucket]
+}
+
+impl Widget for ContextGaugeWidget {
+    fn name(&self) -> &str {
+        "context-gauge"
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let Some(pct) = data
+            .context_window
+            .as_ref()
+            .and_then(|cw| cw.used_percentage)
+        else {
+            return WidgetOutput {
+                text: String::new(),
+                display_width: 0,
+                priority: 85,
+                visible: false,
+                color_hint: None,
+            };
+        };
+        // Same colors as context-percentage unless `warn_pct`/`critical_pct`
+        // move the thresholds.
+        let percent = |key: &str| config.metadata.get(key).and_then(|v| v.parse().ok());
+        let color_hint = match (percent("warn_pct"), percent("critical_pct")) {
+            (None, None) => context_color_hint(pct),
+            (warn, critical) => {
+                context_color_between(pct, warn.unwrap_or(50.0), critical.unwrap_or(80.0))
+            }
+        };
+
+        let text = if config.settings.icons == IconSet::Nerd {
+            glyph(pct).to_string()
+        } else {
+            format!("{}%", format::percent_text(pct, config))
+        };
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 85,
+            visible: true,
+            color_hint,
+        }
+    }
+
+    fn hidden_reason(&self, data: &SessionData, _config: &WidgetConfig) -> Option<String> {
+        let pct = data
+            .context_window
+            .as_ref()
+            .and_then(|cw| cw.used_percentage);
+        pct.is_none().then(|| "no context data".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glyph_buckets() {
+        assert_eq!(glyph(0.0), "○");
+        assert_eq!(glyph(12.0), "○");
+        assert_eq!(glyph(13.0), "◔");
+        assert_eq!(glyph(50.0), "◑");
+        assert_eq!(glyph(80.0), "◕");
+        assert_eq!(glyph(95.0), "●");
+        assert_eq!(glyph(140.0), "●");
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr;
+
+use super::data::SessionData;
+use super::format;
+use super::traits::{Widget, WidgetConfig, WidgetOutput};
+
+/// Sessions not seen for this long are dropped from the state file.
+const STALE_SECS: i64 = 24 * 3600;
+
+/// Context usage with an arrow showing which way it moved since the last
+/// render of the same session.
+pub struct ContextTrendWidget;
+
+#[derive(Serialize, Deserialize)]
+struct Reading {
+    used: f64,
+    /// Unix seconds.
+    at: i64,
+}
+
+fn state_path() -> PathBuf {
+    crate::storage::data_dir().join("context-trend.json")
+}
+
+/// Record `used` for `session` and return the previous reading, if any.
+fn swap_reading(session: &str, used: f64) -> Option<f64> {
+    let path = state_path();
+    let mut readings: HashMap<String, Reading> = fs::read_to_string(&path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    let now = chrono::Utc::now().timestamp();
+    readings.retain(|_, r| now - r.at < STALE_SECS);
+    let previous = readings
+        .insert(session.to_string(), Reading { used, at: now })
+        .map(|r| r.used);
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string(&readings) {
+        let _ = fs::write(&path, json);
+    }
+    previous
+}
+
+impl Widget for ContextTrendWidget {
+    fn name(&self) -> &str {
+        "context-trend"
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let Some(used) = data
+            .context_window
+            .as_ref()
+            .and_then(|cw| cw.used_percentage)
+        else {
+            return WidgetOutput {
+                text: String::new(),
+                display_width: 0,
+                priority: 80,
+                visible: false,
+                color_hint: None,
+            };
+        };
+
+        // Without a session id there's nothing safe to compare against.
+        let previous = data
+            .session_id
+            .as_deref()
+            .and_then(|session| swap_reading(session, used));
+
+        let current = format::percent_text(used, config);
+        let text = match previous {
+            None => format!("{current}%"),
+            Some(prev) => {
+                let delta = format::percent((used - prev).abs(), config);
+                if delta == 0 {
+                    format!("{current}% →")
+                } else if used > prev {
+                    format!("{current}% ↑{delta}")
+                } else {
+                    format!("{current}% ↓{delta}")
+                }
+            }
+        };
+
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 80,
+            visible: true,
+            color_hint: None,
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use super::data::SessionData;
+use super::format;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
+use unicode_width::UnicodeWidthStr;
 
 pub struct SessionCostWidget;
 
@@ -35,7 +37,7 @@ impl Widget for SessionCostWidget {
             }
         };
 
-        let cost_str = format!("${:.2}", total_usd);
+        let cost_str = format::money(total_usd, config);
 
         let text = if config.raw_value {
             cost_str
@@ -49,7 +51,7 @@ impl Widget for SessionCostWidget {
                 if duration_ms > 0 {
                     let hours = duration_ms as f64 / 3_600_000.0;
                     let rate = total_usd / hours;
-                    format!("{} (${:.2}/hr)", cost_str, rate)
+                    format!("{} ({}/hr)", cost_str, format::money(rate, config))
                 } else {
                     cost_str
                 }
@@ -60,7 +62,7 @@ impl Widget for SessionCostWidget {
             cost_str
         };
 
-        let display_width = text.len();
+        let display_width = UnicodeWidthStr::width(text.as_str());
         WidgetOutput {
             text,
             display_width,
@@ -69,4 +71,9 @@ impl Widget for SessionCostWidget {
             color_hint: None,
         }
     }
+
+    fn hidden_reason(&self, data: &SessionData, _config: &WidgetConfig) -> Option<String> {
+        let cost = data.cost.as_ref().and_then(|c| c.total_cost_usd);
+        cost.is_none().then(|| "no cost data".into())
+    }
 }
@@ -1,29 +1,47 @@
 use super::data::SessionData;
-use super::traits::{Widget, WidgetConfig, WidgetOutput};
+use super::format;
+use super::traits::{Widget, WidgetConfig, WidgetOutput, pro_history_enabled, pro_history_reason};
 use crate::storage::CostTracker;
 
 use chrono::{Datelike, Utc};
+use unicode_width::UnicodeWidthStr;
 
 pub struct CostWarningWidget;
 
-impl CostWarningWidget {
-    /// Calculate the start of the current week (Monday 00:00 UTC) as Unix timestamp.
-    fn week_start() -> i64 {
-        let now = Utc::now();
-        let days_since_monday = now.weekday().num_days_from_monday() as i64;
-        let start_of_today = now
-            .date_naive()
-            .and_hms_opt(0, 0, 0)
-            .unwrap()
-            .and_utc()
-            .timestamp();
-        start_of_today - (days_since_monday * 86400)
-    }
+/// Calculate the start of the current week (Monday 00:00 UTC) as Unix timestamp.
+fn week_start() -> i64 {
+    let now = Utc::now();
+    let days_since_monday = now.weekday().num_days_from_monday() as i64;
+    let start_of_today = now
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc()
+        .timestamp();
+    start_of_today - (days_since_monday * 86400)
+}
+
+/// Spend recorded since the start of the week; None without a history database.
+pub(super) fn spent_this_week() -> Option<f64> {
+    let tracker = CostTracker::open().ok()?;
+    Some(tracker.total_cost_since(week_start()))
+}
+
+/// The weekly limit for a widget: the plan's own, then `weekly_limit`
+/// metadata, then `[stats] weekly_limit`.
+pub(super) fn weekly_limit(data: &SessionData, config: &WidgetConfig) -> f64 {
+    data.weekly_limit().unwrap_or_else(|| {
+        config
+            .metadata
+            .get("weekly_limit")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(config.settings.weekly_limit)
+    })
+}
 
+impl CostWarningWidget {
     fn calculate(weekly_limit: f64) -> Option<(f64, f64)> {
-        let tracker = CostTracker::open().ok()?;
-        let since = Self::week_start();
-        let spent = tracker.total_cost_since(since);
+        let spent = spent_this_week()?;
         let pct = if weekly_limit > 0.0 {
             (spent / weekly_limit) * 100.0
         } else {
@@ -38,9 +56,12 @@ impl Widget for CostWarningWidget {
         "cost-warning"
     }
 
-    fn render(&self, _data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
-        // Pro-only: gracefully hidden if not Pro
-        if !crate::license::is_pro() {
+    fn reads_history(&self) -> bool {
+        true
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        if !pro_history_enabled(config) {
             return WidgetOutput {
                 text: String::new(),
                 display_width: 0,
@@ -50,11 +71,7 @@ impl Widget for CostWarningWidget {
             };
         }
 
-        let weekly_limit: f64 = config
-            .metadata
-            .get("weekly_limit")
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(200.0);
+        let weekly_limit = weekly_limit(data, config);
 
         let warn_threshold: f64 = config
             .metadata
@@ -94,31 +111,22 @@ impl Widget for CostWarningWidget {
             };
         }
 
-        let (text, color) = if fraction >= critical_threshold {
-            (
-                format!(
-                    "{} {:.0}% of weekly limit (${:.0}/${:.0})",
-                    "\u{1F534}", // red circle
-                    pct,
-                    spent,
-                    weekly_limit
-                ),
-                "red".to_string(),
-            )
+        let (icon, color) = if fraction >= critical_threshold {
+            ("critical", "red".to_string())
         } else {
-            (
-                format!(
-                    "{} {:.0}% of weekly limit (${:.0}/${:.0})",
-                    "\u{26A0}\u{FE0F}", // warning sign
-                    pct,
-                    spent,
-                    weekly_limit
-                ),
-                "yellow".to_string(),
-            )
+            ("warning", "yellow".to_string())
         };
+        let text = config.settings.icons.prefix(
+            icon,
+            &format!(
+                "{}% of weekly limit ({}/{})",
+                format::percent_text(pct, config),
+                format::money_whole(spent, config),
+                format::money_whole(weekly_limit, config)
+            ),
+        );
 
-        let display_width = text.len();
+        let display_width = UnicodeWidthStr::width(text.as_str());
         WidgetOutput {
             text,
             display_width,
@@ -127,4 +135,8 @@ impl Widget for CostWarningWidget {
             color_hint: Some(color),
         }
     }
+
+    fn hidden_reason(&self, _data: &SessionData, config: &WidgetConfig) -> Option<String> {
+        pro_history_reason(config)
+    }
 }
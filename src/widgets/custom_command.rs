@@ -1,29 +1,10 @@
 use super::data::SessionData;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
-use std::fs;
 use std::process::Command;
-use std::time::{Duration, SystemTime};
+use std::time::Duration;
 
 pub struct CustomCommandWidget;
 
-fn cache_path(command: &str) -> std::path::PathBuf {
-    let hash: String = command
-        .bytes()
-        .take(16)
-        .map(|b| format!("{:02x}", b))
-        .collect();
-    std::path::PathBuf::from(format!("/tmp/claude-status-cmd-{hash}"))
-}
-
-fn read_cache(path: &std::path::Path) -> Option<String> {
-    let metadata = fs::metadata(path).ok()?;
-    let modified = metadata.modified().ok()?;
-    if SystemTime::now().duration_since(modified).ok()? > Duration::from_secs(10) {
-        return None;
-    }
-    fs::read_to_string(path).ok()
-}
-
 fn run_command(cmd: &str) -> Option<String> {
     let child = Command::new("/bin/sh")
         .arg("-c")
@@ -53,6 +34,14 @@ impl Widget for CustomCommandWidget {
         "custom-command"
     }
 
+    fn is_blocking(&self) -> bool {
+        true
+    }
+
+    fn cache_ttl(&self) -> Option<Duration> {
+        Some(Duration::from_secs(10))
+    }
+
     fn render(&self, _data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
         let cmd = match config.metadata.get("command") {
             Some(c) if !c.is_empty() => c,
@@ -67,24 +56,16 @@ impl Widget for CustomCommandWidget {
             }
         };
 
-        let path = cache_path(cmd);
-        let text = if let Some(cached) = read_cache(&path) {
-            cached
-        } else {
-            match run_command(cmd) {
-                Some(result) => {
-                    let _ = fs::write(&path, &result);
-                    result
-                }
-                None => {
-                    return WidgetOutput {
-                        text: String::new(),
-                        display_width: 0,
-                        priority: 40,
-                        visible: false,
-                        color_hint: None,
-                    };
-                }
+        let text = match run_command(cmd) {
+            Some(result) => result,
+            None => {
+                return WidgetOutput {
+                    text: String::new(),
+                    display_width: 0,
+                    priority: 40,
+                    visible: false,
+                    color_hint: None,
+                };
             }
         };
 
@@ -1,4 +1,5 @@
 use super::data::SessionData;
+use super::format;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
 use unicode_width::UnicodeWidthStr;
 
@@ -11,7 +12,7 @@ impl Widget for CustomTextWidget {
 
     fn render(&self, _data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
         let text = match config.metadata.get("text") {
-            Some(t) if !t.is_empty() => t.clone(),
+            Some(t) if !t.is_empty() => format::fit_width(t.clone(), config),
             _ => {
                 return WidgetOutput {
                     text: String::new(),
@@ -1,17 +1,13 @@
 use std::path::Path;
 
+use unicode_width::UnicodeWidthStr;
+
 use super::data::SessionData;
+use super::format;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
 
 pub struct CwdWidget;
 
-fn get_working_dir(data: &SessionData) -> Option<String> {
-    data.workspace
-        .as_ref()
-        .and_then(|w| w.current_dir.clone())
-        .or_else(|| data.cwd.clone())
-}
-
 fn home_dir() -> Option<String> {
     std::env::var("HOME").ok()
 }
@@ -71,8 +67,8 @@ impl Widget for CwdWidget {
     }
 
     fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
-        let dir = match get_working_dir(data) {
-            Some(d) => d,
+        let dir = match data.effective_dir() {
+            Some(d) => d.to_string_lossy().into_owned(),
             None => {
                 return WidgetOutput {
                     text: String::new(),
@@ -98,8 +94,9 @@ impl Widget for CwdWidget {
                 .map(|f| f.to_string_lossy().to_string())
                 .unwrap_or_else(|| dir.clone())
         };
+        let text = format::fit_width(text, config);
 
-        let display_width = text.len();
+        let display_width = UnicodeWidthStr::width(text.as_str());
         WidgetOutput {
             text,
             display_width,
@@ -108,4 +105,13 @@ impl Widget for CwdWidget {
             color_hint: None,
         }
     }
+
+    /// Just the directory name, whatever path style is configured.
+    fn render_compact(&self, data: &SessionData, config: &WidgetConfig) -> Option<WidgetOutput> {
+        let mut config = config.clone();
+        config
+            .metadata
+            .retain(|key, _| !matches!(key.as_str(), "fish_style" | "full" | "segments"));
+        Some(self.render(data, &config))
+    }
 }
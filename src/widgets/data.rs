@@ -1,6 +1,9 @@
-use serde::Deserialize;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 
-#[derive(Debug, Deserialize, Default)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SessionData {
     pub cwd: Option<String>,
     pub session_id: Option<String>,
@@ -14,26 +17,242 @@ pub struct SessionData {
     pub exceeds_200k_tokens: Option<bool>,
     pub vim: Option<Vim>,
     pub agent: Option<Agent>,
+    pub limits: Option<Limits>,
+}
+
+impl SessionData {
+    /// Time since the transcript was last written, i.e. since the last
+    /// message. None without a readable transcript.
+    pub fn transcript_age(&self) -> Option<Duration> {
+        let path = self.transcript_path.as_deref()?;
+        let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+        SystemTime::now().duration_since(modified).ok()
+    }
+
+    /// The plan's weekly limit, when the payload reports one.
+    pub fn weekly_limit(&self) -> Option<f64> {
+        self.limits.as_ref()?.weekly()
+    }
+
+    /// The directory that directory-dependent widgets (cwd, git) work in:
+    /// `workspace.current_dir`, then `cwd`, then the process's own working
+    /// directory. Empty strings count as unset.
+    pub fn effective_dir(&self) -> Option<PathBuf> {
+        self.workspace
+            .as_ref()
+            .and_then(|w| w.current_dir.as_deref())
+            .into_iter()
+            .chain(self.cwd.as_deref())
+            .find(|d| !d.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::current_dir().ok())
+    }
+
+    /// Record this payload's running cost in the history database, as a
+    /// per-message delta (see `CostTracker::record_total`). Does nothing
+    /// without a session id and total cost.
+    pub fn record_cost(&self) {
+        let (Some(id), Some(total_cost)) = (
+            self.session_id.as_deref(),
+            self.cost.as_ref().and_then(|c| c.total_cost_usd),
+        ) else {
+            return;
+        };
+        let Ok(tracker) = crate::storage::CostTracker::open() else {
+            return;
+        };
+        let now = chrono::Utc::now().timestamp();
+        let window = self.context_window.as_ref();
+        let usage = window.and_then(|w| w.current_usage.as_ref());
+        let _ = tracker.record_total(&crate::storage::SessionRecord {
+            id: id.to_string(),
+            start_time: now,
+            end_time: Some(now),
+            model: self
+                .model
+                .as_ref()
+                .and_then(|m| m.id.clone())
+                .unwrap_or_default(),
+            total_cost,
+            tokens_input: window.and_then(|w| w.total_input_tokens).unwrap_or(0),
+            tokens_output: window.and_then(|w| w.total_output_tokens).unwrap_or(0),
+            tokens_cached: usage
+                .map(|u| {
+                    u.cache_creation_input_tokens.unwrap_or(0)
+                        + u.cache_read_input_tokens.unwrap_or(0)
+                })
+                .unwrap_or(0),
+        });
+    }
+
+    /// A fully-populated session used by `dump-schema` and the TUI preview.
+    pub fn sample() -> Self {
+        Self {
+            cwd: Some("/home/user/project".into()),
+            session_id: Some("abc-123-def-456".into()),
+            transcript_path: Some("/tmp/claude/transcript.jsonl".into()),
+            model: Some(Model {
+                id: Some("claude-opus-4-6".into()),
+                display_name: Some("Opus".into()),
+            }),
+            workspace: Some(Workspace {
+                current_dir: Some("/home/user/project".into()),
+                project_dir: Some("/home/user/project".into()),
+            }),
+            version: Some("1.0.30".into()),
+            output_style: Some(OutputStyle {
+                name: Some("default".into()),
+            }),
+            cost: Some(Cost {
+                total_cost_usd: Some(0.42),
+                total_duration_ms: Some(345000),
+                total_api_duration_ms: Some(156000),
+                total_lines_added: Some(120),
+                total_lines_removed: Some(30),
+            }),
+            context_window: Some(ContextWindow {
+                total_input_tokens: Some(50000),
+                total_output_tokens: Some(12000),
+                context_window_size: Some(200000),
+                used_percentage: Some(65.0),
+                remaining_percentage: Some(35.0),
+                current_usage: Some(CurrentUsage {
+                    input_tokens: Some(25000),
+                    output_tokens: Some(8000),
+                    cache_creation_input_tokens: Some(10000),
+                    cache_read_input_tokens: Some(5000),
+                }),
+            }),
+            exceeds_200k_tokens: Some(false),
+            vim: Some(Vim {
+                mode: Some("NORMAL".into()),
+            }),
+            agent: Some(Agent {
+                name: Some("task-agent-1".into()),
+            }),
+            limits: None,
+        }
+    }
+}
+
+/// Builds a `SessionData` one field at a time, for widget tests:
+/// `SessionDataBuilder::new().with_cost(0.42).with_context_pct(65.0).build()`.
+/// Unset fields stay `None`, as in a payload that omits them.
+#[derive(Debug, Clone, Default)]
+pub struct SessionDataBuilder {
+    data: SessionData,
 }
 
-#[derive(Debug, Deserialize, Default)]
+impl SessionDataBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_session_id(mut self, id: &str) -> Self {
+        self.data.session_id = Some(id.into());
+        self
+    }
+
+    pub fn with_cwd(mut self, dir: &str) -> Self {
+        self.data.cwd = Some(dir.into());
+        self
+    }
+
+    pub fn with_model(mut self, id: &str, display_name: &str) -> Self {
+        self.data.model = Some(Model {
+            id: Some(id.into()),
+            display_name: Some(display_name.into()),
+        });
+        self
+    }
+
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.data.version = Some(version.into());
+        self
+    }
+
+    pub fn with_cost(mut self, total_usd: f64) -> Self {
+        self.cost().total_cost_usd = Some(total_usd);
+        self
+    }
+
+    /// Wall-clock and API time, both in milliseconds.
+    pub fn with_duration(mut self, total_ms: u64, api_ms: u64) -> Self {
+        let cost = self.cost();
+        cost.total_duration_ms = Some(total_ms);
+        cost.total_api_duration_ms = Some(api_ms);
+        self
+    }
+
+    pub fn with_lines(mut self, added: u64, removed: u64) -> Self {
+        let cost = self.cost();
+        cost.total_lines_added = Some(added);
+        cost.total_lines_removed = Some(removed);
+        self
+    }
+
+    /// Used percentage; remaining is set to the rest.
+    pub fn with_context_pct(mut self, used: f64) -> Self {
+        let window = self.context_window();
+        window.used_percentage = Some(used);
+        window.remaining_percentage = Some(100.0 - used);
+        self
+    }
+
+    pub fn with_context_size(mut self, size: u64) -> Self {
+        self.context_window().context_window_size = Some(size);
+        self
+    }
+
+    /// Session totals of input and output tokens.
+    pub fn with_tokens(mut self, input: u64, output: u64) -> Self {
+        let window = self.context_window();
+        window.total_input_tokens = Some(input);
+        window.total_output_tokens = Some(output);
+        self
+    }
+
+    pub fn with_weekly_limit(mut self, usd: f64) -> Self {
+        self.data
+            .limits
+            .get_or_insert_with(Limits::default)
+            .weekly_usd = Some(usd);
+        self
+    }
+
+    pub fn build(self) -> SessionData {
+        self.data
+    }
+
+    fn cost(&mut self) -> &mut Cost {
+        self.data.cost.get_or_insert_with(Cost::default)
+    }
+
+    fn context_window(&mut self) -> &mut ContextWindow {
+        self.data
+            .context_window
+            .get_or_insert_with(ContextWindow::default)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Model {
     pub id: Option<String>,
     pub display_name: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Workspace {
     pub current_dir: Option<String>,
     pub project_dir: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct OutputStyle {
     pub name: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Cost {
     pub total_cost_usd: Option<f64>,
     pub total_duration_ms: Option<u64>,
@@ -42,7 +261,7 @@ pub struct Cost {
     pub total_lines_removed: Option<u64>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ContextWindow {
     pub total_input_tokens: Option<u64>,
     pub total_output_tokens: Option<u64>,
@@ -52,7 +271,7 @@ pub struct ContextWindow {
     pub current_usage: Option<CurrentUsage>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CurrentUsage {
     pub input_tokens: Option<u64>,
     pub output_tokens: Option<u64>,
@@ -60,12 +279,56 @@ pub struct CurrentUsage {
     pub cache_read_input_tokens: Option<u64>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Vim {
     pub mode: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Agent {
     pub name: Option<String>,
 }
+
+/// Spend limits of the user's plan. Cost widgets and `stats` prefer these
+/// over configured limits so the budget follows the plan.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct Limits {
+    pub weekly_usd: Option<f64>,
+    pub monthly_usd: Option<f64>,
+}
+
+impl Limits {
+    pub fn weekly(&self) -> Option<f64> {
+        self.weekly_usd.filter(|&v| v > 0.0)
+    }
+
+    pub fn monthly(&self) -> Option<f64> {
+        self.monthly_usd.filter(|&v| v > 0.0)
+    }
+
+    fn saved_path() -> PathBuf {
+        crate::storage::data_dir().join("limits.json")
+    }
+
+    /// The limits from the last payload that had any, for `stats`, which
+    /// runs without a payload.
+    pub fn load_saved() -> Option<Self> {
+        let json = std::fs::read_to_string(Self::saved_path()).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// Remember these limits for `load_saved`. Skips the write when nothing
+    /// changed, since this runs on every render.
+    pub fn save(&self) {
+        if Self::load_saved().as_ref() == Some(self) {
+            return;
+        }
+        let path = Self::saved_path();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(json) = serde_json::to_string(self) {
+            let _ = std::fs::write(&path, json);
+        }
+    }
+}
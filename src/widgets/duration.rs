@@ -1,25 +1,40 @@
+use chrono::Utc;
+
 use super::data::SessionData;
+use super::format;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
+use crate::storage::CostTracker;
 
 pub struct SessionDurationWidget;
 
 fn format_duration(ms: u64, compact: bool) -> String {
-    let total_secs = ms / 1000;
-    let hours = total_secs / 3600;
-    let mins = (total_secs % 3600) / 60;
-    let secs = total_secs % 60;
+    format::elapsed_secs(ms / 1000, if compact { "" } else { " " })
+}
 
-    if compact {
-        if hours > 0 {
-            format!("{}h{}m", hours, mins)
-        } else {
-            format!("{}m{}s", mins, secs)
-        }
-    } else if hours > 0 {
-        format!("{}h {}m", hours, mins)
-    } else {
-        format!("{}m {}s", mins, secs)
+/// The session's length: the payload's, or with `source = "wallclock"` the
+/// time since the session was first seen, across restarts (falling back to
+/// the payload without cost history).
+fn duration_ms(data: &SessionData, config: &WidgetConfig) -> Option<u64> {
+    let wallclock = match config.metadata.get("source").map(String::as_str) {
+        Some("wallclock") => wallclock_ms(data, config),
+        _ => None,
+    };
+    wallclock.or_else(|| data.cost.as_ref().and_then(|c| c.total_duration_ms))
+}
+
+/// Milliseconds since the cost history first saw this session, for
+/// `source = "wallclock"`. None without history or a session id.
+fn wallclock_ms(data: &SessionData, config: &WidgetConfig) -> Option<u64> {
+    if !config.settings.history {
+        return None;
     }
+    let session_id = data.session_id.as_deref()?;
+    let now = Utc::now().timestamp();
+    let start = CostTracker::open()
+        .ok()?
+        .session_start(session_id, now)
+        .ok()?;
+    Some((now - start).max(0) as u64 * 1000)
 }
 
 impl Widget for SessionDurationWidget {
@@ -28,20 +43,7 @@ impl Widget for SessionDurationWidget {
     }
 
     fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
-        let cost = match &data.cost {
-            Some(c) => c,
-            None => {
-                return WidgetOutput {
-                    text: String::new(),
-                    display_width: 0,
-                    priority: 65,
-                    visible: false,
-                    color_hint: None,
-                };
-            }
-        };
-
-        let duration_ms = match cost.total_duration_ms {
+        let duration_ms = match duration_ms(data, config) {
             Some(d) => d,
             None => {
                 return WidgetOutput {
@@ -53,6 +55,7 @@ impl Widget for SessionDurationWidget {
                 };
             }
         };
+        let api_duration_ms = data.cost.as_ref().and_then(|c| c.total_api_duration_ms);
 
         let text = if config.raw_value {
             format_duration(duration_ms, true)
@@ -62,9 +65,10 @@ impl Widget for SessionDurationWidget {
             .map(|v| v == "true")
             .unwrap_or(false)
         {
-            if let Some(api_ms) = cost.total_api_duration_ms {
+            if let Some(api_ms) = api_duration_ms {
                 if duration_ms > 0 {
-                    let ratio = (api_ms as f64 / duration_ms as f64 * 100.0) as u64;
+                    let ratio =
+                        format::percent_text(api_ms as f64 / duration_ms as f64 * 100.0, config);
                     format!("{} (API: {}%)", format_duration(duration_ms, false), ratio)
                 } else {
                     format_duration(duration_ms, false)
@@ -85,4 +89,20 @@ impl Widget for SessionDurationWidget {
             color_hint: None,
         }
     }
+
+    fn render_compact(&self, data: &SessionData, config: &WidgetConfig) -> Option<WidgetOutput> {
+        let text = format::largest_unit(duration_ms(data, config)? / 1000);
+        Some(WidgetOutput {
+            display_width: text.len(),
+            text,
+            priority: 65,
+            visible: true,
+            color_hint: None,
+        })
+    }
+
+    fn hidden_reason(&self, data: &SessionData, _config: &WidgetConfig) -> Option<String> {
+        let duration = data.cost.as_ref().and_then(|c| c.total_duration_ms);
+        duration.is_none().then(|| "no duration data".into())
+    }
 }
@@ -0,0 +1,254 @@
+//! Text helpers shared by widgets.
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use super::traits::WidgetConfig;
+use crate::config::CostConfig;
+
+const ELLIPSIS: char = '…';
+
+/// Keep the start of `s`, ending in "…" if it exceeds `width` columns.
+pub fn truncate_end(s: &str, width: usize) -> String {
+    if UnicodeWidthStr::width(s) <= width {
+        return s.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let mut out = take_width(s.chars(), width - 1);
+    out.push(ELLIPSIS);
+    out
+}
+
+/// Keep the head and tail of `s` with "…" in between, fitting `width` columns.
+/// The head gets the extra column when the split is uneven.
+pub fn truncate_middle(s: &str, width: usize) -> String {
+    if UnicodeWidthStr::width(s) <= width {
+        return s.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let budget = width - 1;
+    let head = take_width(s.chars(), budget.div_ceil(2));
+    let tail: String = take_width(s.chars().rev(), budget / 2)
+        .chars()
+        .rev()
+        .collect();
+    format!("{head}{ELLIPSIS}{tail}")
+}
+
+/// Group digits in threes: `group_digits(1234567, ",")` gives "1,234,567".
+pub fn group_digits(n: u64, separator: &str) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push_str(separator);
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Format a count per the config's `number_format`, leaving it bare by default.
+pub fn count(n: u64, config: &WidgetConfig) -> String {
+    match config.settings.group_separator {
+        Some(ref sep) => group_digits(n, sep),
+        None => n.to_string(),
+    }
+}
+
+/// Human-readable byte size: "512 B", "12.3 KB", "4.1 MB" (1024-based).
+pub fn bytes(n: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if n < 1024 {
+        return format!("{n} B");
+    }
+    let mut value = n as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/// Elapsed time at minute resolution: "5m", "2h5m", and "3d4h" from a day on.
+pub fn elapsed(secs: u64) -> String {
+    span(secs, "", false)
+}
+
+/// Like `elapsed`, but down to the second under an hour ("5m30s"), with
+/// `sep` between the units ("2h 5m").
+pub fn elapsed_secs(secs: u64, sep: &str) -> String {
+    span(secs, sep, true)
+}
+
+fn span(secs: u64, sep: &str, with_secs: bool) -> String {
+    let mins = secs / 60;
+    let (days, hours) = (mins / 1440, mins / 60 % 24);
+    if days > 0 {
+        format!("{days}d{sep}{hours}h")
+    } else if hours > 0 {
+        format!("{hours}h{sep}{}m", mins % 60)
+    } else if with_secs {
+        format!("{mins}m{sep}{}s", secs % 60)
+    } else {
+        format!("{mins}m")
+    }
+}
+
+/// Only the largest unit, for tight lines: "1h", "5m", "45s".
+pub fn largest_unit(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}h", secs / 3600)
+    } else if secs >= 60 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{secs}s")
+    }
+}
+
+/// Seconds in a span like "12h", "3d", "2w" or "1d12h": whole numbers, each
+/// followed by `s`, `m`, `h`, `d` or `w`. For command-line time ranges.
+pub fn parse_duration(s: &str) -> Option<u64> {
+    let mut rest = s.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    let mut total = 0u64;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let n: u64 = rest[..digits].parse().ok()?;
+        let unit = match rest[digits..].chars().next()? {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            'w' => 7 * 86400,
+            _ => return None,
+        };
+        total = total.checked_add(n.checked_mul(unit)?)?;
+        rest = &rest[digits + 1..];
+    }
+    Some(total)
+}
+
+/// How long ago something happened: "just now", "5m ago", "2h5m ago".
+pub fn ago(secs: u64) -> String {
+    if secs < 60 {
+        "just now".into()
+    } else {
+        format!("{} ago", elapsed(secs))
+    }
+}
+
+/// A USD amount in the display currency: the `[cost]` settings, with the
+/// widget's `symbol`, `decimals` and `rate` metadata taking precedence.
+pub fn money(usd: f64, config: &WidgetConfig) -> String {
+    cost_format(config).format(usd)
+}
+
+/// Like `money` without decimals, for limits and budgets.
+pub fn money_whole(usd: f64, config: &WidgetConfig) -> String {
+    cost_format(config).format_places(usd, 0)
+}
+
+fn cost_format(config: &WidgetConfig) -> CostConfig {
+    let mut cost = config.settings.cost.clone();
+    if let Some(symbol) = config.metadata.get("symbol") {
+        cost.symbol = symbol.clone();
+    }
+    if let Some(decimals) = config.metadata.get("decimals").and_then(|v| v.parse().ok()) {
+        cost.decimals = decimals;
+    }
+    if let Some(rate) = config.metadata.get("rate").and_then(|v| v.parse().ok()) {
+        cost.rate = rate;
+    }
+    cost
+}
+
+/// Round to a whole number per the `rounding` metadata key: `round` (the
+/// default), `floor`, or `ceil`.
+fn apply_rounding(value: f64, config: &WidgetConfig) -> f64 {
+    match config.metadata.get("rounding").map(|v| v.as_str()) {
+        Some("floor") => value.floor(),
+        Some("ceil") => value.ceil(),
+        _ => value.round(),
+    }
+}
+
+/// Whole-number percentage per the `rounding` policy.
+pub fn percent(value: f64, config: &WidgetConfig) -> u64 {
+    apply_rounding(value, config).max(0.0) as u64
+}
+
+/// Percentage text without the `%`, with `precision` decimal places (metadata,
+/// default 0, at most 3). The `rounding` policy applies at the last place shown.
+pub fn percent_text(value: f64, config: &WidgetConfig) -> String {
+    let precision = config
+        .metadata
+        .get("precision")
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(0)
+        .min(3);
+    let scale = 10f64.powi(precision as i32);
+    let scaled = apply_rounding(value * scale, config);
+    if scaled <= 0.0 {
+        return format!("{:.*}", precision, 0.0);
+    }
+    format!("{:.*}", precision, scaled / scale)
+}
+
+/// A `width`-cell progress bar, `fraction` of it filled from the left.
+pub fn bar(fraction: f64, width: usize) -> String {
+    let filled = ((fraction * width as f64).round().max(0.0) as usize).min(width);
+    format!("{}{}", "▓".repeat(filled), "░".repeat(width - filled))
+}
+
+/// Map values to ▁▂▃▄▅▆▇█ scaled to the largest one. All-zero (or empty)
+/// input gives a flat baseline.
+pub fn sparkline(values: &[f64]) -> String {
+    const TICKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().fold(0.0_f64, f64::max);
+    values
+        .iter()
+        .map(|&v| {
+            if max <= 0.0 {
+                return TICKS[0];
+            }
+            let idx = ((v.max(0.0) / max) * (TICKS.len() - 1) as f64).round() as usize;
+            TICKS[idx.min(TICKS.len() - 1)]
+        })
+        .collect()
+}
+
+/// Apply the `max_width` / `truncate` metadata options to a widget's text.
+pub fn fit_width(text: String, config: &WidgetConfig) -> String {
+    let Some(max) = config
+        .metadata
+        .get("max_width")
+        .and_then(|v| v.parse::<usize>().ok())
+    else {
+        return text;
+    };
+    match config.metadata.get("truncate").map(|v| v.as_str()) {
+        Some("middle") => truncate_middle(&text, max),
+        _ => truncate_end(&text, max),
+    }
+}
+
+fn take_width(chars: impl Iterator<Item = char>, width: usize) -> String {
+    let mut out = String::new();
+    let mut used = 0;
+    for c in chars {
+        let w = UnicodeWidthChar::width(c).unwrap_or(0);
+        if used + w > width {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+    out
+}
@@ -0,0 +1,133 @@
+use std::process::Command;
+use std::time::Duration;
+
+use unicode_width::UnicodeWidthStr;
+
+use super::data::SessionData;
+use super::format;
+use super::git_branch::git_dir;
+use super::traits::{Widget, WidgetConfig, WidgetOutput};
+
+/// Hours after which the last commit is old enough to flag.
+const DEFAULT_STALE_HOURS: u64 = 24;
+
+/// Time since the last commit on the current branch ("2h5m ago").
+pub struct GitAgeWidget;
+
+impl Widget for GitAgeWidget {
+    fn name(&self) -> &str {
+        "git-age"
+    }
+
+    fn is_blocking(&self) -> bool {
+        true
+    }
+
+    fn cache_ttl(&self) -> Option<Duration> {
+        Some(Duration::from_secs(5))
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let hidden = WidgetOutput {
+            text: String::new(),
+            display_width: 0,
+            priority: 40,
+            visible: false,
+            color_hint: None,
+        };
+        // Outside a repository there is nothing to ask git about.
+        let Some(dir) = data.effective_dir().filter(|d| git_dir(d).is_some()) else {
+            return hidden;
+        };
+        // Fails on an unborn branch, which has no commit yet.
+        let committed = Command::new("git")
+            .args(["log", "-1", "--format=%ct"])
+            .current_dir(&dir)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .and_then(|o| {
+                String::from_utf8_lossy(&o.stdout)
+                    .trim()
+                    .parse::<i64>()
+                    .ok()
+            });
+        let Some(committed) = committed else {
+            return hidden;
+        };
+        let age = (chrono::Utc::now().timestamp() - committed).max(0) as u64;
+
+        let text = if config.raw_value {
+            format::elapsed(age)
+        } else {
+            format::ago(age)
+        };
+        let stale_hours = config
+            .metadata
+            .get("stale_hours")
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_STALE_HOURS);
+
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 40,
+            visible: true,
+            color_hint: (age >= stale_hours.saturating_mul(3600)).then(|| "yellow".into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widgets::SessionDataBuilder;
+
+    #[test]
+    fn test_git_age_since_last_commit() {
+        let root =
+            std::env::temp_dir().join(format!("claude-status-git-age-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let data = SessionDataBuilder::default()
+            .with_cwd(root.to_str().unwrap())
+            .build();
+        let config = WidgetConfig::default();
+        assert!(!GitAgeWidget.render(&data, &config).visible);
+
+        let three_days_ago = chrono::Utc::now().timestamp() - 3 * 86400 - 7200;
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(["-c", "commit.gpgsign=false"])
+                .args(args)
+                .current_dir(&root)
+                .env("GIT_COMMITTER_DATE", format!("{three_days_ago} +0000"))
+                .output()
+                .is_ok_and(|o| o.status.success())
+        };
+        if !git(&["init", "-q"]) {
+            // No git to test against.
+            std::fs::remove_dir_all(&root).ok();
+            return;
+        }
+        // No commit yet.
+        assert!(!GitAgeWidget.render(&data, &config).visible);
+
+        assert!(git(&["commit", "-q", "--allow-empty", "-m", "init"]));
+        let output = GitAgeWidget.render(&data, &config);
+        assert!(output.visible);
+        assert_eq!(output.text, "3d2h ago");
+        assert_eq!(output.color_hint.as_deref(), Some("yellow"));
+
+        let mut config = WidgetConfig::default();
+        config.metadata.insert("stale_hours".into(), "100".into());
+        assert_eq!(GitAgeWidget.render(&data, &config).color_hint, None);
+        config
+            .metadata
+            .insert("stale_hours".into(), u64::MAX.to_string());
+        assert_eq!(GitAgeWidget.render(&data, &config).color_hint, None);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
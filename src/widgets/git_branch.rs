@@ -1,35 +1,154 @@
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
-use std::time::SystemTime;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::Duration;
+
+use unicode_width::UnicodeWidthStr;
 
 use super::data::SessionData;
+use super::format;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
+use crate::icons::IconSet;
 
 pub struct GitBranchWidget;
 
-fn cache_path(prefix: &str, dir: &str) -> PathBuf {
-    let hash: String = dir.bytes().take(8).map(|b| format!("{:02x}", b)).collect();
-    PathBuf::from(format!("/tmp/claude-status-{prefix}-{hash}"))
+/// How the branch relates to its upstream.
+#[derive(Debug, PartialEq)]
+enum Tracking {
+    NoUpstream,
+    Counts { ahead: u64, behind: u64 },
+}
+
+fn tracking(dir: &Path) -> Tracking {
+    let counts = Command::new("git")
+        .args(["rev-list", "--left-right", "--count", "HEAD...@{upstream}"])
+        .current_dir(dir)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| {
+            let s = String::from_utf8_lossy(&o.stdout).into_owned();
+            let mut parts = s.split_whitespace().map(|n| n.parse::<u64>().ok());
+            Some((parts.next()??, parts.next()??))
+        });
+    match counts {
+        Some((ahead, behind)) => Tracking::Counts { ahead, behind },
+        None => Tracking::NoUpstream,
+    }
+}
+
+/// The repository's git directory, found by walking up from `dir`. A `.git`
+/// file (worktrees, submodules) points elsewhere with a `gitdir:` line.
+pub(super) fn git_dir(dir: &Path) -> Option<PathBuf> {
+    for ancestor in dir.ancestors() {
+        let dot_git = ancestor.join(".git");
+        if dot_git.is_dir() {
+            return Some(dot_git);
+        }
+        if dot_git.is_file() {
+            let contents = fs::read_to_string(&dot_git).ok()?;
+            let target = contents.strip_prefix("gitdir:")?.trim();
+            return Some(ancestor.join(target));
+        }
+    }
+    None
+}
+
+/// The checked-out branch, read from the git directory's `HEAD` without
+/// spawning git. `None` on a detached HEAD and for reftable repositories,
+/// whose `HEAD` is a stub; `shell_branch` answers those.
+fn head_branch(dir: &Path) -> Option<String> {
+    let git_dir = git_dir(dir)?;
+    if git_dir.join("reftable").exists() {
+        return None;
+    }
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let branch = head.strip_prefix("ref: refs/heads/")?.trim_end();
+    (!branch.is_empty()).then(|| branch.to_string())
+}
+
+/// The branch name (short SHA when detached) from the configured backend:
+/// with `native`, `head_branch` first and git only for what it can't read.
+fn branch(dir: &Path, native: bool) -> Option<String> {
+    native
+        .then(|| head_branch(dir))
+        .flatten()
+        .or_else(|| shell_branch(dir))
+}
+
+/// The branch as git reports it, or the short SHA on a detached HEAD.
+fn shell_branch(dir: &Path) -> Option<String> {
+    let git = |args: &[&str]| match Command::new("git").args(args).current_dir(dir).output() {
+        Ok(o) if o.status.success() => {
+            Some(String::from_utf8_lossy(&o.stdout).trim().to_string()).filter(|s| !s.is_empty())
+        }
+        result => {
+            crate::debug_log!("git {}: {}", args.join(" "), git_failure(&result));
+            None
+        }
+    };
+    git(&["branch", "--show-current"]).or_else(|| git(&["rev-parse", "--short", "HEAD"]))
+}
+
+/// What went wrong with a git command, for the debug log: its stderr, or
+/// why it couldn't run.
+pub(super) fn git_failure(result: &std::io::Result<Output>) -> String {
+    match result {
+        Ok(o) => String::from_utf8_lossy(&o.stderr).trim().to_string(),
+        Err(e) => format!("can't run git: {e}"),
+    }
 }
 
-fn read_cache(path: &PathBuf, max_age_secs: u64) -> Option<String> {
-    let meta = fs::metadata(path).ok()?;
-    let age = SystemTime::now()
-        .duration_since(meta.modified().ok()?)
-        .ok()?;
-    if age.as_secs() <= max_age_secs {
-        fs::read_to_string(path).ok()
+/// Label for an operation left in progress (rebase, merge, bisect, ...),
+/// detected from the marker files git keeps in its directory.
+pub(super) fn in_progress(dir: &Path) -> Option<&'static str> {
+    let git_dir = git_dir(dir)?;
+    let has = |name: &str| git_dir.join(name).exists();
+    if has("rebase-merge") {
+        Some("REBASE")
+    } else if has("rebase-apply") {
+        Some(if has("rebase-apply/applying") {
+            "AM"
+        } else {
+            "REBASE"
+        })
+    } else if has("MERGE_HEAD") {
+        Some("MERGING")
+    } else if has("CHERRY_PICK_HEAD") {
+        Some("CHERRY-PICK")
+    } else if has("REVERT_HEAD") {
+        Some("REVERT")
+    } else if has("BISECT_LOG") {
+        Some("BISECT")
     } else {
         None
     }
 }
 
-fn get_working_dir(data: &SessionData) -> Option<String> {
-    data.workspace
-        .as_ref()
-        .and_then(|w| w.current_dir.clone())
-        .or_else(|| data.cwd.clone())
+/// Trailing glyph for the upstream state. Icon sets without these glyphs
+/// fall back to plain arrows, and show nothing when synced or untracked.
+fn tracking_suffix(tracking: &Tracking, icons: &IconSet) -> String {
+    let glyph = |name, plain| match icons.get(name) {
+        "" => plain,
+        icon => icon,
+    };
+    match *tracking {
+        Tracking::NoUpstream => icons.get("no-upstream").to_string(),
+        Tracking::Counts {
+            ahead: 0,
+            behind: 0,
+        } => icons.get("synced").to_string(),
+        Tracking::Counts { ahead, behind } => {
+            let mut parts = Vec::new();
+            if ahead > 0 {
+                parts.push(format!("{}{ahead}", glyph("ahead", "↑")));
+            }
+            if behind > 0 {
+                parts.push(format!("{}{behind}", glyph("behind", "↓")));
+            }
+            parts.join(" ")
+        }
+    }
 }
 
 impl Widget for GitBranchWidget {
@@ -37,8 +156,16 @@ impl Widget for GitBranchWidget {
         "git-branch"
     }
 
-    fn render(&self, data: &SessionData, _config: &WidgetConfig) -> WidgetOutput {
-        let dir = match get_working_dir(data) {
+    fn is_blocking(&self) -> bool {
+        true
+    }
+
+    fn cache_ttl(&self) -> Option<Duration> {
+        Some(Duration::from_secs(5))
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let dir = match data.effective_dir() {
             Some(d) => d,
             None => {
                 return WidgetOutput {
@@ -51,84 +178,166 @@ impl Widget for GitBranchWidget {
             }
         };
 
-        let cache = cache_path("git-branch", &dir);
-
-        if let Some(cached) = read_cache(&cache, 5) {
-            let text = cached.trim().to_string();
-            let display_width = text.len();
+        let Some(result) = branch(&dir, config.settings.native_git) else {
             return WidgetOutput {
-                text,
-                display_width,
+                text: String::new(),
+                display_width: 0,
                 priority: 75,
-                visible: true,
+                visible: false,
                 color_hint: None,
             };
-        }
-
-        // Try git branch --show-current
-        let branch = Command::new("git")
-            .args(["branch", "--show-current"])
-            .current_dir(&dir)
-            .output()
-            .ok()
-            .and_then(|o| {
-                if o.status.success() {
-                    let s = String::from_utf8_lossy(&o.stdout).trim().to_string();
-                    if s.is_empty() {
-                        None // detached HEAD
-                    } else {
-                        Some(s)
-                    }
-                } else {
-                    None
-                }
-            });
-
-        let result = match branch {
-            Some(b) => b,
-            None => {
-                // Detached HEAD fallback
-                match Command::new("git")
-                    .args(["rev-parse", "--short", "HEAD"])
-                    .current_dir(&dir)
-                    .output()
-                {
-                    Ok(o) if o.status.success() => {
-                        let hash = String::from_utf8_lossy(&o.stdout).trim().to_string();
-                        if hash.is_empty() {
-                            return WidgetOutput {
-                                text: String::new(),
-                                display_width: 0,
-                                priority: 75,
-                                visible: false,
-                                color_hint: None,
-                            };
-                        }
-                        hash
-                    }
-                    _ => {
-                        return WidgetOutput {
-                            text: String::new(),
-                            display_width: 0,
-                            priority: 75,
-                            visible: false,
-                            color_hint: None,
-                        };
-                    }
-                }
-            }
         };
 
-        // Write cache
-        let _ = fs::write(&cache, &result);
-
-        let display_width = result.len();
+        let result = format::fit_width(result, config);
+        let icons = &config.settings.icons;
+        let metadata_on = |key: &str| config.metadata.get(key).map(|v| v == "true");
+        let mut result = if metadata_on("icon") == Some(true) {
+            icons.prefix("branch", &result)
+        } else {
+            result
+        };
+        if metadata_on("upstream") == Some(true) {
+            let suffix = tracking_suffix(&tracking(&dir), icons);
+            if !suffix.is_empty() {
+                result = format!("{result} {suffix}");
+            }
+        }
+        let state = in_progress(&dir);
+        if let Some(state) = state {
+            result = format!("{result} {state}");
+        }
+        let display_width = UnicodeWidthStr::width(result.as_str());
         WidgetOutput {
             text: result,
             display_width,
             priority: 75,
             visible: true,
-            color_hint: None,
+            color_hint: state.map(|_| "yellow".into()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_progress_reads_git_markers() {
+        let root =
+            std::env::temp_dir().join(format!("claude-status-git-state-{}", std::process::id()));
+        let git_dir = root.join(".git");
+        let nested = root.join("src/widgets");
+        fs::create_dir_all(&git_dir).unwrap();
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(in_progress(&nested), None);
+
+        fs::write(git_dir.join("BISECT_LOG"), "").unwrap();
+        assert_eq!(in_progress(&nested), Some("BISECT"));
+        fs::write(git_dir.join("CHERRY_PICK_HEAD"), "").unwrap();
+        assert_eq!(in_progress(&nested), Some("CHERRY-PICK"));
+        fs::create_dir_all(git_dir.join("rebase-merge")).unwrap();
+        assert_eq!(in_progress(&nested), Some("REBASE"));
+
+        // A worktree's .git file points at its own git directory
+        let worktree = root.join("wt");
+        fs::create_dir_all(&worktree).unwrap();
+        fs::write(worktree.join(".git"), "gitdir: ../.git/worktrees/wt\n").unwrap();
+        fs::create_dir_all(git_dir.join("worktrees/wt")).unwrap();
+        assert_eq!(in_progress(&worktree), None);
+        fs::write(git_dir.join("worktrees/wt/MERGE_HEAD"), "").unwrap();
+        assert_eq!(in_progress(&worktree), Some("MERGING"));
+
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_head_branch_matches_git() {
+        let root =
+            std::env::temp_dir().join(format!("claude-status-git-head-{}", std::process::id()));
+        let nested = root.join("src");
+        fs::create_dir_all(&nested).unwrap();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(&root)
+                .output()
+                .is_ok_and(|o| o.status.success())
+        };
+        if !git(&["init", "-q", "-b", "main"]) {
+            // No git to compare against.
+            fs::remove_dir_all(&root).ok();
+            return;
+        }
+        // An unborn branch, then one with a slash in its name.
+        assert_eq!(head_branch(&nested).as_deref(), Some("main"));
+        assert_eq!(branch(&nested, true), branch(&nested, false));
+
+        // The branch glyph only shows when asked for.
+        let data = SessionData {
+            cwd: Some(nested.to_string_lossy().into_owned()),
+            ..SessionData::default()
+        };
+        let mut config = WidgetConfig::default();
+        config.settings.icons = IconSet::Nerd;
+        assert_eq!(GitBranchWidget.render(&data, &config).text, "main");
+        config.metadata.insert("icon".into(), "true".into());
+        assert_eq!(GitBranchWidget.render(&data, &config).text, "\u{E0A0} main");
+
+        assert!(git(&["checkout", "-q", "-b", "feature/x"]));
+        assert_eq!(head_branch(&nested).as_deref(), Some("feature/x"));
+        assert_eq!(branch(&nested, true), branch(&nested, false));
+
+        // Detached, the native read defers to git for the short SHA.
+        assert!(git(&[
+            "-c",
+            "user.name=t",
+            "-c",
+            "user.email=t@t",
+            "-c",
+            "commit.gpgsign=false",
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "init",
+        ]));
+        assert!(git(&["checkout", "-q", "--detach"]));
+        assert_eq!(head_branch(&nested), None);
+        assert!(shell_branch(&nested).is_some_and(|sha| sha.len() >= 7));
+        assert_eq!(branch(&nested, true), branch(&nested, false));
+
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_tracking_suffix_nerd_glyphs() {
+        let icons = IconSet::Nerd;
+        let synced = Tracking::Counts {
+            ahead: 0,
+            behind: 0,
+        };
+        assert_eq!(tracking_suffix(&synced, &icons), "\u{F0C2}");
+        let diverged = Tracking::Counts {
+            ahead: 2,
+            behind: 1,
+        };
+        assert_eq!(tracking_suffix(&diverged, &icons), "\u{F062}2 \u{F063}1");
+        assert_eq!(tracking_suffix(&Tracking::NoUpstream, &icons), "\u{F127}");
+    }
+
+    #[test]
+    fn test_tracking_suffix_plain_without_icons() {
+        let icons = IconSet::None;
+        let ahead = Tracking::Counts {
+            ahead: 3,
+            behind: 0,
+        };
+        assert_eq!(tracking_suffix(&ahead, &icons), "↑3");
+        let synced = Tracking::Counts {
+            ahead: 0,
+            behind: 0,
+        };
+        assert_eq!(tracking_suffix(&synced, &icons), "");
+        assert_eq!(tracking_suffix(&Tracking::NoUpstream, &icons), "");
+    }
+}
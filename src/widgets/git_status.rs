@@ -1,37 +1,13 @@
-use std::fs;
-use std::path::PathBuf;
 use std::process::Command;
-use std::time::SystemTime;
+use std::time::Duration;
+
+use unicode_width::UnicodeWidthStr;
 
 use super::data::SessionData;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
 
 pub struct GitStatusWidget;
 
-fn cache_path(prefix: &str, dir: &str) -> PathBuf {
-    let hash: String = dir.bytes().take(8).map(|b| format!("{:02x}", b)).collect();
-    PathBuf::from(format!("/tmp/claude-status-{prefix}-{hash}"))
-}
-
-fn read_cache(path: &PathBuf, max_age_secs: u64) -> Option<String> {
-    let meta = fs::metadata(path).ok()?;
-    let age = SystemTime::now()
-        .duration_since(meta.modified().ok()?)
-        .ok()?;
-    if age.as_secs() <= max_age_secs {
-        fs::read_to_string(path).ok()
-    } else {
-        None
-    }
-}
-
-fn get_working_dir(data: &SessionData) -> Option<String> {
-    data.workspace
-        .as_ref()
-        .and_then(|w| w.current_dir.clone())
-        .or_else(|| data.cwd.clone())
-}
-
 fn format_status(staged: usize, modified: usize, untracked: usize) -> String {
     let mut parts = Vec::new();
     if staged > 0 {
@@ -46,13 +22,26 @@ fn format_status(staged: usize, modified: usize, untracked: usize) -> String {
     parts.join(" ")
 }
 
+fn with_state_icon(status: &str, config: &WidgetConfig) -> String {
+    let icon = if status.is_empty() { "clean" } else { "dirty" };
+    config.settings.icons.prefix(icon, status)
+}
+
 impl Widget for GitStatusWidget {
     fn name(&self) -> &str {
         "git-status"
     }
 
-    fn render(&self, data: &SessionData, _config: &WidgetConfig) -> WidgetOutput {
-        let dir = match get_working_dir(data) {
+    fn is_blocking(&self) -> bool {
+        true
+    }
+
+    fn cache_ttl(&self) -> Option<Duration> {
+        Some(Duration::from_secs(5))
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let dir = match data.effective_dir() {
             Some(d) => d,
             None => {
                 return WidgetOutput {
@@ -65,36 +54,14 @@ impl Widget for GitStatusWidget {
             }
         };
 
-        let cache = cache_path("git-status", &dir);
-
-        if let Some(cached) = read_cache(&cache, 5) {
-            let text = cached.trim().to_string();
-            if text.is_empty() {
-                return WidgetOutput {
-                    text: String::new(),
-                    display_width: 0,
-                    priority: 70,
-                    visible: true,
-                    color_hint: None,
-                };
-            }
-            let display_width = text.len();
-            return WidgetOutput {
-                text,
-                display_width,
-                priority: 70,
-                visible: true,
-                color_hint: None,
-            };
-        }
-
         let output = match Command::new("git")
             .args(["status", "--porcelain"])
             .current_dir(&dir)
             .output()
         {
             Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout).to_string(),
-            _ => {
+            result => {
+                crate::debug_log!("git status: {}", super::git_branch::git_failure(&result));
                 return WidgetOutput {
                     text: String::new(),
                     display_width: 0,
@@ -131,18 +98,22 @@ impl Widget for GitStatusWidget {
             }
         }
 
-        let text = format_status(staged, modified, untracked);
-
-        // Write cache
-        let _ = fs::write(&cache, &text);
+        // An unfinished rebase, merge or bisect leads, and counts as dirty
+        let state = super::git_branch::in_progress(&dir);
+        let text = match (state, format_status(staged, modified, untracked)) {
+            (Some(state), counts) if counts.is_empty() => state.to_string(),
+            (Some(state), counts) => format!("{state} {counts}"),
+            (None, counts) => counts,
+        };
 
-        let display_width = text.len();
+        let text = with_state_icon(&text, config);
+        let display_width = UnicodeWidthStr::width(text.as_str());
         WidgetOutput {
             text,
             display_width,
             priority: 70,
             visible: true,
-            color_hint: None,
+            color_hint: state.map(|_| "yellow".into()),
         }
     }
 }
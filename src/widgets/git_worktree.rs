@@ -1,25 +1,27 @@
 use std::path::Path;
 use std::process::Command;
+use std::time::Duration;
 
 use super::data::SessionData;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
 
 pub struct GitWorktreeWidget;
 
-fn get_working_dir(data: &SessionData) -> Option<String> {
-    data.workspace
-        .as_ref()
-        .and_then(|w| w.current_dir.clone())
-        .or_else(|| data.cwd.clone())
-}
-
 impl Widget for GitWorktreeWidget {
     fn name(&self) -> &str {
         "git-worktree"
     }
 
+    fn is_blocking(&self) -> bool {
+        true
+    }
+
+    fn cache_ttl(&self) -> Option<Duration> {
+        Some(Duration::from_secs(5))
+    }
+
     fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
-        let dir = match get_working_dir(data) {
+        let dir = match data.effective_dir() {
             Some(d) => d,
             None => {
                 return WidgetOutput {
@@ -0,0 +1,38 @@
+use super::data::SessionData;
+use super::traits::{Widget, WidgetConfig, WidgetOutput};
+use unicode_width::UnicodeWidthStr;
+
+/// A divider between groups of widgets: `left`, `glyph` and `right` joined,
+/// " ❯ " by default.
+pub struct GroupDividerWidget;
+
+impl Widget for GroupDividerWidget {
+    fn name(&self) -> &str {
+        "group-divider"
+    }
+
+    fn render(&self, _data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let part = |key: &str, default: &str| {
+            config
+                .metadata
+                .get(key)
+                .cloned()
+                .unwrap_or_else(|| default.to_string())
+        };
+        let text = format!(
+            "{}{}{}",
+            part("left", " "),
+            part("glyph", "❯"),
+            part("right", " ")
+        );
+
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            visible: display_width > 0,
+            text,
+            display_width,
+            priority: 100,
+            color_hint: None,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use unicode_width::UnicodeWidthStr;
+
+use super::data::SessionData;
+use super::format;
+use super::traits::{Widget, WidgetConfig, WidgetOutput};
+
+const DEFAULT_THRESHOLD_MINS: u64 = 2;
+
+/// Time since the transcript was last written, i.e. since the last message.
+pub struct IdleTimeWidget;
+
+impl Widget for IdleTimeWidget {
+    fn name(&self) -> &str {
+        "idle-time"
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let hidden = WidgetOutput {
+            text: String::new(),
+            display_width: 0,
+            priority: 45,
+            visible: false,
+            color_hint: None,
+        };
+        let Some(idle) = data.transcript_age() else {
+            return hidden;
+        };
+
+        let threshold_mins = config
+            .metadata
+            .get("threshold")
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_THRESHOLD_MINS);
+        if idle.as_secs() < threshold_mins * 60 {
+            return hidden;
+        }
+
+        let text = if config.raw_value {
+            format::elapsed(idle.as_secs())
+        } else {
+            format!("idle {}", format::elapsed(idle.as_secs()))
+        };
+
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 45,
+            visible: true,
+            color_hint: None,
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use unicode_width::UnicodeWidthStr;
+
+use super::data::SessionData;
+use super::format;
+use super::traits::{Widget, WidgetConfig, WidgetOutput, pro_history_enabled, pro_history_reason};
+use crate::storage::CostTracker;
+
+/// What the last message cost, from the per-render deltas recorded in cost
+/// history (e.g. "+$0.03").
+pub struct LastMessageCostWidget;
+
+impl Widget for LastMessageCostWidget {
+    fn name(&self) -> &str {
+        "last-message-cost"
+    }
+
+    fn reads_history(&self) -> bool {
+        true
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let hidden = WidgetOutput {
+            text: String::new(),
+            display_width: 0,
+            priority: 60,
+            visible: false,
+            color_hint: None,
+        };
+        if !pro_history_enabled(config) {
+            return hidden;
+        }
+        let Some(session) = data.session_id.as_deref() else {
+            return hidden;
+        };
+        let Some(event) = CostTracker::open()
+            .ok()
+            .and_then(|tracker| tracker.latest_event(session))
+        else {
+            return hidden;
+        };
+
+        let text = format!("+{}", format::money(event.cost, config));
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 60,
+            visible: true,
+            color_hint: None,
+        }
+    }
+
+    fn hidden_reason(&self, data: &SessionData, config: &WidgetConfig) -> Option<String> {
+        pro_history_reason(config).or_else(|| {
+            Some(match data.session_id {
+                None => "no session id".into(),
+                Some(_) => "no cost recorded yet".into(),
+            })
+        })
+    }
+}
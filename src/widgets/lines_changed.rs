@@ -1,5 +1,7 @@
 use super::data::SessionData;
+use super::format;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
+use unicode_width::UnicodeWidthStr;
 
 pub struct LinesChangedWidget;
 
@@ -30,13 +32,15 @@ impl Widget for LinesChangedWidget {
             };
         }
 
+        let added = format::count(added, config);
+        let removed = format::count(removed, config);
         let text = if config.raw_value {
             format!("+{added}-{removed}")
         } else {
             format!("+{added} -{removed}")
         };
 
-        let display_width = text.len();
+        let display_width = UnicodeWidthStr::width(text.as_str());
         WidgetOutput {
             text,
             display_width,
@@ -1,13 +1,23 @@
+mod cache;
 pub mod data;
+pub mod format;
 mod registry;
 mod traits;
 
 // Widget implementations
+mod agent_count;
 mod agent_name;
 mod api_duration;
+mod block_cost;
+mod block_gauge;
 mod block_timer;
+mod budget_remaining;
 mod burn_rate;
+mod cache_savings;
+mod compaction_warning;
 mod context;
+mod context_gauge;
+mod context_trend;
 mod cost;
 mod cost_warning;
 mod custom_command;
@@ -16,20 +26,27 @@ mod cwd;
 mod duration;
 mod exceeds_tokens;
 mod flex_separator;
+mod git_age;
 mod git_branch;
 mod git_status;
 mod git_worktree;
+mod group_divider;
+mod idle_time;
+mod last_message_cost;
 mod lines_changed;
 mod model;
 mod model_suggest;
 mod output_style;
+mod project;
 mod separator;
 mod session_id;
 mod terminal_width;
 mod tokens;
+mod transcript_size;
 mod version;
 mod vim_mode;
 
+pub use cache::RenderCache;
 pub use data::*;
 pub use registry::WidgetRegistry;
-pub use traits::{Widget, WidgetConfig, WidgetOutput};
+pub use traits::{RenderSettings, Widget, WidgetConfig, WidgetOutput};
@@ -1,8 +1,32 @@
 use super::data::SessionData;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
+use unicode_width::UnicodeWidthStr;
 
 pub struct ModelWidget;
 
+/// Determine the model tier from a model id or display name.
+pub(super) fn model_tier(model_id: &str) -> Option<&'static str> {
+    let lower = model_id.to_lowercase();
+    if lower.contains("opus") {
+        Some("opus")
+    } else if lower.contains("sonnet") {
+        Some("sonnet")
+    } else if lower.contains("haiku") {
+        Some("haiku")
+    } else {
+        None
+    }
+}
+
+/// Look up `tier` in a `tier_colors` value like "opus=magenta,sonnet=cyan".
+fn tier_color(tier_colors: &str, tier: &str) -> Option<String> {
+    tier_colors.split(',').find_map(|pair| {
+        let (name, color) = pair.split_once('=')?;
+        (name.trim().eq_ignore_ascii_case(tier) && !color.trim().is_empty())
+            .then(|| color.trim().to_string())
+    })
+}
+
 impl Widget for ModelWidget {
     fn name(&self) -> &str {
         "model"
@@ -31,14 +55,24 @@ impl Widget for ModelWidget {
                 .or_else(|| model.id.clone())
                 .unwrap_or_default()
         };
+        let text = config.settings.icons.prefix("model", &text);
+
+        let color_hint = config.metadata.get("tier_colors").and_then(|colors| {
+            let tier = model
+                .id
+                .as_deref()
+                .and_then(model_tier)
+                .or_else(|| model.display_name.as_deref().and_then(model_tier))?;
+            tier_color(colors, tier)
+        });
 
-        let display_width = text.len();
+        let display_width = UnicodeWidthStr::width(text.as_str());
         WidgetOutput {
             text,
             display_width,
             priority: 90,
             visible: true,
-            color_hint: None,
+            color_hint,
         }
     }
 }
@@ -1,5 +1,8 @@
 use super::data::SessionData;
+use super::format;
+use super::model::model_tier;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Complexity {
@@ -42,20 +45,6 @@ impl ModelSuggestWidget {
         Complexity::Simple
     }
 
-    /// Determine the model tier from model id string.
-    fn model_tier(model_id: &str) -> Option<&'static str> {
-        let lower = model_id.to_lowercase();
-        if lower.contains("opus") {
-            Some("opus")
-        } else if lower.contains("sonnet") {
-            Some("sonnet")
-        } else if lower.contains("haiku") {
-            Some("haiku")
-        } else {
-            None
-        }
-    }
-
     /// Suggest a cheaper model if appropriate.
     fn suggest(
         current_tier: &str,
@@ -122,7 +111,7 @@ impl Widget for ModelSuggestWidget {
             }
         };
 
-        let current_tier = match Self::model_tier(model_id) {
+        let current_tier = match model_tier(model_id) {
             Some(t) => t,
             None => {
                 return WidgetOutput {
@@ -160,13 +149,17 @@ impl Widget for ModelSuggestWidget {
         let text = if config.raw_value {
             format!("{}:{:.2}", suggested_model, savings)
         } else {
-            format!(
-                "\u{1F4A1} Try {} -> Save ${:.2}",
-                suggested_model, savings
+            config.settings.icons.prefix(
+                "hint",
+                &format!(
+                    "Try {} -> Save {}",
+                    suggested_model,
+                    format::money(savings, config)
+                ),
             )
         };
 
-        let display_width = text.len();
+        let display_width = UnicodeWidthStr::width(text.as_str());
         WidgetOutput {
             text,
             display_width,
@@ -175,4 +168,8 @@ impl Widget for ModelSuggestWidget {
             color_hint: Some("cyan".into()),
         }
     }
+
+    fn hidden_reason(&self, _data: &SessionData, _config: &WidgetConfig) -> Option<String> {
+        (!crate::license::is_pro()).then(|| "not Pro".into())
+    }
 }
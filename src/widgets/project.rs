@@ -0,0 +1,108 @@
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use unicode_width::UnicodeWidthStr;
+
+use super::data::SessionData;
+use super::format;
+use super::traits::{Widget, WidgetConfig, WidgetOutput};
+
+/// Repository name from the `origin` remote, falling back to the repo's
+/// top-level directory when there is no remote.
+pub struct ProjectWidget;
+
+/// Last path segment of a remote URL without ".git":
+/// `git@github.com:org/repo.git` and `https://host/org/repo/` both give "repo".
+fn repo_name(url: &str) -> Option<String> {
+    let name = url.trim().trim_end_matches('/').rsplit(['/', ':']).next()?;
+    let name = name.strip_suffix(".git").unwrap_or(name);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let s = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!s.is_empty()).then_some(s)
+}
+
+fn basename(path: &Path) -> Option<String> {
+    path.file_name().map(|f| f.to_string_lossy().into_owned())
+}
+
+impl Widget for ProjectWidget {
+    fn name(&self) -> &str {
+        "project"
+    }
+
+    fn is_blocking(&self) -> bool {
+        true
+    }
+
+    fn cache_ttl(&self) -> Option<Duration> {
+        Some(Duration::from_secs(60))
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let name = data.effective_dir().and_then(|dir| {
+            git(&dir, &["remote", "get-url", "origin"])
+                .and_then(|url| repo_name(&url))
+                .or_else(|| {
+                    git(&dir, &["rev-parse", "--show-toplevel"])
+                        .and_then(|top| basename(Path::new(&top)))
+                })
+                .or_else(|| {
+                    let fallback = config.metadata.get("fallback").map(|v| v == "true");
+                    if fallback == Some(true) {
+                        basename(&dir)
+                    } else {
+                        None
+                    }
+                })
+        });
+
+        let Some(name) = name else {
+            return WidgetOutput {
+                text: String::new(),
+                display_width: 0,
+                priority: 70,
+                visible: false,
+                color_hint: None,
+            };
+        };
+
+        let text = format::fit_width(name, config);
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 70,
+            visible: true,
+            color_hint: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_name_from_remote_urls() {
+        for url in [
+            "https://github.com/mstuart/claude-status.git",
+            "https://github.com/mstuart/claude-status/",
+            "git@github.com:mstuart/claude-status.git",
+            "ssh://git@example.com:2222/claude-status",
+            "claude-status.git",
+        ] {
+            assert_eq!(repo_name(url).as_deref(), Some("claude-status"), "{url}");
+        }
+        assert_eq!(repo_name(".git"), None);
+    }
+}
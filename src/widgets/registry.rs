@@ -1,10 +1,20 @@
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
 
+use super::cache::RenderCache;
 use super::data::SessionData;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
 
+/// Widgets by name. `new()` holds every built-in widget; embedders add
+/// their own with `register` and hand the registry to `LayoutEngine`.
 pub struct WidgetRegistry {
-    widgets: HashMap<String, Box<dyn Widget>>,
+    widgets: HashMap<String, Arc<dyn Widget>>,
+    cache: Option<RenderCache>,
+    /// Floor on how long blocking widgets reuse cached output.
+    min_interval: Duration,
 }
 
 impl Default for WidgetRegistry {
@@ -17,13 +27,33 @@ impl WidgetRegistry {
     pub fn new() -> Self {
         let mut registry = Self {
             widgets: HashMap::new(),
+            cache: None,
+            min_interval: Duration::ZERO,
         };
         registry.register_defaults();
         registry
     }
 
+    /// Memoize widgets that declare a `cache_ttl` in `cache`.
+    pub fn with_cache(mut self, cache: RenderCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Throttle blocking widgets: with a cache, each reuses its last output
+    /// for at least `interval`, even without a `cache_ttl` of its own. Saves
+    /// spawning git or shell commands on every refresh while a response streams.
+    pub fn with_min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+
+    /// Add `widget` under its `name()`, which is the `type` config lines use
+    /// to refer to it. Registering a name that already exists replaces that
+    /// widget, built-ins included.
     pub fn register(&mut self, widget: Box<dyn Widget>) {
-        self.widgets.insert(widget.name().to_string(), widget);
+        self.widgets
+            .insert(widget.name().to_string(), Arc::from(widget));
     }
 
     pub fn render(
@@ -32,15 +62,121 @@ impl WidgetRegistry {
         data: &SessionData,
         config: &WidgetConfig,
     ) -> Option<WidgetOutput> {
+        let widget = self.widgets.get(widget_type)?;
+        if let Some(hit) = self.cached(widget.as_ref(), data, config) {
+            return Some(hit);
+        }
+        let cache = self
+            .cache
+            .as_ref()
+            .filter(|_| self.ttl(widget.as_ref()).is_some());
+        Some(render_and_store(widget.as_ref(), cache, data, config))
+    }
+
+    /// The widget's compact form (see `Widget::render_compact`), if it has
+    /// one. Not cached; it only runs on lines that overflow.
+    pub fn render_compact(
+        &self,
+        widget_type: &str,
+        data: &SessionData,
+        config: &WidgetConfig,
+    ) -> Option<WidgetOutput> {
+        self.widgets.get(widget_type)?.render_compact(data, config)
+    }
+
+    /// Registered widget type names, sorted.
+    pub fn widget_types(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.widgets.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    pub fn contains(&self, widget_type: &str) -> bool {
+        self.widgets.contains_key(widget_type)
+    }
+
+    /// The widget's own account of why it renders hidden; see
+    /// `Widget::hidden_reason`.
+    pub fn hidden_reason(
+        &self,
+        widget_type: &str,
+        data: &SessionData,
+        config: &WidgetConfig,
+    ) -> Option<String> {
+        self.widgets.get(widget_type)?.hidden_reason(data, config)
+    }
+
+    pub fn is_blocking(&self, widget_type: &str) -> bool {
+        self.widgets
+            .get(widget_type)
+            .is_some_and(|w| w.is_blocking())
+    }
+
+    pub fn reads_history(&self, widget_type: &str) -> bool {
         self.widgets
             .get(widget_type)
-            .map(|w| w.render(data, config))
+            .is_some_and(|w| w.reads_history())
+    }
+
+    /// Render a widget on a detached thread. The receiver yields the output once
+    /// it is ready; a widget that hangs never sends and is simply abandoned.
+    pub fn spawn_render(
+        &self,
+        widget_type: &str,
+        data: Arc<SessionData>,
+        config: WidgetConfig,
+    ) -> Option<Receiver<WidgetOutput>> {
+        let widget = Arc::clone(self.widgets.get(widget_type)?);
+        let (tx, rx) = mpsc::channel();
+        if let Some(hit) = self.cached(widget.as_ref(), &data, &config) {
+            let _ = tx.send(hit);
+            return Some(rx);
+        }
+        let cache = self
+            .cache
+            .clone()
+            .filter(|_| self.ttl(widget.as_ref()).is_some());
+        thread::spawn(move || {
+            let _ = tx.send(render_and_store(
+                widget.as_ref(),
+                cache.as_ref(),
+                &data,
+                &config,
+            ));
+        });
+        Some(rx)
+    }
+
+    fn cached(
+        &self,
+        widget: &dyn Widget,
+        data: &SessionData,
+        config: &WidgetConfig,
+    ) -> Option<WidgetOutput> {
+        let ttl = self.ttl(widget)?;
+        self.cache.as_ref()?.get(data, config, ttl)
+    }
+
+    /// How long `widget`'s output may be reused: its own `cache_ttl`, raised
+    /// to `min_interval` for blocking widgets.
+    fn ttl(&self, widget: &dyn Widget) -> Option<Duration> {
+        let ttl = widget.cache_ttl();
+        if !widget.is_blocking() {
+            return ttl;
+        }
+        Some(ttl.unwrap_or_default().max(self.min_interval)).filter(|t| !t.is_zero())
     }
 
     fn register_defaults(&mut self) {
         self.register(Box::new(super::model::ModelWidget));
         self.register(Box::new(super::context::ContextPercentageWidget));
+        self.register(Box::new(super::context::ContextRemainingWidget));
         self.register(Box::new(super::context::ContextLengthWidget));
+        self.register(Box::new(super::context::HeadroomWidget));
+        self.register(Box::new(super::context::WindowSizeWidget));
+        self.register(Box::new(super::context_trend::ContextTrendWidget));
+        self.register(Box::new(super::context_gauge::ContextGaugeWidget));
+        self.register(Box::new(super::compaction_warning::CompactionWarningWidget));
         self.register(Box::new(super::tokens::TokenInputWidget));
         self.register(Box::new(super::tokens::TokenOutputWidget));
         self.register(Box::new(super::tokens::TokenCachedWidget));
@@ -51,24 +187,49 @@ impl WidgetRegistry {
         self.register(Box::new(super::git_branch::GitBranchWidget));
         self.register(Box::new(super::git_status::GitStatusWidget));
         self.register(Box::new(super::git_worktree::GitWorktreeWidget));
+        self.register(Box::new(super::git_age::GitAgeWidget));
+        self.register(Box::new(super::project::ProjectWidget));
         self.register(Box::new(super::cwd::CwdWidget));
         self.register(Box::new(super::lines_changed::LinesChangedWidget));
         self.register(Box::new(super::version::VersionWidget));
         self.register(Box::new(super::session_id::SessionIdWidget));
         self.register(Box::new(super::vim_mode::VimModeWidget));
         self.register(Box::new(super::agent_name::AgentNameWidget));
+        self.register(Box::new(super::agent_count::AgentCountWidget));
         self.register(Box::new(super::output_style::OutputStyleWidget));
         self.register(Box::new(super::exceeds_tokens::ExceedsTokensWidget));
         self.register(Box::new(super::api_duration::ApiDurationWidget));
         self.register(Box::new(super::custom_command::CustomCommandWidget));
         self.register(Box::new(super::custom_text::CustomTextWidget));
         self.register(Box::new(super::separator::SeparatorWidget));
+        self.register(Box::new(super::group_divider::GroupDividerWidget));
         self.register(Box::new(super::terminal_width::TerminalWidthWidget));
+        self.register(Box::new(super::transcript_size::TranscriptSizeWidget));
+        self.register(Box::new(super::idle_time::IdleTimeWidget));
         self.register(Box::new(super::flex_separator::FlexSeparatorWidget));
 
         // Pro widgets (gracefully hidden when not licensed)
         self.register(Box::new(super::burn_rate::BurnRateWidget));
+        self.register(Box::new(super::block_cost::BlockCostWidget));
+        self.register(Box::new(super::block_gauge::BlockGaugeWidget));
+        self.register(Box::new(super::last_message_cost::LastMessageCostWidget));
+        self.register(Box::new(super::cache_savings::CacheSavingsWidget));
         self.register(Box::new(super::cost_warning::CostWarningWidget));
+        self.register(Box::new(super::budget_remaining::BudgetRemainingWidget));
         self.register(Box::new(super::model_suggest::ModelSuggestWidget));
     }
 }
+
+/// Render, storing the output in `cache` when there is one.
+fn render_and_store(
+    widget: &dyn Widget,
+    cache: Option<&RenderCache>,
+    data: &SessionData,
+    config: &WidgetConfig,
+) -> WidgetOutput {
+    let output = widget.render(data, config);
+    if let Some(cache) = cache {
+        cache.put(data, config, &output);
+    }
+    output
+}
@@ -1,7 +1,9 @@
 use super::data::SessionData;
+use super::format;
 use super::traits::{Widget, WidgetConfig, WidgetOutput};
+use unicode_width::UnicodeWidthStr;
 
-fn format_tokens(n: u64, compact: bool) -> String {
+fn format_tokens(n: u64, compact: bool, config: &WidgetConfig) -> String {
     if compact {
         if n >= 1_000_000 {
             format!("{:.1}M", n as f64 / 1_000_000.0)
@@ -11,15 +13,9 @@ fn format_tokens(n: u64, compact: bool) -> String {
             n.to_string()
         }
     } else {
-        let s = n.to_string();
-        let mut result = String::new();
-        for (i, c) in s.chars().rev().enumerate() {
-            if i > 0 && i % 3 == 0 {
-                result.push(',');
-            }
-            result.push(c);
-        }
-        result.chars().rev().collect()
+        // Token counts have always been grouped; number_format only swaps the separator
+        let sep = config.settings.group_separator.as_deref().unwrap_or(",");
+        format::group_digits(n, sep)
     }
 }
 
@@ -50,12 +46,12 @@ impl Widget for TokenInputWidget {
 
         let val = usage.input_tokens.unwrap_or(0);
         let text = if config.raw_value {
-            format_tokens(val, true)
+            format_tokens(val, true, config)
         } else {
-            format!("In: {}", format_tokens(val, false))
+            format!("In: {}", format_tokens(val, false, config))
         };
 
-        let display_width = text.len();
+        let display_width = UnicodeWidthStr::width(text.as_str());
         WidgetOutput {
             text,
             display_width,
@@ -93,12 +89,12 @@ impl Widget for TokenOutputWidget {
 
         let val = usage.output_tokens.unwrap_or(0);
         let text = if config.raw_value {
-            format_tokens(val, true)
+            format_tokens(val, true, config)
         } else {
-            format!("Out: {}", format_tokens(val, false))
+            format!("Out: {}", format_tokens(val, false, config))
         };
 
-        let display_width = text.len();
+        let display_width = UnicodeWidthStr::width(text.as_str());
         WidgetOutput {
             text,
             display_width,
@@ -137,12 +133,12 @@ impl Widget for TokenCachedWidget {
         let val = usage.cache_creation_input_tokens.unwrap_or(0)
             + usage.cache_read_input_tokens.unwrap_or(0);
         let text = if config.raw_value {
-            format_tokens(val, true)
+            format_tokens(val, true, config)
         } else {
-            format!("Cache: {}", format_tokens(val, false))
+            format!("Cache: {}", format_tokens(val, false, config))
         };
 
-        let display_width = text.len();
+        let display_width = UnicodeWidthStr::width(text.as_str());
         WidgetOutput {
             text,
             display_width,
@@ -183,12 +179,12 @@ impl Widget for TokenTotalWidget {
             + usage.cache_creation_input_tokens.unwrap_or(0)
             + usage.cache_read_input_tokens.unwrap_or(0);
         let text = if config.raw_value {
-            format_tokens(val, true)
+            format_tokens(val, true, config)
         } else {
-            format!("Total: {}", format_tokens(val, false))
+            format!("Total: {}", format_tokens(val, false, config))
         };
 
-        let display_width = text.len();
+        let display_width = UnicodeWidthStr::width(text.as_str());
         WidgetOutput {
             text,
             display_width,
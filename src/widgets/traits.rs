@@ -1,7 +1,13 @@
 use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
 
 use super::data::SessionData;
+use crate::config::{CostConfig, DEFAULT_WEEKLY_LIMIT};
+use crate::icons::IconSet;
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WidgetOutput {
     pub text: String,
     pub display_width: usize,
@@ -21,9 +27,90 @@ pub struct WidgetConfig {
     pub padding: Option<String>,
     pub merge_next: bool,
     pub metadata: HashMap<String, String>,
+    pub settings: RenderSettings,
+}
+
+/// Config-level options shared by every widget, filled in by the layout engine.
+#[derive(Debug, Clone)]
+pub struct RenderSettings {
+    /// Thousands separator for counts when `number_format = "grouped"`.
+    pub group_separator: Option<String>,
+    pub icons: IconSet,
+    /// `[stats] weekly_limit`; a widget's `weekly_limit` metadata overrides it,
+    /// and a plan limit in the payload (`SessionData::weekly_limit`) overrides both.
+    pub weekly_limit: f64,
+    /// False when cost history is disabled; widgets must not open the DB.
+    pub history: bool,
+    /// `[cost]` currency settings; see `format::money`.
+    pub cost: CostConfig,
+    /// `git_backend = "native"`: read what git state we can in-process.
+    pub native_git: bool,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            group_separator: None,
+            icons: IconSet::default(),
+            weekly_limit: DEFAULT_WEEKLY_LIMIT,
+            history: true,
+            cost: CostConfig::default(),
+            native_git: false,
+        }
+    }
 }
 
 pub trait Widget: Send + Sync {
+    /// The widget's type name. The registry keys on it, so it must match
+    /// the `type` of the config lines that use it
+    /// (`LineWidgetConfig::widget_type`).
     fn name(&self) -> &str;
     fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput;
+
+    /// A terser form the layout engine swaps in when the line would
+    /// otherwise overflow, before it drops widgets. None (the default)
+    /// means the widget has no compact form.
+    fn render_compact(&self, _data: &SessionData, _config: &WidgetConfig) -> Option<WidgetOutput> {
+        None
+    }
+
+    /// Widgets that shell out or wait on IO return true so the layout engine
+    /// runs them off-thread under the render deadline.
+    fn is_blocking(&self) -> bool {
+        false
+    }
+
+    /// Widgets that read the cost history DB return true so a render records
+    /// the session there first.
+    fn reads_history(&self) -> bool {
+        false
+    }
+
+    /// Widgets whose output depends only on their config and the working
+    /// directory can return a TTL to have it memoized per session on disk.
+    fn cache_ttl(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Why `render` would come back hidden for this input, for `--explain`.
+    /// Widgets that return None when hidden are reported generically.
+    fn hidden_reason(&self, _data: &SessionData, _config: &WidgetConfig) -> Option<String> {
+        None
+    }
+}
+
+/// Whether a Pro widget backed by the cost history DB may render at all.
+pub(crate) fn pro_history_enabled(config: &WidgetConfig) -> bool {
+    config.settings.history && crate::license::is_pro()
+}
+
+/// The hidden reason shared by Pro widgets backed by the cost history DB.
+pub(crate) fn pro_history_reason(config: &WidgetConfig) -> Option<String> {
+    if !crate::license::is_pro() {
+        Some("not Pro".into())
+    } else if !config.settings.history {
+        Some("cost history disabled".into())
+    } else {
+        None
+    }
 }
@@ -0,0 +1,78 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+
+use unicode_width::UnicodeWidthStr;
+
+use super::data::SessionData;
+use super::format;
+use super::traits::{Widget, WidgetConfig, WidgetOutput};
+
+/// Transcripts larger than this are reported by size rather than read, so a
+/// long session doesn't cost a full file scan on every refresh.
+const LINE_COUNT_BUDGET: u64 = 4 * 1024 * 1024;
+
+/// Conversation length from the JSONL transcript: one line per message.
+pub struct TranscriptSizeWidget;
+
+fn count_lines(file: File) -> Option<u64> {
+    let mut reader = BufReader::new(file.take(LINE_COUNT_BUDGET));
+    let mut count = 0;
+    loop {
+        let buf = reader.fill_buf().ok()?;
+        if buf.is_empty() {
+            return Some(count);
+        }
+        count += buf.iter().filter(|&&b| b == b'\n').count() as u64;
+        let len = buf.len();
+        reader.consume(len);
+    }
+}
+
+impl Widget for TranscriptSizeWidget {
+    fn name(&self) -> &str {
+        "transcript-size"
+    }
+
+    fn render(&self, data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+        let hidden = WidgetOutput {
+            text: String::new(),
+            display_width: 0,
+            priority: 30,
+            visible: false,
+            color_hint: None,
+        };
+        let Some(file) = data
+            .transcript_path
+            .as_deref()
+            .and_then(|p| File::open(p).ok())
+        else {
+            return hidden;
+        };
+        let Ok(size) = file.metadata().map(|m| m.len()) else {
+            return hidden;
+        };
+
+        let show_size = config.metadata.get("size").map(|v| v == "true") == Some(true);
+        let text = if show_size || size > LINE_COUNT_BUDGET {
+            format::bytes(size)
+        } else {
+            let Some(lines) = count_lines(file) else {
+                return hidden;
+            };
+            if config.raw_value {
+                format::count(lines, config)
+            } else {
+                format!("{} msgs", format::count(lines, config))
+            }
+        };
+
+        let display_width = UnicodeWidthStr::width(text.as_str());
+        WidgetOutput {
+            text,
+            display_width,
+            priority: 30,
+            visible: true,
+            color_hint: None,
+        }
+    }
+}
@@ -0,0 +1,636 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+const BIN: &str = env!("CARGO_BIN_EXE_ai-statusline");
+
+/// A per-test scratch dir so runs never touch the user's config or history.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("claude-status-cli-{}-{name}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn run(name: &str, args: &[&str], stdin: &str) -> Output {
+    let dir = scratch_dir(name);
+    let output = run_in(&dir, args, stdin);
+    let _ = std::fs::remove_dir_all(&dir);
+    output
+}
+
+/// Run with `dir` as the data dir and `dir/config.toml` as the config, so
+/// state persists across calls.
+fn run_in(dir: &Path, args: &[&str], stdin: &str) -> Output {
+    run_env(dir, args, stdin, &[])
+}
+
+/// Start a Pro trial with its license files and trial marker kept in `dir`,
+/// returning the environment later runs need to see it.
+fn start_trial(dir: &Path) -> [(&'static str, &Path); 2] {
+    let env = [("XDG_CONFIG_HOME", dir), ("XDG_DATA_HOME", dir)];
+    assert!(
+        run_env(dir, &["license", "trial"], "", &env)
+            .status
+            .success()
+    );
+    env
+}
+
+/// `run_in` with extra environment variables.
+fn run_env(dir: &Path, args: &[&str], stdin: &str, env: &[(&str, &Path)]) -> Output {
+    let mut child = Command::new(BIN)
+        .arg("--config")
+        .arg(dir.join("config.toml"))
+        .args(args)
+        .env("CLAUDE_STATUS_DATA_DIR", dir)
+        .envs(env.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn binary");
+    // The binary may exit before reading stdin (e.g. on a bad flag).
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn renders_dump_schema_sample_end_to_end() {
+    let schema = run("schema", &["dump-schema"], "");
+    assert!(schema.status.success());
+    let sample = String::from_utf8(schema.stdout).unwrap();
+
+    let output = run(
+        "render",
+        &["--color-level", "none", "--no-history"],
+        &sample,
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Opus"), "missing model in {stdout:?}");
+    assert!(stdout.contains("$0.42"), "missing cost in {stdout:?}");
+    assert!(!stdout.contains('\x1b'), "color-level none emitted ANSI");
+}
+
+#[test]
+fn malformed_input_prints_fallback_line() {
+    let output = run("fallback", &["--color-level", "none"], "{not json");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.trim_end(), "claude-status: bad input");
+}
+
+#[test]
+fn silent_stdin_times_out_to_fallback_line() {
+    let dir = scratch_dir("stdin-timeout");
+    let mut child = Command::new(BIN)
+        .arg("--config")
+        .arg(dir.join("config.toml"))
+        .args([
+            "--color-level",
+            "none",
+            "--no-history",
+            "--stdin-timeout",
+            "200",
+        ])
+        .env("CLAUDE_STATUS_DATA_DIR", &dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn binary");
+    // Hold the pipe open without writing, like a stuck host.
+    let stdin = child.stdin.take().unwrap();
+    let output = child.wait_with_output().unwrap();
+    drop(stdin);
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.trim_end(), "claude-status: input timed out");
+}
+
+#[test]
+fn starship_and_kv_formats() {
+    let payload = r#"{ "model": { "display_name": "Opus" }, "cost": { "total_cost_usd": 0.5 } }"#;
+    let dir = scratch_dir("formats");
+    std::fs::write(
+        dir.join("config.toml"),
+        r#"lines = [[{ type = "model" }, { type = "session-cost" }], [{ type = "model" }]]"#,
+    )
+    .unwrap();
+    let args = |format| ["--color-level", "none", "--no-history", "--format", format];
+
+    let output = run_in(&dir, &args("starship"), payload);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.trim(), "Opus  |  $0.50   Opus");
+    assert!(!stdout.contains('\n'), "{stdout:?}");
+
+    let output = run_in(&dir, &args("kv"), payload);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(stdout, "model=Opus\nsession-cost=$0.50\nmodel.2=Opus\n");
+}
+
+#[test]
+fn empty_input_renders_an_empty_session() {
+    let output = run("empty", &["--color-level", "none", "--no-history"], "");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("claude-status:"), "{stdout:?}");
+
+    let output = run(
+        "require",
+        &["--color-level", "none", "--require-input"],
+        " \n",
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("no input"), "{stdout:?}");
+    let output = run("require-strict", &["--require-input", "--strict"], "");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn strict_mode_exits_non_zero_on_malformed_input() {
+    let output = run("strict", &["--strict"], "{not json");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn version_json_reports_build_metadata() {
+    let output = run("version", &["version", "--json"], "");
+    assert!(output.status.success());
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(value["version"], env!("CARGO_PKG_VERSION"));
+    assert!(!value["commit"].as_str().unwrap().is_empty());
+    let date = value["build_date"].as_str().unwrap();
+    assert!(
+        chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok(),
+        "bad build date {date:?}"
+    );
+}
+
+#[test]
+fn check_update_compares_against_the_release_endpoint() {
+    if Command::new("curl").arg("--version").output().is_err() {
+        // No curl to fetch with.
+        return;
+    }
+    let dir = scratch_dir("check-update");
+    let release = dir.join("latest.json");
+    let config = format!("update_url = \"file://{}\"\n", release.display());
+    std::fs::write(dir.join("config.toml"), config).unwrap();
+
+    std::fs::write(&release, r#"{ "tag_name": "v999.0.0" }"#).unwrap();
+    let output = run_in(&dir, &["check-update"], "");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Update available"), "{stdout}");
+    assert!(stdout.contains("999.0.0"), "{stdout}");
+
+    let current = format!(r#"{{ "tag_name": "v{}" }}"#, env!("CARGO_PKG_VERSION"));
+    std::fs::write(&release, current).unwrap();
+    let output = run_in(&dir, &["check-update"], "");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("up to date"));
+
+    // An unreachable endpoint is reported, not an error.
+    std::fs::remove_file(&release).unwrap();
+    let output = run_in(&dir, &["check-update"], "");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("couldn't check"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn debug_env_traces_to_stderr_only() {
+    let sample = run("debug-schema", &["dump-schema"], "").stdout;
+    let sample = String::from_utf8(sample).unwrap();
+    let dir = scratch_dir("debug");
+    std::fs::write(
+        dir.join("config.toml"),
+        "lines = [[{ type = \"model\" }, { type = \"vim-mode\", metadata = { only_for_models = \"haiku\" } }]]\n",
+    )
+    .unwrap();
+    let quiet = run_in(&dir, &["--color-level", "none"], &sample);
+    assert!(quiet.stderr.is_empty());
+
+    let mut child = Command::new(BIN)
+        .arg("--config")
+        .arg(dir.join("config.toml"))
+        .args(["--color-level", "none"])
+        .env("CLAUDE_STATUS_DATA_DIR", &dir)
+        .env("CLAUDE_STATUS_DEBUG", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(sample.as_bytes())
+        .unwrap();
+    let traced = child.wait_with_output().unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert_eq!(traced.stdout, quiet.stdout);
+    let log = String::from_utf8_lossy(&traced.stderr);
+    assert!(log.contains("config: "), "{log}");
+    assert!(log.contains("color level: None"), "{log}");
+    assert!(log.contains("line 1: model shown"), "{log}");
+    assert!(
+        log.contains("line 1: vim-mode hidden: not for this model"),
+        "{log}"
+    );
+}
+
+#[test]
+fn line_flag_renders_only_that_line() {
+    let dir = scratch_dir("line");
+    std::fs::write(
+        dir.join("config.toml"),
+        "lines = [[{ type = \"model\" }], [{ type = \"session-cost\" }]]\n",
+    )
+    .unwrap();
+    let sample = r#"{"model":{"display_name":"Opus"},"cost":{"total_cost_usd":0.42}}"#;
+    let args = |n: &'static str| ["--color-level", "none", "--no-history", "--line", n];
+
+    let second = run_in(&dir, &args("2"), sample);
+    assert!(second.status.success());
+    let stdout = String::from_utf8(second.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 1, "{stdout:?}");
+    assert!(
+        stdout.contains("$0.42") && !stdout.contains("Opus"),
+        "{stdout:?}"
+    );
+
+    let missing = run_in(&dir, &args("3"), sample);
+    assert!(!missing.status.success());
+    assert!(missing.stdout.is_empty());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn theme_flag_overrides_config_for_one_render() {
+    let dir = scratch_dir("theme-flag");
+    std::fs::write(
+        dir.join("config.toml"),
+        "lines = [[{ type = \"model\" }]]\n",
+    )
+    .unwrap();
+    let sample = r#"{"model":{"display_name":"Opus"}}"#;
+    let args = |theme: &'static str| {
+        [
+            "--color-level",
+            "truecolor",
+            "--no-history",
+            "--theme",
+            theme,
+        ]
+    };
+
+    // nord's model color is #88c0d0
+    let nord = run_in(&dir, &args("nord"), sample);
+    assert!(nord.status.success());
+    let stdout = String::from_utf8(nord.stdout).unwrap();
+    assert!(stdout.contains("38;2;136;192;208"), "{stdout:?}");
+    let config = std::fs::read_to_string(dir.join("config.toml")).unwrap();
+    assert!(!config.contains("nord"));
+
+    let unknown = run_in(&dir, &args("nrod"), sample);
+    assert!(!unknown.status.success());
+    assert!(unknown.stdout.is_empty());
+    let stderr = String::from_utf8(unknown.stderr).unwrap();
+    assert!(
+        stderr.contains("nrod") && stderr.contains("nord"),
+        "{stderr}"
+    );
+
+    // Rejected with the other arguments, not after waiting on stdin: the
+    // pipe stays open and the binary still exits well inside its timeout.
+    let mut child = Command::new(BIN)
+        .args(["--theme", "nrod"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let started = std::time::Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        assert!(
+            started.elapsed() < std::time::Duration::from_millis(1500),
+            "--theme was checked after reading stdin"
+        );
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    };
+    assert!(!status.success());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn stats_since_rejects_malformed_durations() {
+    let output = run("stats-since", &["stats", "--since", "3x"], "");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("3x") && stderr.contains("12h"), "{stderr}");
+
+    let output = run("stats-since-ok", &["stats", "--since", "3d"], "");
+    assert!(output.status.success());
+
+    // Spans past the epoch clamp rather than overflow. Stats needs Pro, so
+    // start a trial in a scratch config dir.
+    let dir = scratch_dir("stats-since-huge");
+    let env = start_trial(&dir);
+    let output = run_env(
+        &dir,
+        &["stats", "--since", "9223372036854775808s"],
+        "",
+        &env,
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Sessions in the last"), "{stdout}");
+}
+
+#[test]
+fn trial_cannot_restart_from_a_fresh_data_dir() {
+    let dir = scratch_dir("trial-once");
+    let env = start_trial(&dir);
+    std::fs::remove_file(dir.join("claude-status/trial.json")).unwrap();
+
+    let fresh = scratch_dir("trial-once-fresh");
+    let output = run_env(&fresh, &["license", "trial"], "", &env);
+    let _ = std::fs::remove_dir_all(&dir);
+    let _ = std::fs::remove_dir_all(&fresh);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("already been used"), "{stderr}");
+}
+
+#[test]
+fn unknown_color_level_is_rejected() {
+    let output = run("bad-color", &["--color-level", "truColor"], "{}");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("truColor"), "unexpected error: {stderr}");
+}
+
+#[test]
+fn profile_writes_widget_timings_to_stderr() {
+    let sample = String::from_utf8(run("schema-profile", &["dump-schema"], "").stdout).unwrap();
+    let output = run(
+        "profile",
+        &["--color-level", "none", "--no-history", "--profile"],
+        &sample,
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Opus"));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("widget"), "unexpected profile: {stderr}");
+    assert!(
+        stderr
+            .lines()
+            .any(|l| l.starts_with("model ") && l.ends_with("yes"))
+    );
+}
+
+#[test]
+fn theme_preview_prints_role_swatches() {
+    let output = run(
+        "preview",
+        &["--color-level", "truecolor", "theme", "preview", "nord"],
+        "",
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Theme: nord"), "{stdout}");
+    assert!(stdout.contains("Git branch"), "{stdout}");
+    assert!(stdout.contains("\x1b[38;2;"), "{stdout:?}");
+
+    let output = run(
+        "preview-plain",
+        &["--color-level", "none", "theme", "preview", "nord"],
+        "",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("████"));
+    assert!(!stdout.contains('\x1b'), "{stdout:?}");
+
+    let output = run("preview-bad", &["theme", "preview", "nope"], "");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Unknown theme 'nope'"), "{stderr}");
+}
+
+#[test]
+fn setup_writes_config_and_backs_up_existing() {
+    let dir = scratch_dir("setup");
+    let path = dir.join("config.toml");
+    let _ = std::fs::remove_file(&path);
+
+    let output = run_in(&dir, &["setup"], "bogus\npowerline\nnord\ny\n");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Please pick one of"), "{stdout}");
+    assert!(stdout.contains("\"statusLine\""), "{stdout}");
+    assert!(stdout.contains("\"type\": \"command\""), "{stdout}");
+    let first = std::fs::read_to_string(&path).unwrap();
+    let config: claude_status::config::Config = toml::from_str(&first).unwrap();
+    assert!(config.powerline.enabled);
+    assert_eq!(config.theme, "nord");
+    assert_eq!(config.icon_set, "nerd");
+
+    // Defaults all the way: back up, "full" preset, keep theme, no Nerd Font.
+    let output = run_in(&dir, &["setup"], "");
+    assert!(output.status.success());
+    assert_eq!(
+        std::fs::read_to_string(dir.join("config.toml.bak")).unwrap(),
+        first
+    );
+    let config: claude_status::config::Config =
+        toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(!config.powerline.enabled);
+    assert_eq!(config.lines.len(), 2);
+    assert_eq!(config.icon_set, "emoji");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn renders_record_cost_deltas() {
+    let payload = |total: f64| {
+        format!(r#"{{ "session_id": "s1", "cost": {{ "total_cost_usd": {total} }} }}"#)
+    };
+    let dir = scratch_dir("cost-deltas");
+    let db = dir.join("history.db");
+
+    // Only Pro renders with a widget that reads the history record to it.
+    assert!(run_in(&dir, &[], &payload(0.5)).status.success());
+    assert!(!db.exists(), "a free render wrote cost history");
+    let env = start_trial(&dir);
+    assert!(run_env(&dir, &[], &payload(0.5), &env).status.success());
+    assert!(
+        !db.exists(),
+        "no widget reads the history, yet it was written"
+    );
+    std::fs::write(
+        dir.join("config.toml"),
+        "lines = [[{ type = \"last-message-cost\" }]]\n",
+    )
+    .unwrap();
+    assert!(
+        run_env(&dir, &["--no-history"], &payload(0.5), &env)
+            .status
+            .success()
+    );
+    assert!(!db.exists(), "--no-history wrote cost history");
+
+    for total in [0.5, 0.75, 0.75] {
+        assert!(run_env(&dir, &[], &payload(total), &env).status.success());
+    }
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    let costs: Vec<f64> = conn
+        .prepare("SELECT cost FROM events WHERE session_id = 's1' ORDER BY id")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(costs, vec![0.5, 0.25]);
+}
+
+#[test]
+fn plan_limits_are_saved_for_stats() {
+    let payload = r#"{ "model": { "display_name": "Opus" }, "limits": { "weekly_usd": 150.0 } }"#;
+    let dir = scratch_dir("limits");
+    let saved = dir.join("limits.json");
+
+    // `stats` is Pro-only, so free renders don't save limits for it.
+    assert!(run_in(&dir, &[], payload).status.success());
+    assert!(!saved.exists(), "a free render wrote limits");
+    let env = start_trial(&dir);
+    assert!(
+        run_env(&dir, &["--no-history"], payload, &env)
+            .status
+            .success()
+    );
+    assert!(!saved.exists(), "--no-history wrote limits");
+
+    assert!(run_env(&dir, &[], payload, &env).status.success());
+    let json = std::fs::read_to_string(&saved).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(json.contains("\"weekly_usd\":150.0"), "{json}");
+}
+
+#[test]
+fn minify_shortens_powerline_output() {
+    let sample = String::from_utf8(run("schema-minify", &["dump-schema"], "").stdout).unwrap();
+    let dir = scratch_dir("minify");
+    std::fs::write(dir.join("config.toml"), "[powerline]\nenabled = true\n").unwrap();
+    let args = ["--color-level", "truecolor", "--no-history"];
+    let full = run_in(&dir, &args, &sample);
+    let small = run_in(&dir, &[&args[..], &["--minify"]].concat(), &sample);
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(small.status.success());
+    let (full, small) = (
+        String::from_utf8(full.stdout).unwrap(),
+        String::from_utf8(small.stdout).unwrap(),
+    );
+    assert!(small.contains("Opus"));
+    assert!(!small.contains("\x1b[0m\x1b[0m"), "{small:?}");
+    assert!(small.len() < full.len(), "{small:?} vs {full:?}");
+}
+
+#[test]
+fn context_trend_compares_with_previous_render_of_same_session() {
+    let dir = scratch_dir("trend");
+    std::fs::write(
+        dir.join("config.toml"),
+        "lines = [[{ type = \"context-trend\" }]]\n",
+    )
+    .unwrap();
+    let payload = |session: &str, used: f64| {
+        format!(r#"{{"session_id":"{session}","context_window":{{"used_percentage":{used}}}}}"#)
+    };
+    let render = |session: &str, used: f64| {
+        let output = run_in(&dir, &["--color-level", "none"], &payload(session, used));
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    };
+
+    assert_eq!(render("a", 40.0), "40%");
+    assert_eq!(render("a", 43.0), "43% ↑3");
+    assert_eq!(render("a", 43.2), "43% →");
+    assert_eq!(render("a", 41.0), "41% ↓2");
+    // Another session starts without a baseline.
+    assert_eq!(render("b", 90.0), "90%");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn explain_prints_a_report_instead_of_the_line() {
+    let output = run(
+        "explain",
+        &["--color-level", "none", "--no-history", "--explain"],
+        r#"{"model":{"display_name":"Opus"}}"#,
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.starts_with("line  widget"),
+        "unexpected report: {stdout}"
+    );
+    assert!(
+        stdout
+            .lines()
+            .any(|l| l.contains("model") && l.ends_with("shown"))
+    );
+    assert!(
+        stdout
+            .lines()
+            .any(|l| l.contains("session-cost") && l.ends_with("hidden: no cost data"))
+    );
+}
+
+#[test]
+fn wallclock_duration_counts_from_first_sighting() {
+    let dir = scratch_dir("wallclock");
+    std::fs::write(
+        dir.join("config.toml"),
+        "lines = [[{ type = \"session-duration\", metadata = { source = \"wallclock\" } }]]\n",
+    )
+    .unwrap();
+    let payload = r#"{ "session_id": "s1", "cost": { "total_duration_ms": 7200000 } }"#;
+    let output = run_in(&dir, &["--color-level", "none"], payload);
+    let _ = std::fs::remove_dir_all(&dir);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    // Just seen, so far short of the payload's two hours.
+    assert!(stdout.contains("0m "), "unexpected output: {stdout}");
+    assert!(!stdout.contains("2h"), "unexpected output: {stdout}");
+}
+
+#[test]
+fn agent_count_sees_other_sessions() {
+    let dir = scratch_dir("agents");
+    std::fs::write(
+        dir.join("config.toml"),
+        "lines = [[{ type = \"agent-count\" }]]\n",
+    )
+    .unwrap();
+    let args = ["--color-level", "none", "--no-history"];
+    let first = run_in(&dir, &args, r#"{ "session_id": "a" }"#);
+    let second = run_in(&dir, &args, r#"{ "session_id": "b" }"#);
+    let again = run_in(&dir, &args, r#"{ "session_id": "a" }"#);
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let stdout = |o: &Output| String::from_utf8(o.stdout.clone()).unwrap();
+    assert!(!stdout(&first).contains("agent"), "{}", stdout(&first));
+    assert!(stdout(&second).contains("2 agents"), "{}", stdout(&second));
+    assert!(stdout(&again).contains("2 agents"), "{}", stdout(&again));
+}
@@ -49,6 +49,92 @@ fn toml_roundtrip() {
     assert_eq!(deserialized.lines[0].len(), original.lines[0].len());
 }
 
+#[test]
+fn lines_load_from_tables_or_nested_arrays() {
+    let tables: Config = toml::from_str(
+        r#"
+        [[lines]]
+        [[lines.widgets]]
+        type = "model"
+        [[lines.widgets]]
+        type = "cwd"
+        metadata = { segments = "2" }
+
+        [[lines]]
+        [[lines.widgets]]
+        type = "git-branch"
+        "#,
+    )
+    .unwrap();
+    let nested: Config = toml::from_str(
+        r#"lines = [[{ type = "model" }, { type = "cwd", metadata = { segments = "2" } }], [{ type = "git-branch" }]]"#,
+    )
+    .unwrap();
+    let mixed: Config = toml::from_str(
+        r#"
+        lines = [[{ type = "model" }, { type = "cwd", metadata = { segments = "2" } }], { widgets = [{ type = "git-branch" }] }]
+        "#,
+    )
+    .unwrap();
+
+    for config in [&tables, &nested, &mixed] {
+        let types: Vec<Vec<&str>> = config
+            .lines
+            .iter()
+            .map(|line| line.iter().map(|w| w.widget_type.as_str()).collect())
+            .collect();
+        assert_eq!(types, [vec!["model", "cwd"], vec!["git-branch"]]);
+        assert_eq!(config.lines[0][1].metadata["segments"], "2");
+    }
+}
+
+#[test]
+fn to_toml_writes_line_tables_and_round_trips() {
+    let mut config = Config::default();
+    config.lines.push(vec![config.lines[0][0].clone()]);
+    config.profiles.insert(
+        "work".into(),
+        toml::from_str("lines = [[{ type = \"cwd\" }]]").unwrap(),
+    );
+
+    let first = config.to_toml();
+    assert!(first.contains("[[lines.widgets]]"), "{first}");
+    assert!(first.contains("[[profiles.work.lines.widgets]]"), "{first}");
+    assert!(!first.contains("lines = [["), "{first}");
+
+    let reloaded: Config = toml::from_str(&first).unwrap();
+    assert_eq!(reloaded.to_toml(), first);
+    assert_eq!(reloaded.lines.len(), 2);
+    assert_eq!(
+        reloaded.profiles["work"].lines.as_ref().unwrap()[0][0].widget_type,
+        "cwd"
+    );
+}
+
+#[test]
+fn flat_line_tables_are_rejected() {
+    // A widget's keys directly under [[lines]] is not a line of widgets.
+    let result = toml::from_str::<Config>("[[lines]]\ntype = \"model\"\n");
+    assert!(result.is_err());
+}
+
+#[test]
+fn bad_widget_fields_are_named_in_either_line_spelling() {
+    for toml in [
+        "lines = [[{ type = \"model\", bold = \"yes\" }]]",
+        "[[lines]]\n[[lines.widgets]]\ntype = \"model\"\nbold = \"yes\"\n",
+    ] {
+        let error = toml::from_str::<Config>(toml).unwrap_err().to_string();
+        assert!(!error.contains("variant"), "{error}");
+        assert!(error.contains("bold"), "{error}");
+        assert!(error.contains("expected a boolean"), "{error}");
+    }
+    let error = toml::from_str::<Config>("[[lines]]\ntype = \"model\"\n")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("type"), "{error}");
+}
+
 #[test]
 fn loading_nonexistent_config_returns_default() {
     let config = Config::load(Some("/nonexistent/path/to/config.toml"));
@@ -57,6 +143,30 @@ fn loading_nonexistent_config_returns_default() {
     assert_eq!(config.lines[0].len(), 4);
 }
 
+#[test]
+fn config_format_follows_extension() {
+    use std::path::Path;
+
+    let json = r#"{ "theme": "dracula", "lines": [[{ "type": "model" }], { "widgets": [{ "type": "cwd" }] }] }"#;
+    let config = Config::parse(Path::new("config.json"), json).unwrap();
+    assert_eq!(config.theme, "dracula");
+    assert_eq!(config.lines[1][0].widget_type, "cwd");
+    // JSON isn't TOML, and the TOML parser gets unknown extensions.
+    assert!(Config::parse(Path::new("config.conf"), json).is_err());
+    assert!(Config::parse(Path::new("config"), "theme = \"nord\"").is_ok());
+
+    let error = Config::parse(Path::new("config.yaml"), "theme: dracula").unwrap_err();
+    assert!(error.contains("YAML"), "{error}");
+
+    // What `init --format json` writes loads back.
+    for name in ["config.toml", "config.json"] {
+        let path = Path::new(name);
+        let defaults = Config::default().serialize_for(path);
+        let config = Config::parse(path, &defaults).unwrap();
+        assert_eq!(config.to_toml(), Config::default().to_toml(), "{name}");
+    }
+}
+
 #[test]
 fn config_powerline_defaults() {
     let config = Config::default();
@@ -70,8 +180,7 @@ fn config_powerline_defaults() {
 
 #[test]
 fn config_from_toml_with_custom_theme() {
-    // Build custom config programmatically (lines is Vec<Vec<LineWidgetConfig>>,
-    // so direct TOML [[lines]] won't map correctly). Verify via roundtrip instead.
+    // Build custom config programmatically and verify via roundtrip.
     let mut config = Config::default();
     config.theme = "solarized".into();
     config.color_level = "truecolor".into();
@@ -93,6 +202,86 @@ fn config_from_toml_with_custom_theme() {
     assert!(deserialized.powerline.auto_align);
 }
 
+#[test]
+fn line_options_default_to_config_order() {
+    let config: Config = toml::from_str(
+        r#"
+        [[line_options]]
+        sort = "priority"
+        "#,
+    )
+    .expect("Failed to parse line_options");
+    assert_eq!(config.line_options(0).sort, "priority");
+    assert_eq!(config.line_options(1).sort, "config");
+    assert!(!Config::default().to_toml().contains("line_options"));
+}
+
+#[test]
+fn select_line_keeps_its_options() {
+    let mut config: Config = toml::from_str(
+        r#"
+        lines = [[{ type = "model" }], [{ type = "cwd" }]]
+
+        [[line_options]]
+        sort = "config"
+
+        [[line_options]]
+        sort = "priority"
+        "#,
+    )
+    .unwrap();
+    assert!(!config.clone().select_line(0));
+    assert!(!config.clone().select_line(3));
+    assert!(config.select_line(2));
+    assert_eq!(config.lines.len(), 1);
+    assert_eq!(config.lines[0][0].widget_type, "cwd");
+    assert_eq!(config.line_options(0).sort, "priority");
+}
+
+#[test]
+fn disabled_widgets_parse_and_default_empty() {
+    let config: Config = toml::from_str("disabled_widgets = [\"git-status\"]\n").unwrap();
+    assert!(config.is_disabled("git-status"));
+    assert!(!config.is_disabled("git-branch"));
+    assert!(Config::default().disabled_widgets.is_empty());
+    assert!(!Config::default().to_toml().contains("disabled_widgets"));
+}
+
+#[test]
+fn number_format_grouped_sets_widget_separator() {
+    let mut config = Config::default();
+    let wc = config.widget_config(&config.lines[0][0]);
+    assert!(wc.settings.group_separator.is_none());
+
+    config.number_format = "grouped".into();
+    config.thousands_separator = "_".into();
+    let wc = config.widget_config(&config.lines[0][0]);
+    assert_eq!(wc.settings.group_separator.as_deref(), Some("_"));
+}
+
+#[test]
+fn stats_weekly_limit_feeds_widget_settings() {
+    let config = Config::default();
+    let wc = config.widget_config(&config.lines[0][0]);
+    assert_eq!(wc.settings.weekly_limit, 200.0);
+
+    let config: Config = toml::from_str("[stats]\nweekly_limit = 100.0\n").unwrap();
+    let wc = config.widget_config(&config.lines[0][0]);
+    assert_eq!(wc.settings.weekly_limit, 100.0);
+}
+
+#[test]
+fn cost_section_feeds_widget_settings() {
+    let config = Config::default();
+    assert_eq!(config.cost.format(1.5), "$1.50");
+
+    let config: Config =
+        toml::from_str("[cost]\nsymbol = \"€\"\nrate = 0.5\ndecimals = 1\n").unwrap();
+    let wc = config.widget_config(&config.lines[0][0]);
+    assert_eq!(wc.settings.cost.format(3.0), "€1.5");
+    assert_eq!(wc.settings.cost.format_places(200.0, 0), "€100");
+}
+
 #[test]
 fn config_to_widget_config_conversion() {
     let config = Config::default();
@@ -113,3 +302,83 @@ fn config_to_toml_is_valid() {
     assert!(toml_str.contains("default"));
     assert!(toml_str.contains("model"));
 }
+
+#[test]
+fn profile_overrides_only_what_it_sets() {
+    let toml_str = r#"
+theme = "nord"
+default_separator = " / "
+
+[profiles.present]
+theme = "light"
+lines = [[{ type = "model" }]]
+"#;
+    let mut config: Config = toml::from_str(toml_str).unwrap();
+    assert!(config.apply_profile("present"));
+    assert_eq!(config.theme, "light");
+    assert_eq!(config.lines.len(), 1);
+    assert_eq!(config.lines[0].len(), 1);
+    assert_eq!(config.lines[0][0].widget_type, "model");
+    assert_eq!(config.default_separator, " / ");
+    assert!(!config.powerline.enabled);
+}
+
+#[test]
+fn unknown_profile_leaves_config_untouched() {
+    let mut config: Config = toml::from_str("theme = \"nord\"\n").unwrap();
+    assert!(!config.apply_profile("missing"));
+    assert_eq!(config.theme, "nord");
+    assert_eq!(config.lines[0].len(), 4);
+}
+
+#[test]
+fn explicit_profile_beats_environment() {
+    let env = || Some("home".to_string());
+    assert_eq!(
+        Config::pick_profile(Some("work"), env()),
+        Some("work".into())
+    );
+    assert_eq!(Config::pick_profile(None, env()), Some("home".into()));
+    assert_eq!(Config::pick_profile(None, Some(String::new())), None);
+    assert_eq!(Config::pick_profile(None, None), None);
+}
+
+#[test]
+fn json_schema_covers_every_config_key() {
+    let schema = Config::json_schema();
+    let properties = schema["properties"].as_object().unwrap();
+
+    let mut config = Config::default();
+    config.line_options.push(Default::default());
+    config.profiles.insert("work".into(), Default::default());
+    let value = serde_json::to_value(&config).unwrap();
+    for key in value.as_object().unwrap().keys() {
+        assert!(properties.contains_key(key), "schema is missing {key}");
+    }
+
+    let widget = &schema["$defs"]["widget"]["properties"];
+    for key in serde_json::to_value(&config.lines[0][0])
+        .unwrap()
+        .as_object()
+        .unwrap()
+        .keys()
+    {
+        assert!(widget.get(key).is_some(), "widget schema is missing {key}");
+    }
+    let types = widget["type"]["enum"].as_array().unwrap();
+    assert!(types.contains(&"model".into()));
+    assert!(types.contains(&"compaction-warning".into()));
+}
+
+#[test]
+fn unknown_git_backend_is_rejected() {
+    let config: Config = toml::from_str("git_backend = \"native\"").unwrap();
+    assert_eq!(config.git_backend, "native");
+    let error = toml::from_str::<Config>("git_backend = \"libgit2\"")
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.contains("libgit2") && error.contains("native"),
+        "{error}"
+    );
+}
@@ -1,7 +1,7 @@
 use claude_status::config::Config;
 use claude_status::layout::LayoutEngine;
-use claude_status::render::Renderer;
-use claude_status::widgets::{SessionData, WidgetRegistry};
+use claude_status::render::{ColorLevel, ColorSpec, Renderer};
+use claude_status::widgets::{SessionData, SessionDataBuilder, WidgetRegistry};
 
 fn render_json(json: &str) -> Vec<String> {
     let data: SessionData = serde_json::from_str(json).expect("Failed to parse JSON");
@@ -135,7 +135,7 @@ fn render_full_session_data() {
     let combined = lines.join("");
     // Default config renders: model, context-percentage, session-cost, session-duration
     assert!(combined.contains("Opus"));
-    assert!(combined.contains("42%"));
+    assert!(combined.contains("43%"));
     assert!(combined.contains("$0.08"));
     assert!(combined.contains("5m"));
 }
@@ -165,31 +165,13 @@ fn multiline_config_produces_multiple_lines() {
 
     // Build a two-line config programmatically since lines is Vec<Vec<LineWidgetConfig>>
     use claude_status::config::LineWidgetConfig;
-    use std::collections::HashMap;
 
     let mut config = Config::default();
     config.lines = vec![
+        vec![LineWidgetConfig::new("model")],
         vec![LineWidgetConfig {
-            widget_type: "model".into(),
-            id: "1".into(),
-            color: None,
-            background_color: None,
-            bold: None,
-            raw_value: false,
-            padding: None,
-            merge_next: false,
-            metadata: HashMap::new(),
-        }],
-        vec![LineWidgetConfig {
-            widget_type: "session-cost".into(),
-            id: "2".into(),
-            color: None,
-            background_color: None,
-            bold: None,
             raw_value: true,
-            padding: None,
-            merge_next: false,
-            metadata: HashMap::new(),
+            ..LineWidgetConfig::new("session-cost")
         }],
     ];
 
@@ -200,6 +182,21 @@ fn multiline_config_produces_multiple_lines() {
     assert_eq!(lines.len(), 2, "Should produce two output lines");
 }
 
+#[test]
+fn dumped_schema_round_trips_into_session_data() {
+    let dumped = serde_json::to_string_pretty(&SessionData::sample()).unwrap();
+    let parsed: SessionData =
+        serde_json::from_str(&dumped).expect("Dumped schema should parse back");
+    assert_eq!(
+        serde_json::to_value(&parsed).unwrap(),
+        serde_json::to_value(SessionData::sample()).unwrap()
+    );
+    assert_eq!(
+        parsed.model.as_ref().unwrap().id.as_deref(),
+        Some("claude-opus-4-6")
+    );
+}
+
 #[test]
 fn json_with_unknown_fields_still_parses() {
     let json = r#"{
@@ -215,6 +212,26 @@ fn json_with_unknown_fields_still_parses() {
     );
 }
 
+#[test]
+fn json_with_future_nested_fields_still_parses() {
+    let json = r#"{
+        "model": { "id": "claude-opus-4-6", "display_name": "Opus", "tier": "max" },
+        "cost": { "total_cost_usd": 0.05, "currency": "USD" },
+        "permissions": {
+            "mode": "acceptEdits",
+            "allowed_tools": ["Read", "Edit"]
+        },
+        "hooks": [{ "event": "Stop" }]
+    }"#;
+    let data: SessionData =
+        serde_json::from_str(json).expect("Should parse with future nested fields");
+    assert_eq!(
+        data.model.as_ref().unwrap().display_name.as_deref(),
+        Some("Opus")
+    );
+    assert_eq!(data.cost.as_ref().unwrap().total_cost_usd, Some(0.05));
+}
+
 #[test]
 fn json_with_null_fields_parses() {
     let json = r#"{
@@ -245,6 +262,285 @@ fn renderer_none_produces_no_ansi() {
     }
 }
 
+#[test]
+fn renderer_dim_respects_color_level() {
+    assert_eq!(Renderer::detect("none").dim(), "");
+    assert_eq!(Renderer::detect("16").dim(), "\x1b[2m");
+}
+
+#[test]
+fn color_level_16m_is_truecolor_alias() {
+    assert_eq!(Renderer::detect("16m").color_level, ColorLevel::TrueColor);
+}
+
+#[test]
+fn colorblind_theme_recolors_critical_context() {
+    let mut config: Config =
+        toml::from_str("lines = [[{ type = \"context-percentage\" }]]").unwrap();
+    let data = SessionDataBuilder::new().with_context_pct(92.0).build();
+    let registry = WidgetRegistry::new();
+    let renderer = Renderer::detect("truecolor");
+
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].contains("\x1b[31m92"), "{:?}", lines[0]);
+
+    // colorblind's context_critical is vermillion #d55e00, never red
+    config.theme = "colorblind".into();
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].contains("\x1b[38;2;213;94;0m92"), "{:?}", lines[0]);
+    assert!(!lines[0].contains("\x1b[31m"), "{:?}", lines[0]);
+}
+
+#[test]
+fn palette_alias_resolves_before_named_colors() {
+    let mut config: Config = toml::from_str(
+        r##"
+        lines = [[{ type = "model", color = "brand" }]]
+
+        [palette]
+        brand = "#ff6600"
+        "##,
+    )
+    .unwrap();
+    let data = SessionData::sample();
+    let registry = WidgetRegistry::new();
+    let renderer = Renderer::detect("truecolor").with_palette(config.palette.clone());
+
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].contains("\x1b[38;2;255;102;0m"), "{:?}", lines[0]);
+
+    config.lines[0][0].color = Some("unknown".into());
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].contains("\x1b[37mOpus"), "{:?}", lines[0]);
+}
+
+#[test]
+fn priority_sort_reorders_line_before_assembly() {
+    use claude_status::config::{LineOptions, LineWidgetConfig};
+
+    let widget = LineWidgetConfig::new;
+
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    config.lines = vec![vec![widget("version"), widget("model")]];
+    let renderer = Renderer::detect("none");
+    let registry = WidgetRegistry::new();
+
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].find("1.0.30") < lines[0].find("Opus"));
+
+    config.line_options = vec![LineOptions {
+        sort: "priority".into(),
+        ..LineOptions::default()
+    }];
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].find("Opus") < lines[0].find("1.0.30"));
+
+    // A configured priority replaces the widget's own.
+    config.lines[0][0].priority = Some(255);
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].find("1.0.30") < lines[0].find("Opus"));
+
+    // On a narrow terminal the lowest priorities are cut first.
+    config.flex_mode = "full".into();
+    config.lines[0].push(widget("session-cost"));
+    config.lines[0][2].priority = Some(254);
+    config.lines[0][1].priority = Some(0);
+    let lines = LayoutEngine::new(&config, &renderer)
+        .with_terminal_width(20)
+        .render(&data, &config, &registry);
+    assert!(lines[0].contains("1.0.30") && lines[0].contains("$0.42"));
+    assert!(!lines[0].contains("Opus"), "{lines:?}");
+}
+
+#[test]
+fn separator_uses_theme_color_unless_color_is_off() {
+    let data = SessionData::sample();
+    let config = Config::default();
+    let registry = WidgetRegistry::new();
+
+    let renderer = Renderer::detect("16");
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].contains("\x1b[90m | \x1b[0m"));
+
+    let renderer = Renderer::detect("none");
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].contains(" | "));
+    assert!(!lines[0].contains('\x1b'));
+}
+
+#[test]
+fn separator_widget_is_skipped_in_powerline_mode() {
+    use claude_status::config::LineWidgetConfig;
+
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    let mut separator = LineWidgetConfig::new("separator");
+    separator.metadata.insert("char".into(), "¦".into());
+    config.lines[0].insert(1, separator);
+    let registry = WidgetRegistry::new();
+    let renderer = Renderer::detect("none");
+
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].contains('¦'));
+
+    config.powerline.enabled = true;
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(!lines[0].contains('¦'));
+    assert!(lines[0].contains("Opus"));
+}
+
+#[test]
+fn group_divider_is_skipped_in_powerline_mode() {
+    use claude_status::config::LineWidgetConfig;
+
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    let mut divider = LineWidgetConfig::new("group-divider");
+    divider.metadata.insert("glyph".into(), "»".into());
+    config.lines[0].insert(1, divider);
+    let registry = WidgetRegistry::new();
+    let renderer = Renderer::detect("none");
+
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].contains(" » "), "{:?}", lines[0]);
+
+    config.powerline.enabled = true;
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(!lines[0].contains('»'));
+    assert!(lines[0].contains("Opus"));
+}
+
+#[test]
+fn right_pinned_widgets_stack_against_the_right_edge() {
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    config.lines.truncate(1);
+    config.lines[0][0].pin = Some("right".into());
+    config.lines[0][2].pin = Some("right".into());
+    config.flex_mode = "full".into();
+    let registry = WidgetRegistry::new();
+    let renderer = Renderer::detect("none");
+
+    let lines = LayoutEngine::new(&config, &renderer)
+        .with_terminal_width(80)
+        .render(&data, &config, &registry);
+    let line = &lines[0];
+    assert_eq!(line.chars().count(), 80, "{line:?}");
+    // Unpinned widgets stay left; the first pinned one ends the line.
+    let context = line.find('%').unwrap();
+    let cost = line.find('$').unwrap();
+    let model = line.find("Opus").unwrap();
+    assert!(context < cost && cost < model, "{line:?}");
+    assert!(line.trim_end().ends_with("Opus"), "{line:?}");
+    assert!(line[context..cost].contains("          "), "{line:?}");
+}
+
+#[test]
+fn placeholder_keeps_a_line_whose_widgets_are_all_hidden() {
+    use claude_status::config::LineOptions;
+
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    let mut hidden = config.lines[0][0].clone();
+    hidden
+        .metadata
+        .insert("only_for_models".into(), "haiku".into());
+    config.lines = vec![vec![hidden], config.lines[0].clone()];
+    let registry = WidgetRegistry::new();
+    let renderer = Renderer::detect("none");
+
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert_eq!(lines.len(), 1);
+
+    config.placeholder = Some("-".into());
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], "-");
+    assert!(lines[1].contains("Opus"));
+
+    // A line's own placeholder wins over the top-level one.
+    config.line_options = vec![LineOptions {
+        placeholder: Some(" ".into()),
+        ..LineOptions::default()
+    }];
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert_eq!(lines[0], " ");
+}
+
+#[test]
+fn weighted_flex_separators_split_the_leftover_width() {
+    use claude_status::config::LineWidgetConfig;
+
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    let flex = |weight: &str| {
+        let mut flex = LineWidgetConfig::new("flex-separator");
+        flex.metadata.insert("weight".into(), weight.into());
+        flex
+    };
+    let line = vec![
+        config.lines[0][0].clone(),
+        flex("2"),
+        config.lines[0][2].clone(),
+        flex("1"),
+        config.lines[0][3].clone(),
+    ];
+    config.lines = vec![line];
+    config.flex_mode = "full".into();
+    let registry = WidgetRegistry::new();
+    let renderer = Renderer::detect("none");
+
+    for width in [80, 81, 82] {
+        let lines = LayoutEngine::new(&config, &renderer)
+            .with_terminal_width(width)
+            .render(&data, &config, &registry);
+        let line = &lines[0];
+        assert_eq!(line.chars().count(), width, "{line:?}");
+        // Runs of spaces between the three widgets, padding included.
+        let gaps: Vec<usize> = line
+            .trim()
+            .split(|c: char| c != ' ')
+            .filter(|run| run.len() > 2)
+            .map(|run| run.len() - 2)
+            .collect();
+        assert_eq!(gaps.len(), 2, "{line:?}");
+        let leftover = gaps[0] + gaps[1];
+        assert_eq!(gaps[1], leftover / 3, "{line:?}");
+        assert_eq!(gaps[0], leftover - leftover / 3, "{line:?}");
+    }
+
+    // An absurd weight is capped rather than overflowing the split.
+    config.lines[0][1]
+        .metadata
+        .insert("weight".into(), usize::MAX.to_string());
+    let lines = LayoutEngine::new(&config, &renderer)
+        .with_terminal_width(80)
+        .render(&data, &config, &registry);
+    assert_eq!(lines[0].chars().count(), 80, "{:?}", lines[0]);
+}
+
+#[test]
+fn attention_mode_wraps_widget_until_reset() {
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    config.lines = vec![vec![config.lines[0][0].clone()]];
+    config.lines[0][0].attention = Some("blink".into());
+    let registry = WidgetRegistry::new();
+
+    let renderer = Renderer::detect("16");
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].contains("\x1b[5mOpus\x1b[0m"));
+
+    config.lines[0][0].attention = Some("reverse".into());
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].contains("\x1b[7mOpus\x1b[0m"));
+
+    let renderer = Renderer::detect("none");
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(!lines[0].contains('\x1b'));
+}
+
 #[test]
 fn widget_registry_has_all_expected_widgets() {
     let registry = WidgetRegistry::new();
@@ -259,12 +555,18 @@ fn widget_registry_has_all_expected_widgets() {
         padding: None,
         merge_next: false,
         metadata: std::collections::HashMap::new(),
+        settings: Default::default(),
     };
 
     let expected = [
         "model",
         "context-percentage",
+        "context-remaining",
         "context-length",
+        "context-trend",
+        "context-gauge",
+        "headroom",
+        "window-size",
         "tokens-input",
         "tokens-output",
         "tokens-cached",
@@ -275,20 +577,27 @@ fn widget_registry_has_all_expected_widgets() {
         "git-branch",
         "git-status",
         "git-worktree",
+        "git-age",
+        "project",
         "cwd",
         "lines-changed",
         "version",
         "session-id",
         "vim-mode",
         "agent-name",
+        "agent-count",
         "output-style",
         "exceeds-tokens",
+        "compaction-warning",
         "api-duration",
         "custom-command",
         "custom-text",
         "separator",
+        "group-divider",
         "flex-separator",
         "terminal-width",
+        "transcript-size",
+        "idle-time",
     ];
 
     for name in &expected {
@@ -301,9 +610,9 @@ fn widget_registry_has_all_expected_widgets() {
 }
 
 #[test]
-fn theme_list_has_eleven_themes() {
+fn theme_list_has_twelve_themes() {
     let themes = claude_status::themes::Theme::list();
-    assert_eq!(themes.len(), 11);
+    assert_eq!(themes.len(), 12);
     assert!(themes.contains(&"default"));
     assert!(themes.contains(&"solarized"));
     assert!(themes.contains(&"nord"));
@@ -315,6 +624,20 @@ fn theme_list_has_eleven_themes() {
     assert!(themes.contains(&"one-dark"));
     assert!(themes.contains(&"tokyo-night"));
     assert!(themes.contains(&"catppuccin"));
+    assert!(themes.contains(&"colorblind"));
+}
+
+#[test]
+fn colorblind_theme_keeps_states_apart() {
+    let theme = claude_status::themes::Theme::get("colorblind");
+    assert_eq!(theme.name, "colorblind");
+    let states = ["context_ok", "context_warn", "context_critical"];
+    for (i, a) in states.iter().enumerate() {
+        for b in &states[i + 1..] {
+            assert_ne!(theme.color(a), theme.color(b), "{a} vs {b}");
+        }
+    }
+    assert_ne!(theme.color("git_clean"), theme.color("git_dirty"));
 }
 
 #[test]
@@ -354,3 +677,730 @@ fn all_themes_have_required_color_roles() {
         }
     }
 }
+
+#[test]
+fn render_profiled_times_every_widget() {
+    let data: SessionData = serde_json::from_str(r#"{"model":{"display_name":"Opus"}}"#).unwrap();
+    let config = Config::default();
+    let renderer = Renderer::detect("none");
+    let registry = WidgetRegistry::new();
+    let engine = LayoutEngine::new(&config, &renderer);
+
+    let (lines, timings) = engine.render_profiled(&data, &config, &registry);
+    assert_eq!(lines, engine.render(&data, &config, &registry));
+    let expected: Vec<&str> = config
+        .lines
+        .iter()
+        .flatten()
+        .map(|wc| wc.widget_type.as_str())
+        .collect();
+    let timed: Vec<&str> = timings.iter().map(|t| t.widget_type.as_str()).collect();
+    assert_eq!(timed, expected);
+    assert!(timings[0].visible, "model should be visible");
+    assert!(timings.iter().any(|t| !t.visible), "cost has no data here");
+}
+
+#[test]
+fn blocking_widget_past_deadline_is_dropped() {
+    use claude_status::config::LineWidgetConfig;
+    use claude_status::widgets::{Widget, WidgetConfig, WidgetOutput};
+    use std::time::{Duration, Instant};
+
+    struct HungWidget;
+
+    impl Widget for HungWidget {
+        fn name(&self) -> &str {
+            "hung"
+        }
+
+        fn is_blocking(&self) -> bool {
+            true
+        }
+
+        fn render(&self, _data: &SessionData, _config: &WidgetConfig) -> WidgetOutput {
+            std::thread::sleep(Duration::from_secs(5));
+            WidgetOutput {
+                text: "too-late".into(),
+                display_width: 8,
+                priority: 50,
+                visible: true,
+                color_hint: None,
+            }
+        }
+    }
+
+    let widget = LineWidgetConfig::new;
+
+    let mut config = Config::default();
+    config.render_timeout_ms = 100;
+    config.lines = vec![vec![widget("model"), widget("hung")]];
+
+    let data: SessionData = serde_json::from_str(r#"{"model":{"display_name":"Opus"}}"#).unwrap();
+    let renderer = Renderer::detect("none");
+    let mut registry = WidgetRegistry::new();
+    registry.register(Box::new(HungWidget));
+    let engine = LayoutEngine::new(&config, &renderer);
+
+    let start = Instant::now();
+    let lines = engine.render(&data, &config, &registry);
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert!(lines[0].contains("Opus"));
+    assert!(!lines[0].contains("too-late"));
+
+    // `--format kv` honors the same deadline.
+    let start = Instant::now();
+    let values = engine.values(&data, &config, &registry);
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert_eq!(values, [("model".to_string(), "Opus".to_string())]);
+}
+
+#[test]
+fn cached_widget_reuses_output_within_session() {
+    use claude_status::widgets::{RenderCache, Widget, WidgetConfig, WidgetOutput};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    struct CountingWidget(Arc<AtomicUsize>);
+
+    impl Widget for CountingWidget {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn cache_ttl(&self) -> Option<Duration> {
+            Some(Duration::from_secs(60))
+        }
+
+        fn render(&self, _data: &SessionData, _config: &WidgetConfig) -> WidgetOutput {
+            let n = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            WidgetOutput {
+                text: format!("run {n}"),
+                display_width: 5,
+                priority: 50,
+                visible: true,
+                color_hint: None,
+            }
+        }
+    }
+
+    let dir = std::env::temp_dir().join(format!("claude-status-cache-{}", std::process::id()));
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut registry = WidgetRegistry::new().with_cache(RenderCache::at(&dir));
+    registry.register(Box::new(CountingWidget(Arc::clone(&calls))));
+
+    let config = WidgetConfig {
+        widget_type: "counting".into(),
+        ..Default::default()
+    };
+    let session = |id: &str| -> SessionData {
+        serde_json::from_str(&format!(r#"{{"session_id":"{id}","cwd":"/tmp"}}"#)).unwrap()
+    };
+
+    let first = registry.render("counting", &session("a"), &config).unwrap();
+    let second = registry.render("counting", &session("a"), &config).unwrap();
+    assert_eq!(first.text, "run 1");
+    assert_eq!(second.text, "run 1");
+
+    // A different session renders fresh.
+    let other = registry.render("counting", &session("b"), &config).unwrap();
+    assert_eq!(other.text, "run 2");
+
+    // Without a session_id, entries are kept per working directory.
+    let anon = |cwd: &str| -> SessionData {
+        serde_json::from_str(&format!(r#"{{"cwd":"{cwd}"}}"#)).unwrap()
+    };
+    assert_eq!(
+        registry
+            .render("counting", &anon("/tmp"), &config)
+            .unwrap()
+            .text,
+        "run 3"
+    );
+    assert_eq!(
+        registry
+            .render("counting", &anon("/tmp"), &config)
+            .unwrap()
+            .text,
+        "run 3"
+    );
+    assert_eq!(
+        registry
+            .render("counting", &anon("/"), &config)
+            .unwrap()
+            .text,
+        "run 4"
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn overflowing_line_compacts_widgets_before_dropping_them() {
+    use claude_status::config::LineWidgetConfig;
+
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    config.flex_mode = "full".into();
+    let widget = LineWidgetConfig::new;
+    let mut cwd = widget("cwd");
+    cwd.metadata.insert("full".into(), "true".into());
+    config.lines = vec![vec![widget("model"), widget("session-duration"), cwd]];
+    let renderer = Renderer::detect("none");
+    let registry = WidgetRegistry::new();
+    let render = |width| {
+        LayoutEngine::new(&config, &renderer)
+            .with_terminal_width(width)
+            .render(&data, &config, &registry)
+    };
+
+    let lines = render(120);
+    assert!(lines[0].contains("5m 45s") && lines[0].contains("/home/user/project"));
+
+    // The lowest priority (session-duration) gives way first
+    let lines = render(37);
+    assert!(
+        lines[0].contains(" 5m ") && !lines[0].contains("45s"),
+        "{lines:?}"
+    );
+    assert!(lines[0].contains("/home/user/project"), "{lines:?}");
+
+    let lines = render(30);
+    assert!(
+        lines[0].contains("Opus") && lines[0].contains(" 5m "),
+        "{lines:?}"
+    );
+    assert!(
+        lines[0].contains("project") && !lines[0].contains("/home"),
+        "{lines:?}"
+    );
+
+    let explained = LayoutEngine::new(&config, &renderer)
+        .with_terminal_width(30)
+        .explain(&data, &config, &registry);
+    let reasons: Vec<&str> = explained.iter().map(|e| e.reason.as_str()).collect();
+    assert_eq!(reasons, ["shown", "shown: compact", "shown: compact"]);
+}
+
+#[test]
+fn zero_terminal_width_falls_back_instead_of_dropping_widgets() {
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    let renderer = Renderer::detect("none");
+    let registry = WidgetRegistry::new();
+
+    for mode in ["full", "full-minus-40", "compact"] {
+        config.flex_mode = mode.into();
+        let lines = LayoutEngine::new(&config, &renderer)
+            .with_terminal_width(0)
+            .render(&data, &config, &registry);
+        assert!(lines[0].contains("Opus"), "{mode}: {lines:?}");
+    }
+}
+
+#[test]
+fn narrow_terminal_keeps_a_minimum_width() {
+    use claude_status::config::LineWidgetConfig;
+
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    config.lines = vec![vec![LineWidgetConfig::new("model")]];
+    let renderer = Renderer::detect("none");
+    let registry = WidgetRegistry::new();
+
+    // 30 columns minus 40 would leave nothing; the floor keeps the model.
+    let lines = LayoutEngine::new(&config, &renderer)
+        .with_terminal_width(30)
+        .render(&data, &config, &registry);
+    assert!(lines[0].contains("Opus"), "{lines:?}");
+}
+
+#[test]
+fn trim_trailing_strips_unstyled_line_end() {
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    config.trim_trailing = true;
+    let renderer = Renderer::detect("256");
+    let registry = WidgetRegistry::new();
+
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].ends_with("5m45s\x1b[0m"), "{:?}", lines[0]);
+
+    let renderer = Renderer::detect("none");
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].ends_with("5m45s"), "{:?}", lines[0]);
+}
+
+#[test]
+fn trim_trailing_keeps_styled_padding_and_alignment() {
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    config.trim_trailing = true;
+    config.powerline.enabled = true;
+    let renderer = Renderer::detect("256");
+    let registry = WidgetRegistry::new();
+
+    // The last segment's padding is on its background color.
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].contains(" 5m45s \x1b[0m"), "{:?}", lines[0]);
+
+    config.powerline.auto_align = true;
+    config.lines.push(config.lines[0][..1].to_vec());
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[1].ends_with(' '), "alignment padding was trimmed");
+}
+
+#[test]
+fn stale_transcript_marks_output() {
+    let path = std::env::temp_dir().join(format!("cs-stale-{}.jsonl", std::process::id()));
+    std::fs::write(&path, "{}\n").unwrap();
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(600))
+        .unwrap();
+
+    let mut data = SessionData::sample();
+    data.transcript_path = Some(path.to_string_lossy().into_owned());
+    let mut config = Config::default();
+    let renderer = Renderer::detect("256");
+    let registry = WidgetRegistry::new();
+
+    let fresh = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    config.stale_after_secs = 300;
+    let marked = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    config.stale_style = "dim".into();
+    let dimmed = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    config.stale_after_secs = 3600;
+    let recent = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    let _ = std::fs::remove_file(&path);
+
+    assert!(!fresh[0].contains("stale"));
+    assert!(
+        marked[0].ends_with(" \x1b[2mstale\x1b[0m"),
+        "{:?}",
+        marked[0]
+    );
+    assert!(dimmed[0].starts_with("\x1b[2m"), "{:?}", dimmed[0]);
+    assert!(dimmed[0].contains("\x1b[0m\x1b[2m"));
+    assert_eq!(recent, fresh);
+}
+
+#[test]
+fn custom_widget_registers_and_renders_by_name() {
+    use claude_status::config::LineWidgetConfig;
+    use claude_status::widgets::{Widget, WidgetConfig, WidgetOutput};
+    use std::collections::HashMap;
+
+    struct Ticket;
+
+    impl Widget for Ticket {
+        fn name(&self) -> &str {
+            "ticket"
+        }
+
+        fn render(&self, _data: &SessionData, config: &WidgetConfig) -> WidgetOutput {
+            let text = config.metadata.get("id").cloned().unwrap_or_default();
+            WidgetOutput {
+                display_width: text.len(),
+                text,
+                priority: 50,
+                visible: true,
+                color_hint: None,
+            }
+        }
+    }
+
+    let mut config = Config::default();
+    config.lines = vec![vec![LineWidgetConfig {
+        metadata: HashMap::from([("id".into(), "ENG-42".into())]),
+        ..LineWidgetConfig::new("ticket")
+    }]];
+    let data = SessionData::sample();
+    let renderer = Renderer::detect("none");
+
+    let mut registry = WidgetRegistry::new();
+    let before = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(before.iter().all(|l| !l.contains("ENG-42")));
+
+    registry.register(Box::new(Ticket));
+    assert!(registry.widget_types().contains(&"ticket"));
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert_eq!(lines, vec![" ENG-42 "]);
+}
+
+#[test]
+fn render_segments_carry_style_before_serialization() {
+    use claude_status::layout::{RenderedSegment, to_ansi};
+
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    let renderer = Renderer::detect("256");
+    let registry = WidgetRegistry::new();
+
+    let engine = LayoutEngine::new(&config, &renderer);
+    let segments = engine.render_segments(&data, &config, &registry);
+    let lines = engine.render(&data, &config, &registry);
+    assert_eq!(segments.len(), lines.len());
+    assert_eq!(to_ansi(&segments[0], &renderer), lines[0]);
+
+    let model = segments[0].iter().find(|s| s.text == "Opus").unwrap();
+    assert!(model.fg.is_some());
+    assert_eq!(model.bg, None);
+    assert!(segments[0].contains(&RenderedSegment::plain(" ")));
+
+    config.powerline.enabled = true;
+    let engine = LayoutEngine::new(&config, &renderer);
+    let segments = engine.render_segments(&data, &config, &registry);
+    let model = segments[0].iter().find(|s| s.text == " Opus ").unwrap();
+    assert_eq!(model.bg, Some(ColorSpec::Named("black".into())));
+}
+
+#[test]
+fn disabled_widgets_are_skipped_everywhere() {
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    config.lines.push(config.lines[0].clone());
+    let renderer = Renderer::detect("none");
+    let registry = WidgetRegistry::new();
+
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines.iter().all(|l| l.contains("Opus")));
+
+    config.disabled_widgets = vec!["model".into()];
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert_eq!(lines.len(), 2);
+    assert!(lines.iter().all(|l| !l.contains("Opus")), "{lines:?}");
+    assert!(lines.iter().all(|l| l.contains("$0.42")));
+}
+
+#[test]
+fn plan_limits_parse_and_ignore_non_positive_values() {
+    let data: SessionData =
+        serde_json::from_str(r#"{ "limits": { "weekly_usd": 150.0, "monthly_usd": 0 } }"#).unwrap();
+    assert_eq!(data.weekly_limit(), Some(150.0));
+    assert_eq!(data.limits.as_ref().unwrap().monthly(), None);
+    assert_eq!(SessionData::default().weekly_limit(), None);
+}
+
+#[test]
+fn contrast_ratio_follows_wcag() {
+    let white = Renderer::parse_color("white");
+    assert!(white.contrast(&Renderer::parse_color("yellow")) < 3.0);
+    assert!(white.contrast(&Renderer::parse_color("blue")) > 3.0);
+    let black = ColorSpec::Rgb(0, 0, 0);
+    assert!((black.contrast(&ColorSpec::Rgb(255, 255, 255)) - 21.0).abs() < 1e-9);
+    assert_eq!(ColorSpec::Ansi256(196).to_rgb(), (255, 0, 0));
+    assert_eq!(ColorSpec::Ansi256(244).to_rgb(), (128, 128, 128));
+}
+
+#[test]
+fn auto_contrast_flips_white_on_yellow_to_black() {
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    config.powerline.enabled = true;
+    config.lines = vec![vec![config.lines[0][0].clone()]];
+    config.lines[0][0].color = Some("white".into());
+    config.lines[0][0].background_color = Some("yellow".into());
+    let registry = WidgetRegistry::new();
+    let model_fg = |config: &Config, level: &str| {
+        let renderer = Renderer::detect(level);
+        let lines = LayoutEngine::new(config, &renderer).render_segments(&data, config, &registry);
+        lines[0]
+            .iter()
+            .find(|s| s.text.contains("Opus"))
+            .and_then(|s| s.fg.clone())
+    };
+
+    assert_eq!(
+        model_fg(&config, "256"),
+        Some(ColorSpec::Named("white".into()))
+    );
+    config.powerline.auto_contrast = true;
+    assert_eq!(model_fg(&config, "256"), Some(ColorSpec::Rgb(0, 0, 0)));
+    assert_eq!(
+        model_fg(&config, "truecolor"),
+        Some(ColorSpec::Rgb(0, 0, 0))
+    );
+    // 16-color output can't know the terminal's palette, so it's left alone.
+    assert_eq!(
+        model_fg(&config, "16"),
+        Some(ColorSpec::Named("white".into()))
+    );
+
+    // Readable pairs are kept.
+    config.lines[0][0].background_color = Some("blue".into());
+    assert_eq!(
+        model_fg(&config, "256"),
+        Some(ColorSpec::Named("white".into()))
+    );
+}
+
+#[test]
+fn line_background_wraps_separators_and_padding() {
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    config.line_background = Some("blue".into());
+    config.lines[0][1].background_color = Some("red".into());
+    let renderer = Renderer::detect("16");
+    let registry = WidgetRegistry::new();
+    let engine = LayoutEngine::new(&config, &renderer);
+
+    let segments = engine.render_segments(&data, &config, &registry);
+    let blue = Some(ColorSpec::Named("blue".into()));
+    let separator = segments[0].iter().find(|s| s.text == " | ").unwrap();
+    assert_eq!(separator.bg, blue);
+    assert!(
+        segments[0]
+            .iter()
+            .filter(|s| s.text == " ")
+            .all(|s| s.bg == blue)
+    );
+    // A widget's own background wins.
+    assert!(
+        segments[0]
+            .iter()
+            .any(|s| s.bg == Some(ColorSpec::Named("red".into())))
+    );
+
+    let lines = engine.render(&data, &config, &registry);
+    assert!(
+        lines[0].contains("\x1b[44m\x1b[90m | \x1b[0m"),
+        "{:?}",
+        lines[0]
+    );
+    assert!(lines[0].starts_with("\x1b[44m \x1b[0m"), "{:?}", lines[0]);
+}
+
+#[test]
+fn explain_gives_a_reason_for_every_widget() {
+    use claude_status::config::LineWidgetConfig;
+
+    let widget = LineWidgetConfig::new;
+
+    let mut config = Config::default();
+    config.flex_mode = "full".into();
+    config.lines = vec![vec![
+        widget("model"),
+        widget("session-cost"),
+        widget("no-such-widget"),
+        widget("version"),
+        widget("custom-text"),
+    ]];
+    config.lines[0][4]
+        .metadata
+        .insert("text".into(), "far too long for the terminal".into());
+    config.disabled_widgets = vec!["version".into()];
+    let renderer = Renderer::detect("none");
+    let registry = WidgetRegistry::new();
+    let data: SessionData = serde_json::from_str(r#"{"model":{"display_name":"Opus"}}"#).unwrap();
+
+    let explained = LayoutEngine::new(&config, &renderer)
+        .with_terminal_width(20)
+        .explain(&data, &config, &registry);
+    let reasons: Vec<(&str, &str)> = explained
+        .iter()
+        .map(|e| (e.widget_type.as_str(), e.reason.as_str()))
+        .collect();
+    assert_eq!(
+        reasons,
+        [
+            ("model", "shown"),
+            ("session-cost", "hidden: no cost data"),
+            ("no-such-widget", "unknown widget type"),
+            ("version", "disabled"),
+            ("custom-text", "dropped: overflow"),
+        ]
+    );
+    assert!(explained[0].visible && !explained[4].visible);
+    assert!(explained.iter().all(|e| e.line == 1));
+}
+
+#[test]
+fn ascii_icon_set_draws_powerline_without_glyphs() {
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    config.powerline.enabled = true;
+    config.powerline.start_cap = Some("\u{E0B6}".into());
+    config.icon_set = "ascii".into();
+    let renderer = Renderer::detect("none");
+    let registry = WidgetRegistry::new();
+
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].starts_with("< Opus >"), "{:?}", lines[0]);
+    assert!(
+        !lines[0]
+            .chars()
+            .any(|c| ('\u{E000}'..='\u{F8FF}').contains(&c)),
+        "{:?}",
+        lines[0]
+    );
+}
+
+#[test]
+fn powerline_uses_configured_reverse_separator_after_flex() {
+    use claude_status::config::LineWidgetConfig;
+
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    config.powerline.enabled = true;
+    config.lines[0].insert(1, LineWidgetConfig::new("flex-separator"));
+    let renderer = Renderer::detect("none");
+    let registry = WidgetRegistry::new();
+
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].contains('\u{E0B2}'), "{:?}", lines[0]);
+
+    config.powerline.separator_reverse = "\u{E0B6}".into();
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert!(lines[0].contains('\u{E0B6}'), "{:?}", lines[0]);
+    assert!(!lines[0].contains('\u{E0B2}'), "{:?}", lines[0]);
+}
+
+#[test]
+fn raw_mode_line_joins_raw_values_without_ansi() {
+    use claude_status::config::{LineOptions, LineWidgetConfig};
+
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    let line = [
+        "model",
+        "separator",
+        "session-cost",
+        "flex-separator",
+        "context-percentage",
+    ]
+    .map(LineWidgetConfig::new);
+    config.lines = vec![line.to_vec()];
+    config.powerline.enabled = true;
+    config.line_options = vec![LineOptions {
+        raw_mode: true,
+        ..LineOptions::default()
+    }];
+    let renderer = Renderer::detect("truecolor");
+    let registry = WidgetRegistry::new();
+
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert_eq!(lines[0], "claude-opus-4-6\t$0.42\t65%");
+
+    config.line_options[0].raw_delimiter = ",".into();
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert_eq!(lines[0], "claude-opus-4-6,$0.42,65%");
+}
+
+#[test]
+fn max_lines_caps_output_and_marks_the_cut() {
+    let data = SessionData::sample();
+    let mut config = Config::default();
+    let model = config.lines[0][0].clone();
+    config.lines = vec![vec![model.clone()]; 4];
+    config.lines[1].clear();
+    config.max_lines = Some(2);
+    let renderer = Renderer::detect("none");
+    let registry = WidgetRegistry::new();
+
+    // The empty line doesn't count toward the cap.
+    let lines = LayoutEngine::new(&config, &renderer).render(&data, &config, &registry);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1].trim_end(), " Opus");
+
+    config.max_lines_indicator = Some("…".into());
+    let engine = LayoutEngine::new(&config, &renderer);
+    let lines = engine.render(&data, &config, &registry);
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].ends_with(" …"), "{:?}", lines[1]);
+    assert_eq!(lines[0].trim_end(), " Opus");
+
+    let explained = engine.explain(&data, &config, &registry);
+    let last = explained.last().unwrap();
+    assert_eq!((last.line, last.reason.as_str()), (4, "dropped: max_lines"));
+}
+
+#[test]
+fn min_interval_throttles_blocking_widgets() {
+    use claude_status::widgets::{RenderCache, Widget, WidgetConfig, WidgetOutput};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    struct SlowWidget(Arc<AtomicUsize>);
+
+    impl Widget for SlowWidget {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn is_blocking(&self) -> bool {
+            true
+        }
+
+        fn render(&self, _data: &SessionData, _config: &WidgetConfig) -> WidgetOutput {
+            let n = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            WidgetOutput {
+                text: format!("run {n}"),
+                display_width: 5,
+                priority: 50,
+                visible: true,
+                color_hint: None,
+            }
+        }
+    }
+
+    let dir = std::env::temp_dir().join(format!("claude-status-throttle-{}", std::process::id()));
+    let calls = Arc::new(AtomicUsize::new(0));
+    let config = WidgetConfig {
+        widget_type: "slow".into(),
+        ..Default::default()
+    };
+    let data: SessionData = serde_json::from_str(r#"{"session_id":"a","cwd":"/tmp"}"#).unwrap();
+
+    // No interval and no cache_ttl: every render recomputes.
+    let mut registry = WidgetRegistry::new().with_cache(RenderCache::at(&dir));
+    registry.register(Box::new(SlowWidget(Arc::clone(&calls))));
+    registry.render("slow", &data, &config).unwrap();
+    registry.render("slow", &data, &config).unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    let mut registry = WidgetRegistry::new()
+        .with_cache(RenderCache::at(&dir))
+        .with_min_interval(Duration::from_secs(60));
+    registry.register(Box::new(SlowWidget(Arc::clone(&calls))));
+    let first = registry.render("slow", &data, &config).unwrap();
+    let rx = registry
+        .spawn_render("slow", Arc::new(data.clone()), config.clone())
+        .unwrap();
+    assert_eq!(rx.recv().unwrap().text, first.text);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn model_filters_hide_widgets_for_other_models() {
+    let mut config = Config::default();
+    config.lines[0][2]
+        .metadata
+        .insert("only_for_models".into(), "Opus".into());
+    config.lines[0][3]
+        .metadata
+        .insert("hide_for_models".into(), "haiku, opus-4".into());
+    let renderer = Renderer::detect("none");
+    let registry = WidgetRegistry::new();
+    let engine = LayoutEngine::new(&config, &renderer);
+    let session = |id: &str| -> SessionData {
+        serde_json::from_str(&format!(
+            r#"{{"model":{{"id":"{id}"}},"cost":{{"total_cost_usd":0.42,"total_duration_ms":60000}}}}"#
+        ))
+        .unwrap()
+    };
+
+    let opus = engine.render(&session("claude-opus-4-1"), &config, &registry);
+    assert!(opus[0].contains("$0.42"), "{opus:?}");
+    assert!(!opus[0].contains("1m0s"), "{opus:?}");
+
+    let sonnet = engine.render(&session("claude-sonnet-4-5"), &config, &registry);
+    assert!(!sonnet[0].contains("$0.42"), "{sonnet:?}");
+    assert!(sonnet[0].contains("1m0s"), "{sonnet:?}");
+
+    let explained = engine.explain(&session("claude-sonnet-4-5"), &config, &registry);
+    assert_eq!(explained[2].reason, "hidden: not for this model");
+}
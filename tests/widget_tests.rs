@@ -1,5 +1,6 @@
+use claude_status::icons::IconSet;
 use claude_status::widgets::data::*;
-use claude_status::widgets::{SessionData, WidgetConfig, WidgetRegistry};
+use claude_status::widgets::{SessionData, WidgetConfig, WidgetRegistry, format};
 use std::collections::HashMap;
 
 fn mock_session() -> SessionData {
@@ -42,6 +43,7 @@ fn mock_session() -> SessionData {
         exceeds_200k_tokens: Some(false),
         vim: None,
         agent: None,
+        limits: None,
     }
 }
 
@@ -56,6 +58,7 @@ fn default_config() -> WidgetConfig {
         padding: None,
         merge_next: false,
         metadata: HashMap::new(),
+        settings: Default::default(),
     }
 }
 
@@ -106,7 +109,7 @@ fn context_percentage_renders_percentage() {
         .render("context-percentage", &data, &config)
         .unwrap();
     assert!(output.visible);
-    assert_eq!(output.text, "42%");
+    assert_eq!(output.text, "43%");
 }
 
 #[test]
@@ -120,7 +123,7 @@ fn context_percentage_bar_mode() {
         .unwrap();
     assert!(output.visible);
     // 42.5% -> round(4.25) = 4 filled, 6 empty
-    assert!(output.text.contains("42%"));
+    assert!(output.text.contains("43%"));
     assert!(output.text.contains('▓'));
     assert!(output.text.contains('░'));
 }
@@ -135,8 +138,8 @@ fn context_percentage_inverse_mode() {
         .render("context-percentage", &data, &config)
         .unwrap();
     assert!(output.visible);
-    // 100 - 42.5 = 57.5, truncated to 57
-    assert_eq!(output.text, "57%");
+    // 100 - 42.5 = 57.5, rounded to 58
+    assert_eq!(output.text, "58%");
 }
 
 #[test]
@@ -150,6 +153,59 @@ fn context_percentage_invisible_without_data() {
     assert!(!output.visible);
 }
 
+// ─── ContextRemainingWidget ───────────────────────────────────
+
+#[test]
+fn context_remaining_renders_headroom() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let output = registry
+        .render("context-remaining", &data, &default_config())
+        .unwrap();
+    assert!(output.visible);
+    assert_eq!(output.text, "58% left");
+    assert_eq!(output.color_hint, Some("green".into()));
+}
+
+#[test]
+fn context_remaining_bar_shrinks_from_right() {
+    let registry = WidgetRegistry::new();
+    let mut data = mock_session();
+    let cw = data.context_window.as_mut().unwrap();
+    cw.used_percentage = Some(90.0);
+    cw.remaining_percentage = Some(10.0);
+    let mut config = default_config();
+    config.metadata.insert("bar".into(), "true".into());
+    let output = registry
+        .render("context-remaining", &data, &config)
+        .unwrap();
+    assert_eq!(output.text, "▓░░░░░░░░░ 10%");
+    assert_eq!(output.display_width, 14);
+    assert_eq!(output.color_hint, Some("red".into()));
+}
+
+#[test]
+fn context_remaining_tokens_mode() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let mut config = default_config();
+    config.metadata.insert("tokens".into(), "true".into());
+    let output = registry
+        .render("context-remaining", &data, &config)
+        .unwrap();
+    // 200000 - (8500 + 5000 + 2000) = 184500
+    assert_eq!(output.text, "184K left");
+}
+
+#[test]
+fn context_remaining_invisible_without_data() {
+    let registry = WidgetRegistry::new();
+    let output = registry
+        .render("context-remaining", &empty_session(), &default_config())
+        .unwrap();
+    assert!(!output.visible);
+}
+
 // ─── ContextLengthWidget ──────────────────────────────────────
 
 #[test]
@@ -174,6 +230,17 @@ fn context_length_raw_value() {
     assert_eq!(output.text, "15500");
 }
 
+#[test]
+fn context_length_raw_value_grouped() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let mut config = default_config();
+    config.raw_value = true;
+    config.settings.group_separator = Some(",".into());
+    let output = registry.render("context-length", &data, &config).unwrap();
+    assert_eq!(output.text, "15,500");
+}
+
 #[test]
 fn context_length_invisible_without_data() {
     let registry = WidgetRegistry::new();
@@ -183,6 +250,74 @@ fn context_length_invisible_without_data() {
     assert!(!output.visible);
 }
 
+// ─── HeadroomWidget ───────────────────────────────────────────
+
+#[test]
+fn headroom_renders_tokens_left() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let output = registry
+        .render("headroom", &data, &default_config())
+        .unwrap();
+    assert!(output.visible);
+    // 200000 - 15234 = 184766
+    assert_eq!(output.text, "184K left");
+    assert_eq!(output.color_hint, None);
+}
+
+#[test]
+fn headroom_is_red_when_low() {
+    let registry = WidgetRegistry::new();
+    let mut data = mock_session();
+    data.context_window.as_mut().unwrap().total_input_tokens = Some(190_000);
+    let mut config = default_config();
+    config.raw_value = true;
+    let output = registry.render("headroom", &data, &config).unwrap();
+    assert_eq!(output.text, "10K");
+    assert_eq!(output.color_hint, Some("red".into()));
+}
+
+#[test]
+fn headroom_invisible_without_window_size() {
+    let registry = WidgetRegistry::new();
+    let mut data = mock_session();
+    data.context_window.as_mut().unwrap().context_window_size = None;
+    let output = registry
+        .render("headroom", &data, &default_config())
+        .unwrap();
+    assert!(!output.visible);
+}
+
+// ─── WindowSizeWidget ─────────────────────────────────────────
+
+#[test]
+fn window_size_renders_capacity() {
+    let registry = WidgetRegistry::new();
+    let mut data = mock_session();
+    data.context_window.as_mut().unwrap().context_window_size = Some(1_000_000);
+    let output = registry
+        .render("window-size", &data, &default_config())
+        .unwrap();
+    assert!(output.visible);
+    assert_eq!(output.text, "1.0M");
+
+    let mut config = default_config();
+    config.raw_value = true;
+    let output = registry.render("window-size", &data, &config).unwrap();
+    assert_eq!(output.text, "1000000");
+}
+
+#[test]
+fn window_size_invisible_when_unknown() {
+    let registry = WidgetRegistry::new();
+    let mut data = mock_session();
+    data.context_window.as_mut().unwrap().context_window_size = None;
+    let output = registry
+        .render("window-size", &data, &default_config())
+        .unwrap();
+    assert!(!output.visible);
+}
+
 // ─── TokenInputWidget ─────────────────────────────────────────
 
 #[test]
@@ -195,6 +330,16 @@ fn token_input_renders_formatted() {
     assert_eq!(output.text, "In: 8,500");
 }
 
+#[test]
+fn token_input_uses_configured_separator() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let mut config = default_config();
+    config.settings.group_separator = Some(".".into());
+    let output = registry.render("tokens-input", &data, &config).unwrap();
+    assert_eq!(output.text, "In: 8.500");
+}
+
 #[test]
 fn token_input_raw_value_renders_compact() {
     let registry = WidgetRegistry::new();
@@ -320,6 +465,31 @@ fn session_cost_with_burn_rate() {
     assert!(output.text.contains("/hr"));
 }
 
+#[test]
+fn session_cost_uses_cost_settings() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let mut config = default_config();
+    config.settings.cost.symbol = "€".into();
+    config.settings.cost.rate = 0.5;
+    config.settings.cost.decimals = 3;
+    let output = registry.render("session-cost", &data, &config).unwrap();
+    assert_eq!(output.text, "€0.042");
+    assert_eq!(output.display_width, 6);
+}
+
+#[test]
+fn session_cost_metadata_overrides_cost_settings() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let mut config = default_config();
+    config.settings.cost.symbol = "€".into();
+    config.metadata.insert("symbol".into(), "£".into());
+    config.metadata.insert("decimals".into(), "1".into());
+    let output = registry.render("session-cost", &data, &config).unwrap();
+    assert_eq!(output.text, "£0.1");
+}
+
 // ─── SessionDurationWidget ────────────────────────────────────
 
 #[test]
@@ -353,6 +523,17 @@ fn session_duration_invisible_without_data() {
     assert!(!output.visible);
 }
 
+#[test]
+fn session_duration_wallclock_falls_back_to_payload_without_history() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let mut config = default_config();
+    config.settings.history = false;
+    config.metadata.insert("source".into(), "wallclock".into());
+    let output = registry.render("session-duration", &data, &config).unwrap();
+    assert_eq!(output.text, "5m 45s");
+}
+
 // ─── BlockTimerWidget ─────────────────────────────────────────
 
 #[test]
@@ -434,14 +615,73 @@ fn cwd_full_mode() {
 }
 
 #[test]
-fn cwd_invisible_without_data() {
+fn cwd_truncates_middle_keeping_basename() {
     let registry = WidgetRegistry::new();
-    let mut data = empty_session();
-    data.workspace = None;
-    data.cwd = None;
+    let mut data = mock_session();
+    data.workspace = Some(Workspace {
+        current_dir: Some("/var/log/myapp/releases/current".into()),
+        project_dir: None,
+    });
+    let mut config = default_config();
+    config.metadata.insert("full".into(), "true".into());
+    config.metadata.insert("max_width".into(), "15".into());
+    config.metadata.insert("truncate".into(), "middle".into());
+    let output = registry.render("cwd", &data, &config).unwrap();
+    assert_eq!(output.text, "/var/lo…current");
+    assert_eq!(output.display_width, 15);
+}
+
+#[test]
+fn cwd_falls_back_to_process_dir() {
+    let registry = WidgetRegistry::new();
+    let data = empty_session();
     let config = default_config();
     let output = registry.render("cwd", &data, &config).unwrap();
-    assert!(!output.visible);
+    let here = std::env::current_dir().unwrap();
+    assert!(output.visible);
+    assert_eq!(
+        output.text,
+        here.file_name().unwrap().to_string_lossy().as_ref()
+    );
+}
+
+#[test]
+fn effective_dir_prefers_workspace_current_dir() {
+    let data = mock_session();
+    assert_eq!(
+        data.effective_dir(),
+        Some(std::path::PathBuf::from("/Users/test/project"))
+    );
+    let mut data = empty_session();
+    data.workspace = Some(Workspace {
+        current_dir: Some("/work/sub".into()),
+        project_dir: None,
+    });
+    data.cwd = Some("/work".into());
+    assert_eq!(
+        data.effective_dir(),
+        Some(std::path::PathBuf::from("/work/sub"))
+    );
+}
+
+#[test]
+fn effective_dir_falls_back_to_cwd() {
+    let mut data = empty_session();
+    data.workspace = Some(Workspace {
+        current_dir: Some(String::new()),
+        project_dir: None,
+    });
+    data.cwd = Some("/work".into());
+    assert_eq!(
+        data.effective_dir(),
+        Some(std::path::PathBuf::from("/work"))
+    );
+}
+
+#[test]
+fn effective_dir_falls_back_to_process_dir() {
+    let data = empty_session();
+    assert_eq!(data.effective_dir(), std::env::current_dir().ok());
 }
 
 // ─── LinesChangedWidget ──────────────────────────────────────
@@ -467,6 +707,17 @@ fn lines_changed_raw_value() {
     assert_eq!(output.text, "+156-23");
 }
 
+#[test]
+fn lines_changed_grouped() {
+    let registry = WidgetRegistry::new();
+    let mut data = mock_session();
+    data.cost.as_mut().unwrap().total_lines_added = Some(12345);
+    let mut config = default_config();
+    config.settings.group_separator = Some(" ".into());
+    let output = registry.render("lines-changed", &data, &config).unwrap();
+    assert_eq!(output.text, "+12 345 -23");
+}
+
 #[test]
 fn lines_changed_invisible_when_zero() {
     let registry = WidgetRegistry::new();
@@ -639,6 +890,210 @@ fn exceeds_tokens_invisible_when_none() {
     assert!(!output.visible);
 }
 
+// ─── CompactionWarningWidget ──────────────────────────────────
+
+#[test]
+fn compaction_warning_invisible_below_threshold() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let output = registry
+        .render("compaction-warning", &data, &default_config())
+        .unwrap();
+    assert!(!output.visible);
+}
+
+#[test]
+fn compaction_warning_fires_at_threshold() {
+    let registry = WidgetRegistry::new();
+    let mut data = mock_session();
+    data.context_window.as_mut().unwrap().used_percentage = Some(85.0);
+    let output = registry
+        .render("compaction-warning", &data, &default_config())
+        .unwrap();
+    assert!(output.visible);
+    assert_eq!(output.text, "\u{26A0}\u{FE0F} compaction soon");
+    assert!(output.priority > 90);
+}
+
+#[test]
+fn compaction_warning_custom_threshold() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session(); // 42.5% used
+    let mut config = default_config();
+    config.metadata.insert("threshold".into(), "40".into());
+    config.settings.icons = IconSet::None;
+    let output = registry
+        .render("compaction-warning", &data, &config)
+        .unwrap();
+    assert_eq!(output.text, "compaction soon");
+}
+
+// ─── TranscriptSizeWidget ─────────────────────────────────────
+
+fn transcript_session(name: &str, contents: &str) -> (SessionData, std::path::PathBuf) {
+    let path = std::env::temp_dir().join(format!(
+        "claude-status-transcript-{}-{name}.jsonl",
+        std::process::id()
+    ));
+    std::fs::write(&path, contents).unwrap();
+    let mut data = mock_session();
+    data.transcript_path = Some(path.to_string_lossy().into_owned());
+    (data, path)
+}
+
+#[test]
+fn transcript_size_counts_messages() {
+    let registry = WidgetRegistry::new();
+    let (data, path) = transcript_session("count", "{}\n{}\n{}\n");
+    let output = registry
+        .render("transcript-size", &data, &default_config())
+        .unwrap();
+    let _ = std::fs::remove_file(path);
+    assert!(output.visible);
+    assert_eq!(output.text, "3 msgs");
+}
+
+#[test]
+fn transcript_size_can_show_bytes() {
+    let registry = WidgetRegistry::new();
+    let (data, path) = transcript_session("bytes", "{}\n{}\n");
+    let mut config = default_config();
+    config.metadata.insert("size".into(), "true".into());
+    let output = registry.render("transcript-size", &data, &config).unwrap();
+    let _ = std::fs::remove_file(path);
+    assert_eq!(output.text, "6 B");
+}
+
+#[test]
+fn transcript_size_hidden_when_missing() {
+    let registry = WidgetRegistry::new();
+    let mut data = mock_session();
+    data.transcript_path = Some("/nonexistent/claude-status/transcript.jsonl".into());
+    let output = registry
+        .render("transcript-size", &data, &default_config())
+        .unwrap();
+    assert!(!output.visible);
+}
+
+// ─── IdleTimeWidget ───────────────────────────────────────────
+
+#[test]
+fn idle_time_shows_minutes_since_last_write() {
+    let registry = WidgetRegistry::new();
+    let (data, path) = transcript_session("idle", "{}\n");
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    let past = std::time::SystemTime::now() - std::time::Duration::from_secs(3 * 60 + 10);
+    file.set_modified(past).unwrap();
+    let output = registry
+        .render("idle-time", &data, &default_config())
+        .unwrap();
+    let _ = std::fs::remove_file(path);
+    assert!(output.visible);
+    assert_eq!(output.text, "idle 3m");
+}
+
+#[test]
+fn idle_time_hidden_under_threshold() {
+    let registry = WidgetRegistry::new();
+    let (data, path) = transcript_session("active", "{}\n");
+    let output = registry
+        .render("idle-time", &data, &default_config())
+        .unwrap();
+    let _ = std::fs::remove_file(path);
+    assert!(!output.visible);
+}
+
+// ─── ContextTrendWidget ───────────────────────────────────────
+
+#[test]
+fn context_trend_without_session_shows_no_arrow() {
+    let registry = WidgetRegistry::new();
+    let mut data = mock_session();
+    data.session_id = None;
+    let output = registry
+        .render("context-trend", &data, &default_config())
+        .unwrap();
+    assert!(output.visible);
+    assert_eq!(output.text, "43%");
+}
+
+#[test]
+fn context_trend_invisible_without_usage() {
+    let registry = WidgetRegistry::new();
+    let output = registry
+        .render("context-trend", &SessionData::default(), &default_config())
+        .unwrap();
+    assert!(!output.visible);
+}
+
+// ─── ContextGaugeWidget ───────────────────────────────────────
+
+#[test]
+fn context_gauge_draws_glyph_with_nerd_icons() {
+    let registry = WidgetRegistry::new();
+    let mut data = mock_session();
+    let mut config = default_config();
+    config.settings.icons = IconSet::Nerd;
+    let output = registry.render("context-gauge", &data, &config).unwrap();
+    assert_eq!(output.text, "◑");
+    assert_eq!(output.display_width, 1);
+    assert_eq!(output.color_hint.as_deref(), Some("green"));
+
+    data.context_window.as_mut().unwrap().used_percentage = Some(90.0);
+    let output = registry.render("context-gauge", &data, &config).unwrap();
+    assert_eq!(output.text, "●");
+    assert_eq!(output.color_hint.as_deref(), Some("red"));
+
+    config.metadata.insert("critical_pct".into(), "95".into());
+    let output = registry.render("context-gauge", &data, &config).unwrap();
+    assert_eq!(output.color_hint.as_deref(), Some("yellow"));
+
+    // Without overrides it matches context-percentage, including at 80%.
+    config.metadata.clear();
+    data.context_window.as_mut().unwrap().used_percentage = Some(80.0);
+    for widget in ["context-gauge", "context-percentage"] {
+        let output = registry.render(widget, &data, &config).unwrap();
+        assert_eq!(output.color_hint.as_deref(), Some("yellow"), "{widget}");
+    }
+}
+
+#[test]
+fn context_gauge_falls_back_to_percent() {
+    let registry = WidgetRegistry::new();
+    let output = registry
+        .render("context-gauge", &mock_session(), &default_config())
+        .unwrap();
+    assert_eq!(output.text, "43%");
+
+    let output = registry
+        .render("context-gauge", &SessionData::default(), &default_config())
+        .unwrap();
+    assert!(!output.visible);
+}
+
+// ─── ProjectWidget ────────────────────────────────────────────
+
+#[test]
+fn project_outside_repo_needs_fallback() {
+    let registry = WidgetRegistry::new();
+    let dir = std::env::temp_dir().join(format!("cs-project-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut data = empty_session();
+    data.cwd = Some(dir.to_string_lossy().into_owned());
+
+    let hidden = registry
+        .render("project", &data, &default_config())
+        .unwrap();
+    let mut config = default_config();
+    config.metadata.insert("fallback".into(), "true".into());
+    let shown = registry.render("project", &data, &config).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert!(!hidden.visible);
+    assert!(shown.visible);
+    assert_eq!(shown.text, format!("cs-project-{}", std::process::id()));
+}
+
 // ─── CustomTextWidget ─────────────────────────────────────────
 
 #[test]
@@ -671,6 +1126,244 @@ fn custom_text_invisible_with_empty_text() {
     assert!(!output.visible);
 }
 
+#[test]
+fn custom_text_truncates_end_by_default() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let mut config = default_config();
+    config.metadata.insert("text".into(), "Hello World".into());
+    config.metadata.insert("max_width".into(), "6".into());
+    let output = registry.render("custom-text", &data, &config).unwrap();
+    assert_eq!(output.text, "Hello…");
+}
+
+// ─── Cost history ─────────────────────────────────────────────
+
+#[test]
+fn history_widgets_hidden_when_history_disabled() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let mut config = default_config();
+    config.settings.history = false;
+    for widget in [
+        "burn-rate",
+        "block-cost",
+        "block-gauge",
+        "budget-remaining",
+        "cost-warning",
+        "last-message-cost",
+    ] {
+        let output = registry.render(widget, &data, &config).unwrap();
+        assert!(!output.visible, "{widget} should hide without history");
+    }
+}
+
+#[test]
+fn cache_savings_hidden_without_usage() {
+    let registry = WidgetRegistry::new();
+    let output = registry
+        .render("cache-savings", &empty_session(), &default_config())
+        .unwrap();
+    assert!(!output.visible);
+}
+
+#[test]
+fn block_cost_hidden_without_duration() {
+    let registry = WidgetRegistry::new();
+    let output = registry
+        .render("block-cost", &empty_session(), &default_config())
+        .unwrap();
+    assert!(!output.visible);
+}
+
+#[test]
+fn block_gauge_hidden_without_duration() {
+    let registry = WidgetRegistry::new();
+    let output = registry
+        .render("block-gauge", &empty_session(), &default_config())
+        .unwrap();
+    assert!(!output.visible);
+}
+
+// ─── Icons ────────────────────────────────────────────────────
+
+#[test]
+fn model_widget_prefixes_nerd_icon() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let mut config = default_config();
+    config.settings.icons = IconSet::Nerd;
+    let output = registry.render("model", &data, &config).unwrap();
+    assert_eq!(output.text, "\u{F544} Opus");
+    assert_eq!(output.display_width, 6);
+}
+
+#[test]
+fn default_icon_set_leaves_model_bare() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let output = registry.render("model", &data, &default_config()).unwrap();
+    assert_eq!(output.text, "Opus");
+}
+
+#[test]
+fn icon_set_falls_back_to_emoji() {
+    assert_eq!(IconSet::from_name("ascii"), IconSet::Ascii);
+    assert_eq!(IconSet::from_name("bogus"), IconSet::Emoji);
+    assert_eq!(IconSet::Emoji.get("critical"), "\u{1F534}");
+    assert_eq!(IconSet::None.get("critical"), "");
+}
+
+#[test]
+fn icon_prefix_handles_missing_glyph_and_empty_text() {
+    assert_eq!(IconSet::Ascii.prefix("warning", "low"), "! low");
+    assert_eq!(IconSet::Ascii.prefix("branch", "main"), "main");
+    assert_eq!(IconSet::Nerd.prefix("clean", ""), "\u{F00C}");
+}
+
+#[test]
+fn ascii_set_replaces_powerline_glyphs() {
+    assert_eq!(IconSet::Ascii.fallback_text("\u{E0B0}"), ">");
+    assert_eq!(IconSet::Ascii.fallback_text("\u{E0B6}"), "<");
+    assert_eq!(IconSet::Ascii.fallback_text("\u{F544} Opus"), "  Opus");
+    assert_eq!(IconSet::Nerd.fallback_text("\u{E0B0}"), "\u{E0B0}");
+}
+
+// ─── format ───────────────────────────────────────────────────
+
+#[test]
+fn group_digits_inserts_separator_every_three() {
+    assert_eq!(format::group_digits(0, ","), "0");
+    assert_eq!(format::group_digits(999, ","), "999");
+    assert_eq!(format::group_digits(1000, ","), "1,000");
+    assert_eq!(format::group_digits(1234567, "'"), "1'234'567");
+}
+
+#[test]
+fn percent_rounding_policy() {
+    let mut config = default_config();
+    assert_eq!(format::percent(99.6, &config), 100);
+    config.metadata.insert("rounding".into(), "floor".into());
+    assert_eq!(format::percent(99.6, &config), 99);
+    config.metadata.insert("rounding".into(), "ceil".into());
+    assert_eq!(format::percent(99.1, &config), 100);
+}
+
+#[test]
+fn context_percentage_honors_rounding() {
+    let registry = WidgetRegistry::new();
+    let mut config = default_config();
+    config.metadata.insert("rounding".into(), "floor".into());
+    let output = registry
+        .render("context-percentage", &mock_session(), &config)
+        .unwrap();
+    assert_eq!(output.text, "42%");
+}
+
+#[test]
+fn percent_precision_keeps_colors_on_the_unrounded_value() {
+    let registry = WidgetRegistry::new();
+    let mut data = mock_session();
+    let mut config = default_config();
+    config.metadata.insert("precision".into(), "1".into());
+    assert_eq!(format::percent_text(89.96, &config), "90.0");
+    config.metadata.insert("rounding".into(), "floor".into());
+    assert_eq!(format::percent_text(89.96, &config), "89.9");
+    config.metadata.remove("rounding");
+
+    // Just under the 80% critical line: shown as 80.0% but still warn-colored
+    data.context_window.as_mut().unwrap().used_percentage = Some(79.98);
+    let output = registry
+        .render("context-percentage", &data, &config)
+        .unwrap();
+    assert_eq!(output.text, "80.0%");
+    assert_eq!(output.color_hint.as_deref(), Some("yellow"));
+
+    data.context_window.as_mut().unwrap().used_percentage = Some(80.04);
+    let output = registry
+        .render("context-percentage", &data, &config)
+        .unwrap();
+    assert_eq!(output.text, "80.0%");
+    assert_eq!(output.color_hint.as_deref(), Some("red"));
+
+    config.metadata.remove("precision");
+    let output = registry
+        .render("context-percentage", &data, &config)
+        .unwrap();
+    assert_eq!(output.text, "80%");
+}
+
+#[test]
+fn parse_duration_accepts_unit_suffixed_spans() {
+    assert_eq!(format::parse_duration("12h"), Some(12 * 3600));
+    assert_eq!(format::parse_duration("3d"), Some(3 * 86400));
+    assert_eq!(format::parse_duration("2w"), Some(14 * 86400));
+    assert_eq!(format::parse_duration("1d12h"), Some(36 * 3600));
+    assert_eq!(format::parse_duration(" 90m "), Some(90 * 60));
+    for bad in ["", "3", "d", "3x", "3d2", "-1d", "99999999999999999999w"] {
+        assert_eq!(format::parse_duration(bad), None, "{bad:?}");
+    }
+}
+
+#[test]
+fn elapsed_and_ago_use_the_largest_units() {
+    assert_eq!(format::elapsed(59), "0m");
+    assert_eq!(format::elapsed(5 * 60), "5m");
+    assert_eq!(format::elapsed(2 * 3600 + 5 * 60), "2h5m");
+    assert_eq!(format::elapsed(3 * 86400 + 4 * 3600 + 59), "3d4h");
+    assert_eq!(format::elapsed_secs(5 * 60 + 30, " "), "5m 30s");
+    assert_eq!(format::elapsed_secs(2 * 3600 + 5 * 60 + 30, ""), "2h5m");
+    assert_eq!(format::ago(30), "just now");
+    assert_eq!(format::ago(2 * 3600), "2h0m ago");
+    assert_eq!(format::largest_unit(45), "45s");
+    assert_eq!(format::largest_unit(5 * 60 + 45), "5m");
+    assert_eq!(format::largest_unit(3 * 86400), "72h");
+}
+
+#[test]
+fn bytes_scales_units() {
+    assert_eq!(format::bytes(512), "512 B");
+    assert_eq!(format::bytes(1536), "1.5 KB");
+    assert_eq!(format::bytes(5 * 1024 * 1024), "5.0 MB");
+}
+
+#[test]
+fn sparkline_scales_to_max() {
+    assert_eq!(format::sparkline(&[0.0, 1.0, 2.0, 4.0]), "▁▃▅█");
+}
+
+#[test]
+fn sparkline_all_zero_is_flat() {
+    assert_eq!(format::sparkline(&[0.0; 5]), "▁▁▁▁▁");
+    assert_eq!(format::sparkline(&[]), "");
+}
+
+#[test]
+fn truncate_middle_even_width() {
+    // 9 columns for text: head takes the extra one
+    assert_eq!(
+        format::truncate_middle("abcdefghijklmnop", 10),
+        "abcde…mnop"
+    );
+}
+
+#[test]
+fn truncate_middle_odd_width() {
+    assert_eq!(format::truncate_middle("abcdefghijklmnop", 9), "abcd…mnop");
+}
+
+#[test]
+fn truncate_middle_leaves_short_text_alone() {
+    assert_eq!(format::truncate_middle("short", 5), "short");
+    assert_eq!(format::truncate_middle("short", 0), "");
+}
+
+#[test]
+fn truncate_middle_counts_wide_chars() {
+    // Each CJK char is two columns, so one fits on each side of the "…"
+    assert_eq!(format::truncate_middle("日本語のテキスト", 6), "日…ト");
+}
+
 // ─── SeparatorWidget ──────────────────────────────────────────
 
 #[test]
@@ -694,6 +1387,37 @@ fn separator_renders_custom_char() {
     assert_eq!(output.text, "::");
 }
 
+// ─── GroupDividerWidget ───────────────────────────────────────
+
+#[test]
+fn group_divider_renders_default_chevron() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let config = default_config();
+    let output = registry.render("group-divider", &data, &config).unwrap();
+    assert!(output.visible);
+    assert_eq!(output.text, " ❯ ");
+    assert_eq!(output.display_width, 3);
+}
+
+#[test]
+fn group_divider_joins_left_glyph_right() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let mut config = default_config();
+    config.metadata.insert("left".into(), "  ".into());
+    config.metadata.insert("glyph".into(), "──".into());
+    config.metadata.insert("right".into(), "".into());
+    let output = registry.render("group-divider", &data, &config).unwrap();
+    assert_eq!(output.text, "  ──");
+    assert_eq!(output.display_width, 4);
+
+    config.metadata.insert("left".into(), "".into());
+    config.metadata.insert("glyph".into(), "".into());
+    let output = registry.render("group-divider", &data, &config).unwrap();
+    assert!(!output.visible);
+}
+
 // ─── TerminalWidthWidget ─────────────────────────────────────
 
 #[test]
@@ -889,6 +1613,128 @@ fn model_widget_has_no_color_hint() {
     assert_eq!(output.color_hint, None);
 }
 
+#[test]
+fn model_widget_uses_tier_color() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let mut config = default_config();
+    config.metadata.insert(
+        "tier_colors".into(),
+        "opus=magenta, sonnet=cyan,haiku=green".into(),
+    );
+    let output = registry.render("model", &data, &config).unwrap();
+    assert_eq!(output.color_hint, Some("magenta".into()));
+}
+
+#[test]
+fn model_widget_unlisted_tier_has_no_color_hint() {
+    let registry = WidgetRegistry::new();
+    let data = mock_session();
+    let mut config = default_config();
+    config
+        .metadata
+        .insert("tier_colors".into(), "sonnet=cyan".into());
+    let output = registry.render("model", &data, &config).unwrap();
+    assert_eq!(output.color_hint, None);
+}
+
+// ─── SessionDataBuilder ───────────────────────────────────────
+
+#[test]
+fn builder_leaves_unset_fields_empty() {
+    let registry = WidgetRegistry::new();
+    let data = SessionDataBuilder::new().with_cost(0.42).build();
+    assert!(data.model.is_none());
+    assert!(data.context_window.is_none());
+    let config = default_config();
+    assert_eq!(
+        registry
+            .render("session-cost", &data, &config)
+            .unwrap()
+            .text,
+        "$0.42"
+    );
+    assert!(!registry.render("model", &data, &config).unwrap().visible);
+    assert!(
+        !registry
+            .render("context-percentage", &data, &config)
+            .unwrap()
+            .visible
+    );
+}
+
+#[test]
+fn builder_model_renders_name_and_id() {
+    let registry = WidgetRegistry::new();
+    let data = SessionDataBuilder::new()
+        .with_model("claude-sonnet-4-5", "Sonnet")
+        .build();
+    let mut config = default_config();
+    assert_eq!(
+        registry.render("model", &data, &config).unwrap().text,
+        "Sonnet"
+    );
+    config.raw_value = true;
+    assert_eq!(
+        registry.render("model", &data, &config).unwrap().text,
+        "claude-sonnet-4-5"
+    );
+}
+
+#[test]
+fn builder_context_pct_sets_used_and_remaining() {
+    let registry = WidgetRegistry::new();
+    let data = SessionDataBuilder::new().with_context_pct(65.0).build();
+    let config = default_config();
+    let output = registry
+        .render("context-percentage", &data, &config)
+        .unwrap();
+    assert_eq!(output.text, "65%");
+    assert_eq!(output.color_hint.as_deref(), Some("yellow"));
+    let output = registry
+        .render("context-remaining", &data, &config)
+        .unwrap();
+    assert_eq!(output.text, "35% left");
+
+    let data = SessionDataBuilder::new().with_context_pct(92.0).build();
+    let output = registry
+        .render("context-percentage", &data, &config)
+        .unwrap();
+    assert_eq!(output.color_hint.as_deref(), Some("red"));
+}
+
+#[test]
+fn builder_cost_and_duration_render_together() {
+    let registry = WidgetRegistry::new();
+    let data = SessionDataBuilder::new()
+        .with_cost(1.5)
+        .with_duration(3_723_000, 1_861_500)
+        .build();
+    let mut config = default_config();
+    assert_eq!(
+        registry
+            .render("session-cost", &data, &config)
+            .unwrap()
+            .text,
+        "$1.50"
+    );
+    assert_eq!(
+        registry
+            .render("session-duration", &data, &config)
+            .unwrap()
+            .text,
+        "1h 2m"
+    );
+    config.metadata.insert("api_ratio".into(), "true".into());
+    assert_eq!(
+        registry
+            .render("session-duration", &data, &config)
+            .unwrap()
+            .text,
+        "1h 2m (API: 50%)"
+    );
+}
+
 #[test]
 fn unknown_widget_returns_none() {
     let registry = WidgetRegistry::new();